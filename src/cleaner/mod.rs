@@ -1,26 +1,216 @@
 //! Cleaner module for executing cleanup operations
 
-use crate::rules::{CleanItem, CleanResult, RiskLevel};
+pub mod report;
+pub mod staging;
+
+use crate::config::TrashByRisk;
+use crate::rules::{CleanItem, CleanResult, CleanRule, RiskLevel, remove_path};
+use crate::scanner::ScanSummary;
+use crate::ui::glyphs::glyphs;
 use colored::*;
 use dialoguer::Confirm;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// One entry of the dry-run manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestEntry {
+    /// Path that would be deleted
+    pub path: PathBuf,
+    /// Size in bytes
+    pub size: u64,
+    /// Risk level of deleting it
+    pub risk_level: RiskLevel,
+    /// Category name
+    pub category: String,
+}
+
+/// Build a manifest of what would be cleaned, sorted by path so it can be diffed
+pub fn dry_run_manifest(items: &[CleanItem]) -> Vec<ManifestEntry> {
+    let mut manifest: Vec<ManifestEntry> = items
+        .iter()
+        .map(|item| ManifestEntry {
+            path: item.path.clone(),
+            size: item.size,
+            risk_level: item.risk_level,
+            category: item.category.to_string(),
+        })
+        .collect();
+    manifest.sort_by(|a, b| a.path.cmp(&b.path));
+    manifest
+}
+
+/// Pick the items to clean within a budget of `max_total` bytes
+///
+/// Lower-risk items come first, and within a risk level the largest; each
+/// item that still fits is taken, so the selection never exceeds the budget.
+pub fn select_within_budget(mut items: Vec<CleanItem>, max_total: u64) -> Vec<CleanItem> {
+    items.sort_by(|a, b| a.risk_level.cmp(&b.risk_level).then(b.size.cmp(&a.size)));
+
+    let mut total = 0u64;
+    items
+        .into_iter()
+        .filter(|item| {
+            let fits = total + item.size <= max_total;
+            if fits {
+                total += item.size;
+            }
+            fits
+        })
+        .collect()
+}
+
+/// Items and bytes a dry run would clean in one category
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CategoryTotal {
+    category: String,
+    count: usize,
+    bytes: u64,
+}
+
+/// Per-category totals of the `items` a dry run would clean, largest first
+///
+/// Items that were refused or whose risk group was declined are left out.
+fn dry_run_totals(items: &[CleanItem], result: &CleanResult) -> Vec<CategoryTotal> {
+    let excluded: HashSet<&PathBuf> = result
+        .failed
+        .iter()
+        .map(|(path, _)| path)
+        .chain(&result.skipped)
+        .collect();
+
+    let mut totals: HashMap<String, CategoryTotal> = HashMap::new();
+    for item in items.iter().filter(|item| !excluded.contains(&item.path)) {
+        let category = item.category.to_string();
+        let total = totals
+            .entry(category.clone())
+            .or_insert_with(|| CategoryTotal {
+                category,
+                count: 0,
+                bytes: 0,
+            });
+        total.count += 1;
+        total.bytes += item.size;
+    }
+
+    let mut totals: Vec<_> = totals.into_values().collect();
+    totals.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.category.cmp(&b.category)));
+    totals
+}
+
+/// Print the table of what a dry run would free
+fn print_dry_run_totals(totals: &[CategoryTotal]) {
+    println!(
+        "\n{}",
+        "Dry run mode - no files were deleted. Would free:".cyan()
+    );
+    for total in totals {
+        println!(
+            "  {:<24} {:>6} items  {:>10}",
+            total.category,
+            total.count,
+            bytesize::ByteSize::b(total.bytes).to_string()
+        );
+    }
+    println!("  {}", glyphs().line(46));
+    println!(
+        "  {:<24} {:>6} items  {:>10}",
+        "Total",
+        totals.iter().map(|t| t.count).sum::<usize>(),
+        bytesize::ByteSize::b(totals.iter().map(|t| t.bytes).sum())
+            .to_string()
+            .green()
+    );
+    println!("  {}", "Run with --verbose to list every item".dimmed());
+}
+
+/// What happens to cleaned items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisposalMode {
+    /// Move to the system trash
+    Trash,
+    /// Delete permanently
+    Delete,
+    /// Move into a timestamped batch directory under this staging root
+    ///
+    /// Command-driven items without a path on disk (e.g. Docker) are still
+    /// cleaned by their tool; those with one are refused, since their rule
+    /// would clean them in place rather than stage them.
+    Stage(PathBuf),
+}
+
+impl std::fmt::Display for DisposalMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisposalMode::Trash => write!(f, "trash"),
+            DisposalMode::Delete => write!(f, "delete permanently"),
+            DisposalMode::Stage(root) => write!(f, "stage in {}", root.display()),
+        }
+    }
+}
+
+/// Prompt used to confirm risky items; returns whether to proceed
+type ConfirmFn = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Removes a path, moving it to the trash when the flag is set
+type RemoveFn = Box<dyn Fn(&Path, bool) -> crate::Result<()> + Send + Sync>;
 
 /// Cleaner for executing cleanup operations
 pub struct Cleaner {
-    /// Whether to use trash instead of permanent deletion
-    use_trash: bool,
+    /// How cleaned items are disposed of
+    disposal: DisposalMode,
+    /// Risk levels that go to the trash under `DisposalMode::Trash`
+    trash_by_risk: TrashByRisk,
     /// Whether to confirm high-risk operations
     confirm_high_risk: bool,
+    /// Whether to confirm medium-risk operations
+    confirm_medium_risk: bool,
     /// Dry run mode (no actual deletion)
     dry_run: bool,
+    /// Whether rules that require root may clean via `sudo`
+    use_sudo: bool,
+    /// Suppress progress bars and per-item messages
+    quiet: bool,
+    /// List every item of a dry run instead of per-category totals
+    verbose: bool,
+    /// Paths that are never deleted, nor are any of their ancestors
+    protected_paths: Vec<PathBuf>,
+    /// Rules that clean command-driven items, normally the ones that scanned them
+    rules: Vec<Box<dyn CleanRule>>,
+    /// Prompt used for risk confirmations
+    confirm: ConfirmFn,
+    /// Removes items that no rule handles
+    remove: RemoveFn,
+    /// Set (e.g. on Ctrl-C) to stop after the item being cleaned
+    cancel: Arc<AtomicBool>,
 }
 
 impl Default for Cleaner {
     fn default() -> Self {
         Self {
-            use_trash: true,
+            disposal: DisposalMode::Trash,
+            trash_by_risk: TrashByRisk::default(),
             confirm_high_risk: true,
+            confirm_medium_risk: false,
             dry_run: false,
+            use_sudo: false,
+            quiet: false,
+            verbose: false,
+            protected_paths: crate::config::SafetyConfig::default().resolved_protected_paths(),
+            rules: Vec::new(),
+            confirm: Box::new(|prompt| {
+                Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()
+                    .unwrap_or(false)
+            }),
+            remove: Box::new(remove_path),
+            cancel: Arc::new(AtomicBool::new(false)),
         }
     }
 }
@@ -31,100 +221,324 @@ impl Cleaner {
         Self::default()
     }
 
-    /// Set whether to use trash
+    /// Set whether to use trash (shorthand for `Trash` or `Delete` disposal)
     pub fn use_trash(mut self, value: bool) -> Self {
-        self.use_trash = value;
+        self.disposal = if value {
+            DisposalMode::Trash
+        } else {
+            DisposalMode::Delete
+        };
         self
     }
 
+    /// Set how cleaned items are disposed of
+    pub fn disposal(mut self, mode: DisposalMode) -> Self {
+        self.disposal = mode;
+        self
+    }
+
+    /// Set which risk levels are trashed rather than deleted permanently
+    /// when disposing to the trash
+    pub fn trash_by_risk(mut self, matrix: TrashByRisk) -> Self {
+        self.trash_by_risk = matrix;
+        self
+    }
+
+    /// Whether this item goes to the trash
+    fn to_trash(&self, item: &CleanItem) -> bool {
+        self.disposal == DisposalMode::Trash && self.trash_by_risk.trash(item.risk_level)
+    }
+
     /// Set whether to confirm high-risk operations
     pub fn confirm_high_risk(mut self, value: bool) -> Self {
         self.confirm_high_risk = value;
         self
     }
 
+    /// Set whether to confirm medium-risk operations
+    pub fn confirm_medium_risk(mut self, value: bool) -> Self {
+        self.confirm_medium_risk = value;
+        self
+    }
+
     /// Set dry run mode
     pub fn dry_run(mut self, value: bool) -> Self {
         self.dry_run = value;
         self
     }
 
+    /// Set whether rules that require root may clean via `sudo`
+    pub fn use_sudo(mut self, value: bool) -> Self {
+        self.use_sudo = value;
+        self
+    }
+
+    /// Set quiet mode (no progress bar or per-item output)
+    pub fn quiet(mut self, value: bool) -> Self {
+        self.quiet = value;
+        self
+    }
+
+    /// Set whether a dry run lists every item rather than per-category totals
+    pub fn verbose(mut self, value: bool) -> Self {
+        self.verbose = value;
+        self
+    }
+
+    /// Set the paths that must never be deleted (see `[safety] protected_paths`)
+    pub fn protected_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.protected_paths = paths;
+        self
+    }
+
+    /// Set the rules that clean items naming a handler
+    ///
+    /// Pass the rules the scan used (see [`crate::scanner::FileScanner::into_rules`])
+    /// so they carry the same configuration as when the items were found.
+    pub fn rules(mut self, rules: Vec<Box<dyn CleanRule>>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Replace the interactive prompt used for risk confirmations
+    pub fn with_confirm(mut self, confirm: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        self.confirm = Box::new(confirm);
+        self
+    }
+
+    /// Replace how items are trashed or deleted, e.g. to simulate failures
+    pub fn with_remover(
+        mut self,
+        remove: impl Fn(&Path, bool) -> crate::Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.remove = Box::new(remove);
+        self
+    }
+
+    /// Stop cleaning once `flag` is set, leaving the remaining items alone
+    pub fn cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = flag;
+        self
+    }
+
+    /// Whether cancellation was requested
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
     /// Clean the specified items
-    pub fn clean(&self, items: &[CleanItem]) -> anyhow::Result<CleanResult> {
+    pub fn clean(&self, items: &[CleanItem]) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
-        // Filter out items that need confirmation
-        let (high_risk, normal): (Vec<_>, Vec<_>) = items
-            .iter()
-            .partition(|item| item.risk_level == RiskLevel::High);
+        // All groups of one clean share a single staging batch
+        let batch = match &self.disposal {
+            DisposalMode::Stage(root) => Some(staging::new_batch_dir(root)),
+            _ => None,
+        };
 
-        // Handle high-risk items first
-        if !high_risk.is_empty() && self.confirm_high_risk {
-            println!("\n{}", "⚠️  High-risk items detected:".yellow().bold());
-            for item in &high_risk {
-                println!(
-                    "  {} {} ({})",
-                    "•".red(),
-                    item.path.display(),
-                    bytesize::ByteSize::b(item.size)
-                );
+        // Partition by risk so each level can be confirmed separately
+        let mut low_risk = Vec::new();
+        let mut medium_risk = Vec::new();
+        let mut high_risk = Vec::new();
+        for item in items {
+            match item.risk_level {
+                RiskLevel::Low => low_risk.push(item),
+                RiskLevel::Medium => medium_risk.push(item),
+                RiskLevel::High => high_risk.push(item),
+            }
+        }
+
+        // Handle riskier items first
+        for (level, group, needs_confirm) in [
+            (RiskLevel::High, high_risk, self.confirm_high_risk),
+            (RiskLevel::Medium, medium_risk, self.confirm_medium_risk),
+            (RiskLevel::Low, low_risk, false),
+        ] {
+            if group.is_empty() {
+                continue;
             }
 
-            let confirm = Confirm::new()
-                .with_prompt("Do you want to clean these high-risk items?")
-                .default(false)
-                .interact()
-                .unwrap_or(false);
-
-            if confirm {
-                let high_risk_result = self.clean_items(&high_risk)?;
-                result.merge(high_risk_result);
-            } else {
-                println!("{}", "Skipping high-risk items.".yellow());
+            if self.is_cancelled() {
+                result.cancelled = true;
+                result
+                    .skipped
+                    .extend(group.iter().map(|item| item.path.clone()));
+                continue;
             }
-        } else if !high_risk.is_empty() {
-            let high_risk_result = self.clean_items(&high_risk)?;
-            result.merge(high_risk_result);
+
+            if needs_confirm && !self.confirm_group(level, &group) {
+                if !self.quiet {
+                    println!(
+                        "{}",
+                        format!("Skipping {}-risk items.", level.to_string().to_lowercase())
+                            .yellow()
+                    );
+                }
+                result
+                    .skipped
+                    .extend(group.iter().map(|item| item.path.clone()));
+                continue;
+            }
+
+            let group_result = self.clean_items(&group, batch.as_deref())?;
+            result.merge(group_result);
         }
 
-        // Clean normal items
-        if !normal.is_empty() {
-            let normal_result = self.clean_items(&normal)?;
-            result.merge(normal_result);
+        if self.dry_run && !self.quiet && !self.verbose {
+            print_dry_run_totals(&dry_run_totals(items, &result));
         }
 
         Ok(result)
     }
 
-    /// Clean a list of items with progress bar
-    fn clean_items(&self, items: &[&CleanItem]) -> anyhow::Result<CleanResult> {
+    /// List a risk group and ask whether to clean it
+    fn confirm_group(&self, level: RiskLevel, items: &[&CleanItem]) -> bool {
+        let label = level.to_string().to_lowercase();
+        if self.quiet {
+            return (self.confirm)(&format!("Do you want to clean these {}-risk items?", label));
+        }
+        println!(
+            "\n{}",
+            format!("{}  {}-risk items detected:", glyphs().warning, level)
+                .yellow()
+                .bold()
+        );
+        for item in items {
+            let bullet = match level {
+                RiskLevel::High => glyphs().bullet.red(),
+                _ => glyphs().bullet.yellow(),
+            };
+            println!(
+                "  {} {} ({})",
+                bullet,
+                item.path.display(),
+                bytesize::ByteSize::b(item.size)
+            );
+        }
+
+        (self.confirm)(&format!("Do you want to clean these {}-risk items?", label))
+    }
+
+    /// Why cleaning `item` is refused: its path is a protected path, the
+    /// current working directory, or an ancestor of either, or it cannot be
+    /// staged
+    fn refusal_reason(&self, item: &CleanItem) -> Option<String> {
+        let path = item.path.as_path();
+        let canonical = path.canonicalize().ok();
+        let covers = |guarded: &Path| {
+            guarded.starts_with(path)
+                || canonical.as_ref().is_some_and(|canonical| {
+                    guarded.starts_with(canonical)
+                        || guarded
+                            .canonicalize()
+                            .is_ok_and(|guarded| guarded.starts_with(canonical))
+                })
+        };
+
+        if let Some(protected) = self.protected_paths.iter().find(|p| covers(p)) {
+            return Some(format!(
+                "Refusing to delete protected path (covers {})",
+                protected.display()
+            ));
+        }
+
+        // Staging an ancestor of the staging root would move it into itself
+        if let DisposalMode::Stage(root) = &self.disposal
+            && covers(root)
+        {
+            return Some(format!(
+                "Refusing to stage a path containing the staging directory ({})",
+                root.display()
+            ));
+        }
+
+        // Its rule would delete or prune it in place, which staging promises not to do
+        if let (DisposalMode::Stage(_), Some(handler)) = (&self.disposal, &item.handler)
+            && path.symlink_metadata().is_ok()
+        {
+            return Some(format!(
+                "Refusing to clean with --stage: {} cleans this path in place",
+                handler
+            ));
+        }
+
+        // A misconfigured rule could return a parent of the directory we run in
+        if let Ok(cwd) = std::env::current_dir()
+            && covers(&cwd)
+        {
+            return Some(format!(
+                "Refusing to delete the current working directory or its parent ({})",
+                cwd.display()
+            ));
+        }
+
+        None
+    }
+
+    /// Clean a list of items with progress bar, staging them into `batch`
+    /// when set
+    fn clean_items(
+        &self,
+        items: &[&CleanItem],
+        batch: Option<&Path>,
+    ) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
+        // Protected paths are rejected before anything else sees them
+        let mut allowed = Vec::with_capacity(items.len());
+        for item in items {
+            match self.refusal_reason(item) {
+                Some(reason) => result.failed.push((item.path.clone(), reason)),
+                None => allowed.push(*item),
+            }
+        }
+        let items = allowed.as_slice();
+
         if self.dry_run {
-            println!("\n{}", "Dry run mode - no files will be deleted:".cyan());
+            // Without --verbose, clean() prints per-category totals instead
+            let itemized = self.verbose && !self.quiet;
+            if itemized {
+                println!("\n{}", "Dry run mode - no files will be deleted:".cyan());
+            }
             for item in items {
-                println!(
-                    "  {} {} ({})",
-                    "Would delete:".cyan(),
-                    item.path.display(),
-                    bytesize::ByteSize::b(item.size)
-                );
+                if itemized {
+                    println!(
+                        "  {} {} ({})",
+                        "Would delete:".cyan(),
+                        item.path.display(),
+                        bytesize::ByteSize::b(item.size)
+                    );
+                }
                 result.bytes_freed += item.size;
                 result.cleaned_count += 1;
             }
             return Ok(result);
         }
 
-        let pb = ProgressBar::new(items.len() as u64);
+        let pb = if self.quiet {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(items.len() as u64)
+        };
         pb.set_style(
             ProgressStyle::default_bar()
                 .template(
                     "{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}",
                 )
-                .unwrap_or_else(|_| ProgressStyle::default_bar()),
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars(glyphs().progress_chars)
+                .tick_chars(glyphs().tick_chars),
         );
 
-        for item in items {
+        for (i, item) in items.iter().enumerate() {
+            if self.is_cancelled() {
+                result.cancelled = true;
+                result
+                    .skipped
+                    .extend(items[i..].iter().map(|item| item.path.clone()));
+                break;
+            }
+
             pb.set_message(format!(
                 "Cleaning: {}",
                 item.path
@@ -133,12 +547,32 @@ impl Cleaner {
                     .unwrap_or_default()
             ));
 
-            let clean_result = if self.use_trash {
-                trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-            } else if item.path.is_dir() {
-                std::fs::remove_dir_all(&item.path)
-            } else {
-                std::fs::remove_file(&item.path)
+            // Command-driven items are cleaned by the rule that produced them
+            if let Some(handler) = &item.handler {
+                match self.rules.iter().find(|rule| rule.id() == handler) {
+                    Some(rule) => {
+                        let single = std::slice::from_ref(*item);
+                        let rule_result = if self.use_sudo && rule.requires_root() {
+                            rule.clean_elevated(single)
+                        } else {
+                            rule.clean(single, self.to_trash(item))
+                        };
+                        match rule_result {
+                            Ok(rule_result) => result.merge(rule_result),
+                            Err(e) => result.failed.push((item.path.clone(), e.to_string())),
+                        }
+                    }
+                    None => result
+                        .failed
+                        .push((item.path.clone(), format!("Unknown rule: {}", handler))),
+                }
+                pb.inc(1);
+                continue;
+            }
+
+            let clean_result = match batch {
+                Some(batch) => staging::stage_path(&item.path, batch).map(|_| ()),
+                None => self.trash_or_delete(item, &mut result),
             };
 
             match clean_result {
@@ -146,6 +580,11 @@ impl Cleaner {
                     result.cleaned_count += 1;
                     result.bytes_freed += item.size;
                 }
+                // Removed by someone else since the scan; nothing was freed
+                Err(e) if e.is_not_found() => {
+                    tracing::info!("{} is already gone", item.path.display());
+                    result.already_gone.push(item.path.clone());
+                }
                 Err(e) => {
                     result.failed.push((item.path.clone(), e.to_string()));
                 }
@@ -154,31 +593,51 @@ impl Cleaner {
             pb.inc(1);
         }
 
-        pb.finish_with_message("Clean complete");
+        if result.cancelled {
+            pb.abandon_with_message("Cancelled");
+        } else {
+            pb.finish_with_message("Clean complete");
+        }
         Ok(result)
     }
 
+    /// Remove a single item, deleting it permanently if the trash lives on
+    /// another file system
+    fn trash_or_delete(&self, item: &CleanItem, result: &mut CleanResult) -> crate::Result<()> {
+        let to_trash = self.to_trash(item);
+        match (self.remove)(&item.path, to_trash) {
+            Err(e) if to_trash && e.is_cross_device() => {
+                (self.remove)(&item.path, false)?;
+                let warning = "Trash is on another file system; deleted permanently";
+                tracing::warn!("{}: {}", item.path.display(), warning);
+                result
+                    .warnings
+                    .push((item.path.clone(), warning.to_string()));
+                Ok(())
+            }
+            Ok(()) if to_trash => {
+                result.trashed.push(item.path.clone());
+                Ok(())
+            }
+            other => other,
+        }
+    }
+
     /// Preview what would be cleaned
+    ///
+    /// Categories and items are listed in [`ScanSummary`] order, largest first.
     pub fn preview(&self, items: &[CleanItem]) {
-        use std::collections::HashMap;
+        let summary = ScanSummary::from_items(items.to_vec());
+        let total_size = summary.total_size;
 
-        let mut by_category: HashMap<String, Vec<&CleanItem>> = HashMap::new();
-        let mut total_size = 0u64;
+        println!("\n{}", format!("{} Scan Results:", glyphs().stats).bold());
+        println!("{}", glyphs().double_line(60));
 
-        for item in items {
-            total_size += item.size;
-            let category_name = item.category.to_string();
-            by_category.entry(category_name).or_default().push(item);
-        }
-
-        println!("\n{}", "📊 Scan Results:".bold());
-        println!("{}", "═".repeat(60));
-
-        for (category, cat_items) in &by_category {
+        for (category, cat_items) in &summary.by_category {
             let cat_size: u64 = cat_items.iter().map(|i| i.size).sum();
             println!(
                 "\n{} {} ({} items, {})",
-                "▸".cyan(),
+                glyphs().arrow.cyan(),
                 category.bold(),
                 cat_items.len(),
                 bytesize::ByteSize::b(cat_size).to_string().green()
@@ -193,9 +652,9 @@ impl Cleaner {
                 println!(
                     "    {} {} ({})",
                     match item.risk_level {
-                        RiskLevel::Low => "●".green(),
-                        RiskLevel::Medium => "●".yellow(),
-                        RiskLevel::High => "●".red(),
+                        RiskLevel::Low => glyphs().dot.green(),
+                        RiskLevel::Medium => glyphs().dot.yellow(),
+                        RiskLevel::High => glyphs().dot.red(),
                     },
                     item.path.display(),
                     bytesize::ByteSize::b(item.size)
@@ -207,7 +666,7 @@ impl Cleaner {
             }
         }
 
-        println!("\n{}", "═".repeat(60));
+        println!("\n{}", glyphs().double_line(60));
         println!(
             "{} {} items, {}",
             "Total:".bold(),
@@ -216,3 +675,505 @@ impl Cleaner {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Category;
+    use crate::test_support::env_read;
+
+    fn item(name: &str, risk_level: RiskLevel) -> CleanItem {
+        CleanItem::new(
+            PathBuf::from(format!("/nonexistent/{}", name)),
+            10,
+            name,
+            risk_level,
+            Category::Other("test".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_declined_medium_risk_items_are_skipped() {
+        let _env = env_read();
+        let items = vec![
+            item("low", RiskLevel::Low),
+            item("medium", RiskLevel::Medium),
+        ];
+        let cleaner = Cleaner::new()
+            .dry_run(true)
+            .confirm_medium_risk(true)
+            .with_confirm(|_| false);
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(result.bytes_freed, 10);
+        assert_eq!(result.skipped, [items[1].path.clone()]);
+    }
+
+    #[test]
+    fn test_low_risk_items_are_never_prompted_for() {
+        let _env = env_read();
+        let items = vec![
+            item("low", RiskLevel::Low),
+            item("medium", RiskLevel::Medium),
+            item("high", RiskLevel::High),
+        ];
+        let prompts = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = prompts.clone();
+        // How `clean --yes-low` configures the cleaner
+        let cleaner = Cleaner::new()
+            .dry_run(true)
+            .quiet(true)
+            .confirm_high_risk(true)
+            .confirm_medium_risk(true)
+            .with_confirm(move |prompt| {
+                recorded.lock().unwrap().push(prompt.to_string());
+                prompt.contains("medium")
+            });
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert_eq!(result.cleaned_count, 2);
+        let prompts = prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts.iter().all(|p| !p.contains("low-risk")));
+    }
+
+    #[test]
+    fn test_dry_run_manifest_is_sorted() {
+        let items = vec![item("b", RiskLevel::High), item("a", RiskLevel::Low)];
+
+        let json = serde_json::to_string(&dry_run_manifest(&items)).unwrap();
+
+        assert_eq!(
+            json,
+            r#"[{"path":"/nonexistent/a","size":10,"risk_level":"Low","category":"test"},{"path":"/nonexistent/b","size":10,"risk_level":"High","category":"test"}]"#
+        );
+    }
+
+    #[test]
+    fn test_refuses_to_delete_home() {
+        let home = tempfile::tempdir().unwrap();
+        crate::test_support::with_env(&[("HOME", Some(home.path()))], || {
+            let mut target = item("home", RiskLevel::Low);
+            target.path = crate::paths::home_dir().unwrap();
+            let cleaner = Cleaner::new().use_trash(false);
+
+            let result = cleaner.clean(&[target]).unwrap();
+
+            assert_eq!(result.cleaned_count, 0);
+            assert_eq!(result.failed.len(), 1);
+            assert!(result.failed[0].1.contains("protected"));
+            assert!(home.path().exists());
+        });
+    }
+
+    #[test]
+    fn test_refuses_ancestor_of_protected_path() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let protected = dir.path().join("keep/me");
+        std::fs::create_dir_all(&protected).unwrap();
+        let mut target = item("parent", RiskLevel::Low);
+        target.path = dir.path().join("keep");
+        let mut sibling = item("sibling", RiskLevel::Low);
+        sibling.path = dir.path().join("other");
+        let cleaner = Cleaner::new()
+            .dry_run(true)
+            .protected_paths(vec![protected.clone()]);
+
+        let result = cleaner.clean(&[target, sibling]).unwrap();
+
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, dir.path().join("keep"));
+        assert!(protected.exists());
+    }
+
+    #[test]
+    fn test_refuses_to_delete_current_dir_and_ancestors() {
+        let dir = tempfile::tempdir().unwrap();
+        let cwd = dir.path().join("project/sub");
+        std::fs::create_dir_all(&cwd).unwrap();
+        std::fs::create_dir_all(dir.path().join("project/sub/target")).unwrap();
+
+        crate::test_support::with_current_dir(&cwd, || {
+            let mut parent = item("parent", RiskLevel::Low);
+            parent.path = dir.path().join("project");
+            let mut current = item("current", RiskLevel::Low);
+            current.path = cwd.clone();
+            let mut child = item("child", RiskLevel::Low);
+            child.path = cwd.join("target");
+            let cleaner = Cleaner::new().use_trash(false).protected_paths(Vec::new());
+
+            let result = cleaner.clean(&[parent, current, child]).unwrap();
+
+            assert_eq!(result.cleaned_count, 1);
+            assert_eq!(result.failed.len(), 2);
+            assert!(
+                result
+                    .failed
+                    .iter()
+                    .all(|(_, reason)| reason.contains("current working directory"))
+            );
+            assert!(cwd.exists());
+            assert!(!cwd.join("target").exists());
+        });
+    }
+
+    #[test]
+    fn test_medium_risk_not_prompted_when_disabled() {
+        let _env = env_read();
+        let items = vec![item("medium", RiskLevel::Medium)];
+        let cleaner = Cleaner::new()
+            .dry_run(true)
+            .with_confirm(|_| panic!("should not prompt"));
+
+        assert_eq!(cleaner.clean(&items).unwrap().cleaned_count, 1);
+    }
+
+    #[test]
+    fn test_stage_moves_items_into_one_batch() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("staging");
+        let mut items = Vec::new();
+        for (name, risk_level) in [("low", RiskLevel::Low), ("medium", RiskLevel::Medium)] {
+            let mut cache = item(name, risk_level);
+            cache.path = dir.path().join(name);
+            std::fs::write(&cache.path, b"cache").unwrap();
+            items.push(cache);
+        }
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .disposal(DisposalMode::Stage(root.clone()));
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert_eq!(result.cleaned_count, 2);
+        let batches = staging::list_batches(&root).unwrap();
+        assert_eq!(batches.len(), 1);
+        for cache in &items {
+            assert!(!cache.path.exists());
+            assert!(staging::staged_path(&batches[0].path, &cache.path).exists());
+        }
+    }
+
+    #[test]
+    fn test_stage_refuses_handler_items_on_disk() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("staging");
+        let mut cache = item("cache", RiskLevel::Low).with_handler("sized_handler");
+        cache.path = dir.path().join("cache");
+        std::fs::create_dir(&cache.path).unwrap();
+        // Command-driven items without a path on disk still go to their rule
+        let remote = item("remote", RiskLevel::Low).with_handler("sized_handler");
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .disposal(DisposalMode::Stage(root.clone()))
+            .rules(vec![Box::new(SizedHandler(42))]);
+
+        let result = cleaner.clean(&[cache.clone(), remote]).unwrap();
+
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(result.bytes_freed, 42);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, cache.path);
+        assert!(result.failed[0].1.contains("--stage"));
+        assert!(cache.path.exists());
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_cross_device_trash_falls_back_to_delete() {
+        let _env = env_read();
+        let items = vec![item("cache", RiskLevel::Low), item("other", RiskLevel::Low)];
+        let deleted = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = deleted.clone();
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .with_remover(move |path, to_trash| {
+                if to_trash {
+                    let message = if path.ends_with("cache") {
+                        "Invalid cross-device link (os error 18)"
+                    } else {
+                        "trash is full"
+                    };
+                    return Err(crate::Error::trash(path, message));
+                }
+                log.lock().unwrap().push(path.to_path_buf());
+                Ok(())
+            });
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(*deleted.lock().unwrap(), [items[0].path.clone()]);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].1.contains("deleted permanently"));
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, items[1].path);
+        assert!(result.trashed.is_empty());
+    }
+
+    #[test]
+    fn test_trash_by_risk_deletes_low_risk_permanently() {
+        let _env = env_read();
+        let items = vec![item("cache", RiskLevel::Low), item("docs", RiskLevel::High)];
+        let removed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = removed.clone();
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .with_confirm(|_| true)
+            .trash_by_risk(TrashByRisk {
+                low: false,
+                medium: true,
+                high: true,
+            })
+            .with_remover(move |path, to_trash| {
+                log.lock().unwrap().push((path.to_path_buf(), to_trash));
+                Ok(())
+            });
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert_eq!(result.cleaned_count, 2);
+        let mut removed = removed.lock().unwrap().clone();
+        removed.sort();
+        assert_eq!(
+            removed,
+            [
+                (items[0].path.clone(), false),
+                (items[1].path.clone(), true)
+            ]
+        );
+        assert_eq!(result.trashed, [items[1].path.clone()]);
+
+        // --permanent overrides the matrix
+        let removed = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = removed.clone();
+        Cleaner::new()
+            .quiet(true)
+            .with_confirm(|_| true)
+            .use_trash(false)
+            .with_remover(move |_, to_trash| {
+                log.lock().unwrap().push(to_trash);
+                Ok(())
+            })
+            .clean(&items)
+            .unwrap();
+        assert_eq!(*removed.lock().unwrap(), [false, false]);
+    }
+
+    #[test]
+    fn test_vanished_items_are_not_failures() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let mut gone = item("gone", RiskLevel::Low);
+        gone.path = dir.path().join("gone");
+        let mut present = item("present", RiskLevel::Low);
+        present.path = dir.path().join("present");
+        std::fs::create_dir(&present.path).unwrap();
+
+        let result = Cleaner::new()
+            .quiet(true)
+            .use_trash(false)
+            .clean(&[gone.clone(), present.clone()])
+            .unwrap();
+
+        assert!(result.failed.is_empty());
+        assert_eq!(result.already_gone, [gone.path.clone()]);
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(result.bytes_freed, present.size);
+        assert!(!present.path.exists());
+
+        // Trashing a vanished path is reported the same way
+        let error = remove_path(&gone.path, true).unwrap_err();
+        assert!(error.is_not_found(), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dangling_symlink_is_removed_itself() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let mut link = item("link", RiskLevel::Low);
+        link.path = dir.path().join("link");
+        std::os::unix::fs::symlink(dir.path().join("missing-target"), &link.path).unwrap();
+
+        // Not trashed, so the test never touches the real trash
+        let result = Cleaner::new().quiet(true).clean(&[link.clone()]).unwrap();
+
+        assert!(result.failed.is_empty(), "{:?}", result.failed);
+        assert_eq!(result.cleaned_count, 1);
+        assert!(link.path.symlink_metadata().is_err());
+    }
+
+    #[test]
+    fn test_budget_selects_largest_low_risk_items_first() {
+        let sized = |name: &str, size: u64, risk_level: RiskLevel| {
+            let mut item = item(name, risk_level);
+            item.size = size;
+            item
+        };
+        let items = vec![
+            sized("small", 1, RiskLevel::Low),
+            sized("huge", 20, RiskLevel::Low),
+            sized("large", 6, RiskLevel::Low),
+            sized("medium", 5, RiskLevel::Low),
+            sized("risky", 9, RiskLevel::Medium),
+            sized("mid", 3, RiskLevel::Low),
+        ];
+
+        let names = |selected: Vec<CleanItem>| -> Vec<String> {
+            selected.into_iter().map(|i| i.description).collect()
+        };
+        // 20 never fits, 6 + 3 + 1 fill the budget exactly and nothing follows
+        assert_eq!(
+            names(select_within_budget(items.clone(), 10)),
+            ["large", "mid", "small"]
+        );
+        // Medium risk only once every low-risk item fits
+        assert_eq!(
+            names(select_within_budget(items.clone(), 44)),
+            ["huge", "large", "medium", "mid", "small", "risky"]
+        );
+        assert!(select_within_budget(items, 0).is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_totals_skip_declined_items() {
+        let _env = env_read();
+        let mut items = vec![
+            item("a", RiskLevel::Low),
+            item("b", RiskLevel::Low),
+            item("declined", RiskLevel::Medium),
+        ];
+        items[1].size = 30;
+        items.push(CleanItem::new(
+            PathBuf::from("/nonexistent/rust"),
+            5,
+            "rust",
+            RiskLevel::Low,
+            Category::Rust,
+        ));
+        let cleaner = Cleaner::new()
+            .dry_run(true)
+            .quiet(true)
+            .confirm_medium_risk(true)
+            .with_confirm(|_| false);
+
+        let result = cleaner.clean(&items).unwrap();
+        let totals = dry_run_totals(&items, &result);
+
+        assert_eq!(
+            totals,
+            [
+                CategoryTotal {
+                    category: "test".to_string(),
+                    count: 2,
+                    bytes: 40
+                },
+                CategoryTotal {
+                    category: "Rust".to_string(),
+                    count: 1,
+                    bytes: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancel_stops_after_current_item() {
+        let _env = env_read();
+        let items = vec![
+            item("medium", RiskLevel::Medium),
+            item("first", RiskLevel::Low),
+            item("second", RiskLevel::Low),
+        ];
+        let cancel = Arc::new(AtomicBool::new(false));
+        let interrupt = cancel.clone();
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .confirm_high_risk(false)
+            .cancel_flag(cancel)
+            .with_remover(move |path, _| {
+                // Ctrl-C arrives while the first low-risk item is removed
+                if path.ends_with("first") {
+                    interrupt.store(true, Ordering::Relaxed);
+                }
+                Ok(())
+            });
+
+        let result = cleaner.clean(&items).unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.cleaned_count, 2);
+        assert_eq!(result.bytes_freed, 20);
+        assert_eq!(result.skipped, [items[2].path.clone()]);
+    }
+
+    /// Command-driven rule whose clean reports the size it was configured with
+    struct SizedHandler(u64);
+
+    impl CleanRule for SizedHandler {
+        fn id(&self) -> &str {
+            "sized_handler"
+        }
+
+        fn name(&self) -> &str {
+            "Sized Handler"
+        }
+
+        fn category(&self) -> Category {
+            Category::Other("test".to_string())
+        }
+
+        fn risk_level(&self) -> RiskLevel {
+            RiskLevel::Low
+        }
+
+        fn description(&self) -> &str {
+            "test handler"
+        }
+
+        fn is_applicable(&self) -> bool {
+            true
+        }
+
+        fn scan_paths(&self) -> Vec<PathBuf> {
+            Vec::new()
+        }
+
+        fn scan(&self, _ctx: &crate::rules::ScanContext) -> crate::Result<Vec<CleanItem>> {
+            Ok(Vec::new())
+        }
+
+        fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
+            Ok(CleanResult {
+                cleaned_count: items.len(),
+                bytes_freed: self.0,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_handler_items_use_the_given_rules() {
+        let _env = env_read();
+        let items = vec![item("handled", RiskLevel::Low).with_handler("sized_handler")];
+
+        let result = Cleaner::new().quiet(true).clean(&items).unwrap();
+        assert_eq!(result.cleaned_count, 0);
+        assert_eq!(result.failed.len(), 1);
+
+        let cleaner = Cleaner::new()
+            .quiet(true)
+            .rules(vec![Box::new(SizedHandler(42))]);
+        let result = cleaner.clean(&items).unwrap();
+        assert_eq!(result.cleaned_count, 1);
+        assert_eq!(result.bytes_freed, 42);
+    }
+}
@@ -0,0 +1,249 @@
+//! Post-clean audit reports (`clean --report`)
+//!
+//! The journal keeps totals and the cleaned paths for `history` and `verify`;
+//! a report also lists failed, skipped and vanished items with what happened
+//! to each, and is meant to be read by people.
+
+use super::DisposalMode;
+use crate::rules::{CleanItem, CleanResult, RiskLevel};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// What happened to a single item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Removed as requested (or would be, in a dry run)
+    Cleaned,
+    /// Removed, but not the way it was asked to be
+    CleanedWithWarning,
+    /// Could not be removed
+    Failed,
+    /// Left alone, e.g. because its risk group was declined
+    Skipped,
+    /// Had disappeared before it was cleaned
+    AlreadyGone,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Cleaned => write!(f, "cleaned"),
+            Outcome::CleanedWithWarning => write!(f, "cleaned (warning)"),
+            Outcome::Failed => write!(f, "failed"),
+            Outcome::Skipped => write!(f, "skipped"),
+            Outcome::AlreadyGone => write!(f, "already gone"),
+        }
+    }
+}
+
+/// One item of an audit report
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditItem {
+    pub path: PathBuf,
+    pub size: u64,
+    pub risk_level: RiskLevel,
+    pub category: String,
+    pub outcome: Outcome,
+    /// Error or warning message, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Everything a clean did, for audit trails
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanAudit {
+    /// When the clean finished
+    pub timestamp: DateTime<Local>,
+    /// Command line arguments the clean ran with
+    pub arguments: Vec<String>,
+    pub dry_run: bool,
+    /// How items were disposed of
+    pub disposal: String,
+    pub items: Vec<AuditItem>,
+    /// Totals, failures and warnings as returned by the cleaner
+    pub result: CleanResult,
+}
+
+impl CleanAudit {
+    /// Build a report for `items` from the cleaner's `result`
+    pub fn new(
+        items: &[CleanItem],
+        result: &CleanResult,
+        dry_run: bool,
+        disposal: &DisposalMode,
+    ) -> Self {
+        let details = |list: &[(PathBuf, String)]| -> HashMap<PathBuf, String> {
+            list.iter().cloned().collect()
+        };
+        let failed = details(&result.failed);
+        let warnings = details(&result.warnings);
+
+        let items = items
+            .iter()
+            .map(|item| {
+                let (outcome, detail) = if let Some(error) = failed.get(&item.path) {
+                    (Outcome::Failed, Some(error.clone()))
+                } else if result.skipped.contains(&item.path) {
+                    (Outcome::Skipped, None)
+                } else if result.already_gone.contains(&item.path) {
+                    (Outcome::AlreadyGone, None)
+                } else if let Some(warning) = warnings.get(&item.path) {
+                    (Outcome::CleanedWithWarning, Some(warning.clone()))
+                } else {
+                    (Outcome::Cleaned, None)
+                };
+                AuditItem {
+                    path: item.path.clone(),
+                    size: item.size,
+                    risk_level: item.risk_level,
+                    category: item.category.to_string(),
+                    outcome,
+                    detail,
+                }
+            })
+            .collect();
+
+        Self {
+            timestamp: Local::now(),
+            arguments: std::env::args().skip(1).collect(),
+            dry_run,
+            disposal: disposal.to_string(),
+            items,
+            result: result.clone(),
+        }
+    }
+
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let size = |bytes: u64| bytesize::ByteSize::b(bytes).to_string();
+        // Pipes would end the table cell early
+        let cell = |s: &str| s.replace('|', "\\|");
+
+        let _ = writeln!(out, "# Clean report\n");
+        let _ = writeln!(
+            out,
+            "- **Date:** {}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S %:z")
+        );
+        let _ = writeln!(out, "- **Arguments:** `{}`", self.arguments.join(" "));
+        let _ = writeln!(
+            out,
+            "- **Disposal:** {}{}",
+            self.disposal,
+            if self.dry_run { " (dry run)" } else { "" }
+        );
+        let _ = writeln!(
+            out,
+            "- **Cleaned:** {} items, {} freed",
+            self.result.cleaned_count,
+            size(self.result.bytes_freed)
+        );
+        let _ = writeln!(out, "- **Failed:** {}", self.result.failed.len());
+        if self.result.cancelled {
+            let _ = writeln!(out, "- **Cancelled** before all items were processed");
+        }
+
+        let _ = writeln!(out, "\n| Outcome | Path | Size | Risk | Category |");
+        let _ = writeln!(out, "|---|---|---:|---|---|");
+        for item in &self.items {
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} | {} | {} |",
+                item.outcome,
+                cell(&item.path.display().to_string()),
+                size(item.size),
+                item.risk_level,
+                cell(&item.category)
+            );
+        }
+
+        let notes: Vec<_> = self
+            .items
+            .iter()
+            .filter_map(|item| Some((item, item.detail.as_ref()?)))
+            .collect();
+        if !notes.is_empty() {
+            let _ = writeln!(out, "\n## Failures and warnings\n");
+            for (item, detail) in notes {
+                let _ = writeln!(out, "- `{}`: {}", item.path.display(), detail);
+            }
+        }
+
+        out
+    }
+
+    /// Write the report to `path`, as JSON if it ends in `.json` and as
+    /// Markdown otherwise
+    pub fn write(&self, path: &Path) -> crate::Result<()> {
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let content = if is_json {
+            serde_json::to_string_pretty(self).map_err(|e| crate::Error::Other(e.to_string()))?
+        } else {
+            self.to_markdown()
+        };
+        std::fs::write(path, content).map_err(|e| crate::Error::filesystem(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Category;
+
+    #[test]
+    fn test_report_records_each_outcome() {
+        let items: Vec<CleanItem> = ["ok", "broken", "declined", "deleted"]
+            .into_iter()
+            .map(|name| {
+                CleanItem::new(
+                    PathBuf::from(format!("/tmp/{}", name)),
+                    100,
+                    name,
+                    RiskLevel::Low,
+                    Category::Other("Test".to_string()),
+                )
+            })
+            .collect();
+        let result = CleanResult {
+            cleaned_count: 2,
+            bytes_freed: 200,
+            failed: vec![(items[1].path.clone(), "permission denied".to_string())],
+            warnings: vec![(items[3].path.clone(), "deleted permanently".to_string())],
+            skipped: vec![items[2].path.clone()],
+            ..Default::default()
+        };
+
+        let report = CleanAudit::new(&items, &result, false, &DisposalMode::Trash);
+        let outcomes: Vec<_> = report.items.iter().map(|item| item.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                Outcome::Cleaned,
+                Outcome::Failed,
+                Outcome::Skipped,
+                Outcome::CleanedWithWarning
+            ]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let markdown = dir.path().join("report.md");
+        report.write(&markdown).unwrap();
+        let markdown = std::fs::read_to_string(markdown).unwrap();
+        assert!(markdown.contains("| failed | `/tmp/broken` |"));
+        assert!(markdown.contains("- `/tmp/broken`: permission denied"));
+
+        let json = dir.path().join("report.JSON");
+        report.write(&json).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(json).unwrap()).unwrap();
+        assert_eq!(json["items"][2]["outcome"], "skipped");
+        assert_eq!(json["result"]["bytes_freed"], 200);
+    }
+}
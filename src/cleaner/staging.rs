@@ -0,0 +1,212 @@
+//! Staging directory disposal
+//!
+//! Instead of being trashed or deleted, staged items are moved into a
+//! timestamped batch directory under the staging root, keeping their original
+//! absolute path below it. Batches can be inspected, moved back by hand, and
+//! later removed with `purge-staging`.
+
+use chrono::{Local, NaiveDateTime};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Format of batch directory names
+const BATCH_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A batch of items staged by one clean
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagingBatch {
+    /// Batch directory
+    pub path: PathBuf,
+    /// When the batch was created
+    pub created: NaiveDateTime,
+}
+
+/// Pick a new, not yet existing batch directory under `root`
+///
+/// Nothing is created; the directory appears with the first staged item.
+pub fn new_batch_dir(root: &Path) -> PathBuf {
+    let name = Local::now().format(BATCH_FORMAT).to_string();
+    let mut batch = root.join(&name);
+    // Two cleans within the same second get numbered batches
+    let mut n = 1;
+    while batch.exists() {
+        batch = root.join(format!("{}-{}", name, n));
+        n += 1;
+    }
+    batch
+}
+
+/// Where `path` is placed inside `batch`
+///
+/// The root and any drive prefix are dropped so `/home/me/.cache/pip` ends
+/// up at `<batch>/home/me/.cache/pip`.
+pub fn staged_path(batch: &Path, path: &Path) -> PathBuf {
+    let relative: PathBuf = path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    batch.join(relative)
+}
+
+/// Move `path` into `batch`, returning its new location
+pub fn stage_path(path: &Path, batch: &Path) -> crate::Result<PathBuf> {
+    let target = staged_path(batch, path);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| crate::Error::filesystem(parent, e))?;
+    }
+    move_path(path, &target, |from, to| std::fs::rename(from, to))
+        .map_err(|e| crate::Error::from_delete(path, e))?;
+    Ok(target)
+}
+
+/// Rename `from` to `to`, copying and then deleting when they are on
+/// different file systems
+///
+/// `rename` is injectable so the cross-device fallback can be tested.
+fn move_path(
+    from: &Path,
+    to: &Path,
+    rename: impl Fn(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    match rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_recursive(from, to) {
+                // Leave the original untouched and drop the partial copy
+                let _ = remove(to);
+                return Err(e);
+            }
+            remove(from)
+        }
+        other => other,
+    }
+}
+
+/// Copy a file, symlink, or directory tree without following symlinks
+fn copy_recursive(from: &Path, to: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(from).follow_links(false) {
+        let entry = entry.map_err(io::Error::other)?;
+        let target = to.join(entry.path().strip_prefix(from).map_err(io::Error::other)?);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::copy(from, to).map(|_| ())
+}
+
+/// Remove a file, symlink, or directory tree
+fn remove(path: &Path) -> io::Result<()> {
+    if path.is_dir() && !path.is_symlink() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Batches under `root`, oldest first
+///
+/// Only directories named like a batch are listed, so pointing
+/// `purge-staging` at the wrong directory cannot remove unrelated data.
+pub fn list_batches(root: &Path) -> crate::Result<Vec<StagingBatch>> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(crate::Error::from_read_dir(root, e)),
+    };
+
+    let mut batches: Vec<StagingBatch> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            // Strip the "-N" suffix of batches created within the same second
+            let stamp = name.get(..15)?;
+            let created = NaiveDateTime::parse_from_str(stamp, BATCH_FORMAT).ok()?;
+            let suffix = &name[15..];
+            (suffix.is_empty()
+                || suffix
+                    .strip_prefix('-')
+                    .is_some_and(|n| n.parse::<u32>().is_ok()))
+            .then(|| StagingBatch {
+                path: e.path(),
+                created,
+            })
+        })
+        .collect();
+    batches.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_path_keeps_absolute_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        std::fs::create_dir_all(cache.join("sub")).unwrap();
+        std::fs::write(cache.join("sub/blob"), b"data").unwrap();
+
+        let batch = new_batch_dir(&dir.path().join("staging"));
+        let staged = stage_path(&cache, &batch).unwrap();
+
+        assert!(!cache.exists());
+        assert_eq!(staged, staged_path(&batch, &cache));
+        assert_eq!(std::fs::read(staged.join("sub/blob")).unwrap(), b"data");
+        assert_eq!(list_batches(&dir.path().join("staging")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_cross_device_move_falls_back_to_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from");
+        std::fs::create_dir_all(from.join("nested")).unwrap();
+        std::fs::write(from.join("nested/file"), b"payload").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("nested/file", from.join("link")).unwrap();
+        let to = dir.path().join("to");
+
+        let exdev = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::CrossesDevices));
+        move_path(&from, &to, exdev).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(std::fs::read(to.join("nested/file")).unwrap(), b"payload");
+        #[cfg(unix)]
+        assert_eq!(
+            std::fs::read_link(to.join("link")).unwrap(),
+            Path::new("nested/file")
+        );
+    }
+
+    #[test]
+    fn test_list_batches_ignores_unrelated_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["20260101-120000", "20260101-120000-1", "photos", "2026"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        std::fs::write(dir.path().join("20260102-080000"), b"").unwrap();
+
+        let batches = list_batches(dir.path()).unwrap();
+        let names: Vec<_> = batches
+            .iter()
+            .map(|b| b.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["20260101-120000", "20260101-120000-1"]);
+    }
+}
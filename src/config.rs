@@ -1,7 +1,14 @@
 //! Configuration management for CleanMyMac-rs
 
+use crate::rules::RiskLevel;
+use crate::scanner::ExtensionGroup;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Profile selected for this process (see [`Config::select_profile`])
+static SELECTED_PROFILE: OnceLock<String> = OnceLock::new();
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,10 +21,47 @@ pub struct Config {
     pub categories: CategoryConfig,
     /// Heuristic detection settings
     pub heuristic: HeuristicConfig,
+    /// Project discovery settings
+    pub scan: ScanConfig,
     /// Risk confirmation settings
     pub risk: RiskConfig,
     /// Ignore settings
     pub ignore: IgnoreConfig,
+    /// Linux-specific settings
+    pub linux: LinuxConfig,
+    /// Xcode settings
+    pub xcode: XcodeConfig,
+    /// Storage analysis settings
+    pub analyze: AnalyzeConfig,
+    /// Partial cache pruning settings
+    pub prune: PruneConfig,
+    /// Minimum item size settings
+    pub thresholds: ThresholdConfig,
+    /// Deletion safety settings
+    pub safety: SafetyConfig,
+    /// Named profiles that override parts of this configuration
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// User-defined cleanup rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub custom_rules: Vec<CustomRuleConfig>,
+    /// Per-rule settings keyed by rule id (e.g. `[rules.npm_cache]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<String, RuleConfig>,
+}
+
+/// Settings for a single rule (`[rules.<id>]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleConfig {
+    /// Whether the rule runs at all
+    pub enabled: bool,
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
 }
 
 /// General configuration options
@@ -32,6 +76,14 @@ pub struct GeneralConfig {
     pub parallel_threads: usize,
     /// Whether to confirm high-risk operations
     pub confirm_high_risk: bool,
+    /// Whether to show a desktop notification after a clean
+    pub notify: bool,
+    /// Which risk levels go to the trash while `use_trash` is on; the rest
+    /// are deleted permanently
+    pub trash_by_risk: TrashByRisk,
+    /// Always run at low CPU and I/O priority with fewer threads, as with
+    /// `--low-priority`
+    pub io_nice: bool,
 }
 
 impl Default for GeneralConfig {
@@ -41,32 +93,75 @@ impl Default for GeneralConfig {
             scan_hidden: true,
             parallel_threads: 0,
             confirm_high_risk: true,
+            notify: false,
+            trash_by_risk: TrashByRisk::default(),
+            io_nice: false,
         }
     }
 }
 
-/// Category configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Whether items of each risk level are moved to the trash
+/// (`trash_by_risk = { low = false, medium = true, high = true }`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
-pub struct CategoryConfig {
-    /// Enabled cleanup categories
-    pub enabled: Vec<String>,
+pub struct TrashByRisk {
+    pub low: bool,
+    pub medium: bool,
+    pub high: bool,
 }
 
-impl Default for CategoryConfig {
+impl Default for TrashByRisk {
     fn default() -> Self {
         Self {
-            enabled: vec![
-                "system".to_string(),
-                "brew".to_string(),
-                "xcode".to_string(),
-                "npm".to_string(),
-                "pip".to_string(),
-                "cargo".to_string(),
-                "docker".to_string(),
-            ],
+            low: true,
+            medium: true,
+            high: true,
+        }
+    }
+}
+
+impl TrashByRisk {
+    /// Whether items of this risk level go to the trash
+    pub fn trash(&self, level: RiskLevel) -> bool {
+        match level {
+            RiskLevel::Low => self.low,
+            RiskLevel::Medium => self.medium,
+            RiskLevel::High => self.high,
         }
     }
+
+    /// Risk levels that are deleted permanently, lowest first
+    pub fn permanent_levels(&self) -> Vec<RiskLevel> {
+        [RiskLevel::Low, RiskLevel::Medium, RiskLevel::High]
+            .into_iter()
+            .filter(|level| !self.trash(*level))
+            .collect()
+    }
+}
+
+/// `categories.enabled` as written by `config --init` before it was used;
+/// still treated as "everything" so those files keep their behavior
+const LEGACY_ENABLED: [&str; 7] = ["system", "brew", "xcode", "npm", "pip", "cargo", "docker"];
+
+/// Category configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryConfig {
+    /// Categories or rule ids that `scan` and `clean` use without
+    /// `--categories` or `--all`; empty means all of them
+    pub enabled: Vec<String>,
+    /// Categories switched off in the TUI settings tab, skipped unless
+    /// requested explicitly with `--categories` or `--all`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disabled: Vec<String>,
+}
+
+impl CategoryConfig {
+    /// The categories to use by default, or `None` for all of them
+    pub fn default_selection(&self) -> Option<&[String]> {
+        let legacy = self.enabled.iter().map(String::as_str).eq(LEGACY_ENABLED);
+        (!self.enabled.is_empty() && !legacy).then_some(self.enabled.as_slice())
+    }
 }
 
 /// Heuristic detection configuration
@@ -79,18 +174,112 @@ pub struct HeuristicConfig {
     pub size_threshold_mb: u64,
     /// Number of days after which a file is considered stale
     pub stale_days: u32,
+    /// Directory name fragments that mark a cache (case-insensitive);
+    /// setting this replaces the built-in list
+    pub cache_patterns: Vec<String>,
+    /// Fragments added to `cache_patterns`, keeping the built-in ones
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_cache_patterns: Vec<String>,
+    /// File extensions that mark temporary files; setting this replaces the
+    /// built-in list
+    pub temp_extensions: Vec<String>,
+    /// Extensions added to `temp_extensions`, keeping the built-in ones
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_temp_extensions: Vec<String>,
 }
 
+/// Built-in fragments that mark a cache directory
+const DEFAULT_CACHE_PATTERNS: &[&str] = &[
+    "cache",
+    "Cache",
+    ".cache",
+    "caches",
+    "Caches",
+    "tmp",
+    "temp",
+    "Temp",
+    "Temporary",
+];
+
+/// Built-in extensions that mark temporary files
+const DEFAULT_TEMP_EXTENSIONS: &[&str] =
+    &["tmp", "temp", "log", "bak", "old", "orig", "swp", "swo"];
+
 impl Default for HeuristicConfig {
     fn default() -> Self {
+        let strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
         Self {
             enabled: true,
             size_threshold_mb: 100,
             stale_days: 30,
+            cache_patterns: strings(DEFAULT_CACHE_PATTERNS),
+            extra_cache_patterns: Vec::new(),
+            temp_extensions: strings(DEFAULT_TEMP_EXTENSIONS),
+            extra_temp_extensions: Vec::new(),
+        }
+    }
+}
+
+impl HeuristicConfig {
+    /// `cache_patterns` followed by `extra_cache_patterns`
+    pub fn all_cache_patterns(&self) -> Vec<String> {
+        [&self.cache_patterns, &self.extra_cache_patterns]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// `temp_extensions` followed by `extra_temp_extensions`
+    pub fn all_temp_extensions(&self) -> Vec<String> {
+        [&self.temp_extensions, &self.extra_temp_extensions]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Where and how deep rules look for per-project build directories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Directories searched for projects (`~` expands to the home directory)
+    pub project_dirs: Vec<String>,
+    /// Maximum directory depth walked below each project directory
+    pub project_max_depth: usize,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            project_dirs: [
+                "~/Projects",
+                "~/projects",
+                "~/Code",
+                "~/code",
+                "~/Development",
+                "~/dev",
+                "~/src",
+            ]
+            .iter()
+            .map(|dir| dir.to_string())
+            .collect(),
+            project_max_depth: 4,
         }
     }
 }
 
+impl ScanConfig {
+    /// Project directories with `~` expanded
+    pub fn project_search_dirs(&self) -> Vec<PathBuf> {
+        self.project_dirs
+            .iter()
+            .map(|dir| crate::util::expand_tilde(dir))
+            .collect()
+    }
+}
+
 /// Risk confirmation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -119,6 +308,225 @@ pub struct IgnoreConfig {
     pub paths: Vec<PathBuf>,
 }
 
+/// Safety settings enforced by the cleaner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SafetyConfig {
+    /// Paths that are never deleted, nor are any of their ancestors
+    /// (`~` expands to the home directory)
+    pub protected_paths: Vec<String>,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            protected_paths: vec![
+                "~".to_string(),
+                "/".to_string(),
+                "~/Documents".to_string(),
+                "~/Desktop".to_string(),
+            ],
+        }
+    }
+}
+
+impl SafetyConfig {
+    /// Protected paths with `~` expanded
+    pub fn resolved_protected_paths(&self) -> Vec<PathBuf> {
+        self.protected_paths
+            .iter()
+            .map(|p| crate::util::expand_tilde(p))
+            .collect()
+    }
+}
+
+/// Linux-specific configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinuxConfig {
+    /// Target size passed to `journalctl --vacuum-size` (e.g. "500M")
+    pub journal_vacuum_size: String,
+    /// Optional retention passed to `journalctl --vacuum-time` (e.g. "2weeks")
+    pub journal_vacuum_time: Option<String>,
+}
+
+impl Default for LinuxConfig {
+    fn default() -> Self {
+        Self {
+            journal_vacuum_size: "500M".to_string(),
+            journal_vacuum_time: None,
+        }
+    }
+}
+
+/// Xcode configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XcodeConfig {
+    /// Number of most recently built DerivedData folders to keep (0 = clean all)
+    pub keep_recent: usize,
+}
+
+/// Storage analysis configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyzeConfig {
+    /// Group for extensions the built-in mapping misses or gets wrong,
+    /// keyed by lowercase extension without the dot (e.g. `blend = "images"`)
+    pub extension_groups: BTreeMap<String, ExtensionGroup>,
+}
+
+/// Partial pruning of package caches instead of deleting them whole
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PruneConfig {
+    /// Policies keyed by rule id (e.g. "pip_cache"); display names such as
+    /// "pip Cache" are still accepted
+    pub rules: BTreeMap<String, PrunePolicy>,
+}
+
+impl PruneConfig {
+    /// Policy for a rule, if one is configured under its id or name and it
+    /// limits anything
+    pub fn policy(&self, rule_id: &str, rule_name: &str) -> Option<PrunePolicy> {
+        self.rules
+            .get(rule_id)
+            .or_else(|| self.rules.get(rule_name))
+            .filter(|p| p.keep_newest.is_some() || p.older_than_days.is_some())
+            .cloned()
+    }
+}
+
+/// Which entries of a cache to remove
+///
+/// With both limits set, only entries outside the newest `keep_newest` that
+/// are also older than `older_than_days` are removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrunePolicy {
+    /// Number of most recently used entries to keep
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_newest: Option<usize>,
+    /// Only remove entries unused for more than this many days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u32>,
+}
+
+impl std::fmt::Display for PrunePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.keep_newest, self.older_than_days) {
+            (Some(n), Some(days)) => {
+                write!(f, "keeping newest {}, removing older than {} days", n, days)
+            }
+            (Some(n), None) => write!(f, "keeping newest {}", n),
+            (None, Some(days)) => write!(f, "removing older than {} days", days),
+            (None, None) => write!(f, "keeping everything"),
+        }
+    }
+}
+
+/// Minimum size (in MB) an item must exceed to be reported
+///
+/// Rules fall back to their built-in default when neither their name nor
+/// their category has an entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThresholdConfig {
+    /// Thresholds keyed by category name (e.g. "rust", "node.js")
+    pub categories: BTreeMap<String, u64>,
+    /// Thresholds keyed by rule id (e.g. "cargo_target"); display names such
+    /// as "Rust Build Artifacts" are still accepted
+    pub rules: BTreeMap<String, u64>,
+}
+
+impl ThresholdConfig {
+    /// Configured threshold in MB for a rule, preferring the rule entry (by id,
+    /// then name) over its category
+    pub fn min_size_mb(&self, rule_id: &str, rule_name: &str, category: &str) -> Option<u64> {
+        let lookup = |map: &BTreeMap<String, u64>, key: &str| {
+            map.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| *v)
+        };
+        lookup(&self.rules, rule_id)
+            .or_else(|| lookup(&self.rules, rule_name))
+            .or_else(|| lookup(&self.categories, category))
+    }
+}
+
+/// A user-defined cleanup rule (`[[custom_rules]]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomRuleConfig {
+    /// Stable id for `[rules.<id>]` (defaults to "custom_" plus the name in snake case)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Rule name shown in listings
+    pub name: String,
+    /// Absolute paths or glob patterns to clean (`~` expands to the home
+    /// directory); relative ones are ignored
+    pub paths: Vec<String>,
+    /// Category name; unknown names become a custom category
+    pub category: String,
+    /// Risk level of cleaning the matched paths
+    pub risk: RiskLevel,
+    /// Minimum size in MB for a matched path to be reported
+    pub min_size_mb: u64,
+}
+
+impl Default for CustomRuleConfig {
+    fn default() -> Self {
+        Self {
+            id: None,
+            name: "Custom Rule".to_string(),
+            paths: Vec::new(),
+            category: "Custom".to_string(),
+            risk: RiskLevel::Medium,
+            min_size_mb: 0,
+        }
+    }
+}
+
+/// Overrides applied by a named profile (`[profiles.<name>]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// General settings to override; unset fields keep the base value
+    pub general: GeneralOverride,
+    /// Replacement category settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<CategoryConfig>,
+    /// Thresholds merged over the base thresholds
+    pub thresholds: ThresholdConfig,
+}
+
+/// Partial [`GeneralConfig`] used by profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneralOverride {
+    /// Whether to use trash instead of permanent deletion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_trash: Option<bool>,
+    /// Whether to scan hidden files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_hidden: Option<bool>,
+    /// Number of parallel threads (0 = auto)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_threads: Option<usize>,
+    /// Whether to confirm high-risk operations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm_high_risk: Option<bool>,
+    /// Whether to show a desktop notification after a clean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<bool>,
+    /// Which risk levels go to the trash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_by_risk: Option<TrashByRisk>,
+    /// Run at low CPU and I/O priority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_nice: Option<bool>,
+}
+
 impl Config {
     /// Load configuration from a TOML file
     pub fn load(path: &std::path::Path) -> crate::Result<Self> {
@@ -133,27 +541,120 @@ impl Config {
         Self::load(std::path::Path::new(path))
     }
 
+    /// Parse `content` strictly, returning the dotted paths of keys that are
+    /// not recognized and would be ignored
+    ///
+    /// Parse errors carry the line and column of the problem.
+    pub fn validate_str(content: &str) -> crate::Result<Vec<String>> {
+        let config: Config = toml::from_str(content)?;
+        let raw: toml::Value = toml::from_str(content)?;
+        // Everything that was understood survives a round trip through
+        // `Config`, except the collections in `OMITTED_WHEN_EMPTY`
+        let known =
+            toml::Value::try_from(&config).map_err(|e| crate::Error::Config(e.to_string()))?;
+
+        let mut unknown = Vec::new();
+        collect_unknown_keys(&raw, &known, "", &mut unknown);
+        Ok(unknown)
+    }
+
     /// Load configuration from the default location or create default
+    ///
+    /// The profile chosen with [`Config::select_profile`], if any, is merged
+    /// over the loaded configuration.
     pub fn load_or_default() -> Self {
+        let mut config = Self::default();
+
         // Try to load from ~/.config/cleanmymac-rs/config.toml
-        if let Some(config_dir) = dirs::config_dir() {
+        if let Some(config_dir) = crate::paths::config_dir() {
             let config_path = config_dir.join("cleanmymac-rs").join("config.toml");
             if config_path.exists() {
-                if let Ok(config) = Self::load(&config_path) {
-                    return config;
+                match Self::load(&config_path) {
+                    Ok(loaded) => config = loaded,
+                    Err(e) => tracing::warn!(
+                        "Ignoring malformed config {} and using defaults (check it with \
+                         `config --validate`): {}",
+                        config_path.display(),
+                        e
+                    ),
                 }
             }
         }
-        Self::default()
+
+        if let Some(name) = SELECTED_PROFILE.get() {
+            if let Err(e) = config.apply_profile(name) {
+                tracing::warn!("{}", e);
+            }
+        }
+
+        config
+    }
+
+    /// Select the profile merged by [`Config::load_or_default`] for the rest of the process
+    pub fn select_profile(name: &str) {
+        let _ = SELECTED_PROFILE.set(name.to_string());
+    }
+
+    /// Merge the named profile over this configuration
+    pub fn apply_profile(&mut self, name: &str) -> crate::Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            let available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            return Err(crate::Error::Config(format!(
+                "Unknown profile '{}'. Available profiles: {}",
+                name,
+                if available.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )));
+        };
+
+        let general = &profile.general;
+        if let Some(v) = general.use_trash {
+            self.general.use_trash = v;
+        }
+        if let Some(v) = general.scan_hidden {
+            self.general.scan_hidden = v;
+        }
+        if let Some(v) = general.parallel_threads {
+            self.general.parallel_threads = v;
+        }
+        if let Some(v) = general.confirm_high_risk {
+            self.general.confirm_high_risk = v;
+        }
+        if let Some(v) = general.notify {
+            self.general.notify = v;
+        }
+        if let Some(v) = general.trash_by_risk {
+            self.general.trash_by_risk = v;
+        }
+        if let Some(v) = general.io_nice {
+            self.general.io_nice = v;
+        }
+        if let Some(categories) = profile.categories {
+            self.categories = categories;
+        }
+        self.thresholds
+            .categories
+            .extend(profile.thresholds.categories);
+        self.thresholds.rules.extend(profile.thresholds.rules);
+
+        Ok(())
     }
 
     /// Get the default configuration path
     pub fn default_path() -> crate::Result<std::path::PathBuf> {
-        dirs::config_dir()
+        crate::paths::config_dir()
             .map(|p| p.join("cleanmymac-rs").join("config.toml"))
             .ok_or_else(|| crate::Error::Config("Could not determine config directory".to_string()))
     }
 
+    /// Whether the rule with `id` is enabled (rules without an entry are)
+    pub fn rule_enabled(&self, id: &str) -> bool {
+        self.rules.get(id).is_none_or(|rule| rule.enabled)
+    }
+
     /// Save configuration to a TOML file
     pub fn save(&self, path: &std::path::Path) -> crate::Result<()> {
         let content =
@@ -173,3 +674,184 @@ impl Config {
         self.save(path)
     }
 }
+
+/// Collections that `Config` leaves out when serialized empty, so an empty
+/// one in the input has no counterpart in the round trip
+///
+/// Paths inside a profile are given relative to the profile.
+const OMITTED_WHEN_EMPTY: &[&str] = &[
+    "profiles",
+    "custom_rules",
+    "rules",
+    "categories.disabled",
+    "heuristic.extra_cache_patterns",
+    "heuristic.extra_temp_extensions",
+];
+
+/// Whether `value` at the dotted `path` is an empty collection that is known
+/// but omitted when `Config` is serialized
+fn omitted_when_empty(path: &str, value: &toml::Value) -> bool {
+    let empty = match value {
+        toml::Value::Array(array) => array.is_empty(),
+        toml::Value::Table(table) => table.is_empty(),
+        _ => false,
+    };
+    let relative = match path.strip_prefix("profiles.") {
+        Some(rest) => rest.split_once('.').map_or(rest, |(_, field)| field),
+        None => path,
+    };
+    empty && OMITTED_WHEN_EMPTY.contains(&relative)
+}
+
+/// Record keys of `raw` missing from `known`, recursing into tables and arrays
+fn collect_unknown_keys(
+    raw: &toml::Value,
+    known: &toml::Value,
+    prefix: &str,
+    unknown: &mut Vec<String>,
+) {
+    match (raw, known) {
+        (toml::Value::Table(raw), toml::Value::Table(known)) => {
+            for (key, value) in raw {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match known.get(key) {
+                    Some(known) => collect_unknown_keys(value, known, &path, unknown),
+                    None if omitted_when_empty(&path, value) => {}
+                    None => unknown.push(path),
+                }
+            }
+        }
+        (toml::Value::Array(raw), toml::Value::Array(known)) => {
+            for (i, (value, known)) in raw.iter().zip(known).enumerate() {
+                collect_unknown_keys(value, known, &format!("{}[{}]", prefix, i), unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+
+    #[test]
+    fn test_validate_reports_error_location_and_unknown_keys() {
+        let _env = env_read();
+        let err = Config::validate_str("[general]\nuse_trash = maybe\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+
+        let unknown = Config::validate_str(
+            r#"
+            verbose = true
+
+            [general]
+            use_trash = false
+            use_trahs = true
+
+            [thresholds.rules]
+            "pip Cache" = 5
+
+            [[custom_rules]]
+            name = "Logs"
+            paths = ["~/logs"]
+            ctegory = "logs"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            unknown,
+            ["custom_rules[0].ctegory", "general.use_trahs", "verbose"]
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_empty_collections() {
+        let _env = env_read();
+        let unknown = Config::validate_str(
+            r#"
+            custom_rules = []
+
+            [categories]
+            disabled = []
+
+            [heuristic]
+            extra_cache_patterns = []
+
+            [rules]
+
+            [profiles.work.categories]
+            enabled = ["rust"]
+            disabled = []
+            "#,
+        )
+        .unwrap();
+        assert!(unknown.is_empty(), "{:?}", unknown);
+
+        let unknown = Config::validate_str("[heuristic]\nextra_cache_patern = []\n").unwrap();
+        assert_eq!(unknown, ["heuristic.extra_cache_patern"]);
+    }
+
+    const PROFILED: &str = r#"
+[general]
+use_trash = true
+
+[thresholds.categories]
+rust = 50
+
+[profiles.work.general]
+use_trash = false
+
+[profiles.work.categories]
+enabled = ["docker", "rust"]
+
+[profiles.work.thresholds.categories]
+rust = 10
+
+[profiles.personal]
+"#;
+
+    #[test]
+    fn test_profile_round_trip() {
+        let _env = env_read();
+        let config: Config = toml::from_str(PROFILED).unwrap();
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let reparsed: Config = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(serialized, toml::to_string_pretty(&reparsed).unwrap());
+        assert_eq!(
+            reparsed.profiles.keys().collect::<Vec<_>>(),
+            ["personal", "work"]
+        );
+        let work = &reparsed.profiles["work"];
+        assert_eq!(work.general.use_trash, Some(false));
+        assert_eq!(work.general.scan_hidden, None);
+        assert_eq!(work.thresholds.categories.get("rust"), Some(&10));
+    }
+
+    #[test]
+    fn test_apply_profile() {
+        let _env = env_read();
+        let mut config: Config = toml::from_str(PROFILED).unwrap();
+        config.apply_profile("work").unwrap();
+
+        assert!(!config.general.use_trash);
+        assert!(config.general.scan_hidden);
+        assert_eq!(config.categories.enabled, ["docker", "rust"]);
+        assert_eq!(config.thresholds.categories.get("rust"), Some(&10));
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available() {
+        let _env = env_read();
+        let mut config: Config = toml::from_str(PROFILED).unwrap();
+        let err = config.apply_profile("home").unwrap_err().to_string();
+
+        assert!(err.contains("home"));
+        assert!(err.contains("personal, work"));
+    }
+}
@@ -50,9 +50,15 @@ pub enum Error {
     Cancelled,
 
     /// Permission denied
-    #[error("Permission denied: {path}")]
+    #[error("Permission denied: {path} (try rerunning with sudo)")]
     PermissionDenied { path: PathBuf },
 
+    /// Another clean holds the lock
+    #[error(
+        "Another clean is already running ({holder}); if it crashed, rerun with --force or remove {path}"
+    )]
+    Locked { path: PathBuf, holder: String },
+
     /// Generic IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -99,4 +105,51 @@ impl Error {
     pub fn permission_denied(path: impl Into<PathBuf>) -> Self {
         Self::PermissionDenied { path: path.into() }
     }
+
+    /// Map an IO error from deleting `path` to a typed error
+    pub fn from_delete(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        if source.kind() == std::io::ErrorKind::PermissionDenied {
+            Self::permission_denied(path)
+        } else {
+            Self::delete(path, source)
+        }
+    }
+
+    /// Whether a move to the trash failed because the trash is on another
+    /// file system (`EXDEV`)
+    pub fn is_cross_device(&self) -> bool {
+        match self {
+            // The trash crate formats the underlying io::Error with Debug
+            Self::Trash { message, .. } => {
+                let message = message.to_lowercase();
+                message.contains("cross-device") || message.contains("crossesdevices")
+            }
+            Self::Delete { source, .. } | Self::FileSystem { source, .. } | Self::Io(source) => {
+                source.kind() == std::io::ErrorKind::CrossesDevices
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the operation failed because the path no longer exists
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::Delete { source, .. } | Self::FileSystem { source, .. } | Self::Io(source) => {
+                source.kind() == std::io::ErrorKind::NotFound
+            }
+            _ => false,
+        }
+    }
+
+    /// Map an IO error from reading directory `path` to a typed error
+    pub fn from_read_dir(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        if source.kind() == std::io::ErrorKind::PermissionDenied {
+            Self::permission_denied(path)
+        } else {
+            Self::ReadDir {
+                path: path.into(),
+                source,
+            }
+        }
+    }
 }
@@ -0,0 +1,343 @@
+//! Journal of completed clean operations
+//!
+//! Every clean appends one JSON line to the journal so the `history` command
+//! can report how much space has been reclaimed over time, and `verify` can
+//! check which cleaned paths have grown back since.
+
+use crate::rules::{CleanItem, CleanResult};
+use crate::scanner::fs_util::dir_size;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A single completed clean operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the clean finished
+    pub timestamp: DateTime<Utc>,
+    /// Number of items cleaned
+    pub cleaned_count: usize,
+    /// Number of items that failed
+    pub failed_count: usize,
+    /// Total bytes freed
+    pub bytes_freed: u64,
+    /// Whether every cleaned item was moved to the trash instead of deleted
+    pub to_trash: bool,
+    /// Bytes freed per category
+    #[serde(default)]
+    pub by_category: BTreeMap<String, u64>,
+    /// Paths that were cleaned (empty for entries from older versions)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<CleanedPath>,
+}
+
+/// A path removed by a clean operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CleanedPath {
+    pub path: PathBuf,
+    /// Size when it was cleaned
+    pub size: u64,
+    pub category: String,
+    /// Whether it was moved to the trash rather than deleted permanently
+    #[serde(default)]
+    pub trashed: bool,
+}
+
+/// Current state of a previously cleaned path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathRegrowth {
+    pub path: PathBuf,
+    pub category: String,
+    /// Size when it was cleaned
+    pub cleaned_size: u64,
+    /// Whether the path exists again
+    pub exists: bool,
+    /// Size now (0 if missing)
+    pub current_size: u64,
+}
+
+impl JournalEntry {
+    /// Record the outcome of cleaning `items`
+    pub fn from_clean(items: &[CleanItem], result: &CleanResult) -> Self {
+        // Failed, declined, never-reached and vanished items were not cleaned
+        let not_cleaned: HashSet<&PathBuf> = result
+            .failed
+            .iter()
+            .map(|(path, _)| path)
+            .chain(&result.skipped)
+            .chain(&result.already_gone)
+            .collect();
+        let trashed: HashSet<&PathBuf> = result.trashed.iter().collect();
+        let mut by_category = BTreeMap::new();
+        let mut paths = Vec::new();
+        for item in items
+            .iter()
+            .filter(|item| !not_cleaned.contains(&item.path))
+        {
+            *by_category.entry(item.category.to_string()).or_insert(0) += item.size;
+            paths.push(CleanedPath {
+                path: item.path.clone(),
+                size: item.size,
+                category: item.category.to_string(),
+                trashed: trashed.contains(&item.path),
+            });
+        }
+
+        Self {
+            timestamp: Utc::now(),
+            cleaned_count: result.cleaned_count,
+            failed_count: result.failed.len(),
+            bytes_freed: result.bytes_freed,
+            to_trash: !paths.is_empty() && paths.iter().all(|p| p.trashed),
+            by_category,
+            paths,
+        }
+    }
+
+    /// Check whether each cleaned path exists again and how large it is now
+    pub fn verify(&self) -> Vec<PathRegrowth> {
+        self.paths
+            .iter()
+            .map(|cleaned| {
+                let exists = cleaned.path.exists();
+                PathRegrowth {
+                    path: cleaned.path.clone(),
+                    category: cleaned.category.clone(),
+                    cleaned_size: cleaned.size,
+                    exists,
+                    current_size: if exists { dir_size(&cleaned.path) } else { 0 },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Append-only JSON-lines journal file
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Open the journal at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Default journal location (e.g. ~/.local/share/cleanmymac-rs/journal.jsonl)
+    pub fn default_path() -> crate::Result<PathBuf> {
+        crate::paths::data_local_dir()
+            .map(|p| p.join("cleanmymac-rs").join("journal.jsonl"))
+            .ok_or_else(|| crate::Error::Other("Could not determine data directory".to_string()))
+    }
+
+    /// Open the journal at its default location
+    pub fn open_default() -> crate::Result<Self> {
+        Ok(Self::new(Self::default_path()?))
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether any history has been recorded
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Append an entry
+    pub fn append(&self, entry: &JournalEntry) -> crate::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(entry).map_err(|e| crate::Error::Other(e.to_string()))?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// Read all entries, skipping lines that fail to parse
+    pub fn read_all(&self) -> crate::Result<Vec<JournalEntry>> {
+        if !self.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .filter_map(|l| match serde_json::from_str(l) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed journal entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Most recent entry that recorded its cleaned paths
+    pub fn latest_with_paths(&self) -> crate::Result<Option<JournalEntry>> {
+        Ok(self
+            .read_all()?
+            .into_iter()
+            .rev()
+            .find(|entry| !entry.paths.is_empty()))
+    }
+}
+
+/// Aggregated view of the journal
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistorySummary {
+    /// Number of clean operations
+    pub operations: usize,
+    /// Total items cleaned
+    pub items_cleaned: usize,
+    /// Total bytes freed
+    pub bytes_freed: u64,
+    /// Bytes freed per category
+    pub by_category: BTreeMap<String, u64>,
+    /// Bytes freed per month ("YYYY-MM")
+    pub by_month: BTreeMap<String, u64>,
+}
+
+impl HistorySummary {
+    /// Summarize entries, keeping only those on or after `since`
+    pub fn from_entries(entries: &[JournalEntry], since: Option<NaiveDate>) -> Self {
+        let mut summary = Self::default();
+
+        for entry in entries {
+            if since.is_some_and(|since| entry.timestamp.date_naive() < since) {
+                continue;
+            }
+
+            summary.operations += 1;
+            summary.items_cleaned += entry.cleaned_count;
+            summary.bytes_freed += entry.bytes_freed;
+            for (category, bytes) in &entry.by_category {
+                *summary.by_category.entry(category.clone()).or_insert(0) += bytes;
+            }
+            *summary
+                .by_month
+                .entry(entry.timestamp.format("%Y-%m").to_string())
+                .or_insert(0) += entry.bytes_freed;
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(year: i32, month: u32, day: u32, bytes: u64) -> JournalEntry {
+        JournalEntry {
+            timestamp: Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap(),
+            cleaned_count: 2,
+            failed_count: 0,
+            bytes_freed: bytes,
+            to_trash: true,
+            by_category: BTreeMap::from([("Rust".to_string(), bytes)]),
+            paths: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = Journal::new(dir.path().join("nested/journal.jsonl"));
+        assert!(journal.read_all().unwrap().is_empty());
+
+        let entries = [entry(2026, 1, 5, 100), entry(2026, 2, 1, 50)];
+        for e in &entries {
+            journal.append(e).unwrap();
+        }
+
+        assert_eq!(journal.read_all().unwrap(), entries);
+    }
+
+    #[test]
+    fn test_history_summary_since() {
+        let entries = [
+            entry(2026, 1, 5, 100),
+            entry(2026, 2, 1, 50),
+            entry(2026, 2, 20, 25),
+        ];
+
+        let all = HistorySummary::from_entries(&entries, None);
+        assert_eq!(all.operations, 3);
+        assert_eq!(all.bytes_freed, 175);
+        assert_eq!(all.by_month.get("2026-02"), Some(&75));
+        assert_eq!(all.by_category.get("Rust"), Some(&175));
+
+        let since = NaiveDate::from_ymd_opt(2026, 2, 1);
+        let recent = HistorySummary::from_entries(&entries, since);
+        assert_eq!(recent.operations, 2);
+        assert_eq!(recent.bytes_freed, 75);
+        assert_eq!(recent.items_cleaned, 4);
+    }
+
+    #[test]
+    fn test_verify_reports_regrown_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let regrown = dir.path().join("cache");
+        std::fs::create_dir(&regrown).unwrap();
+        std::fs::write(regrown.join("blob"), vec![0u8; 64]).unwrap();
+
+        let mut cleaned = entry(2026, 3, 1, 1000);
+        cleaned.paths = ["cache", "gone"]
+            .into_iter()
+            .map(|name| CleanedPath {
+                path: dir.path().join(name),
+                size: 500,
+                category: "Rust".to_string(),
+                trashed: false,
+            })
+            .collect();
+
+        let journal = Journal::new(dir.path().join("journal.jsonl"));
+        journal.append(&cleaned).unwrap();
+        journal.append(&entry(2026, 3, 2, 10)).unwrap();
+
+        let latest = journal.latest_with_paths().unwrap().unwrap();
+        let status = latest.verify();
+        assert_eq!(status.len(), 2);
+        assert!(status[0].exists);
+        assert_eq!(status[0].current_size, 64);
+        assert!(!status[1].exists);
+        assert_eq!(status[1].current_size, 0);
+    }
+
+    #[test]
+    fn test_from_clean_records_what_was_trashed() {
+        let item = |name: &str| {
+            CleanItem::new(
+                PathBuf::from("/nonexistent").join(name),
+                10,
+                "test",
+                crate::rules::RiskLevel::Low,
+                crate::rules::Category::System,
+            )
+        };
+        let items = [item("kept"), item("deleted"), item("trashed")];
+        let result = CleanResult {
+            cleaned_count: 2,
+            bytes_freed: 20,
+            failed: vec![(items[0].path.clone(), "denied".to_string())],
+            trashed: vec![items[2].path.clone()],
+            ..Default::default()
+        };
+
+        let entry = JournalEntry::from_clean(&items, &result);
+        let trashed: Vec<_> = entry.paths.iter().map(|p| p.trashed).collect();
+        assert_eq!(trashed, [false, true]);
+        assert!(!entry.to_trash);
+    }
+}
@@ -6,8 +6,17 @@
 pub mod cleaner;
 pub mod config;
 pub mod error;
+pub mod journal;
+pub mod lock;
+pub mod paths;
+pub mod priority;
 pub mod rules;
 pub mod scanner;
+pub mod scheduler;
 pub mod ui;
+pub mod util;
+
+#[cfg(test)]
+mod test_support;
 
 pub use error::{Error, Result};
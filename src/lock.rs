@@ -0,0 +1,213 @@
+//! Lock preventing two cleans from running at the same time
+//!
+//! A scheduled clean and a manual one would otherwise race to delete the same
+//! paths. The lock is a file created atomically in the cache directory that
+//! records the holder's pid and start time, and is removed when the
+//! [`CleanLock`] guard is dropped. A run that never drops it (`panic =
+//! "abort"`, or exiting on a second Ctrl-C) leaves the file behind, so a lock
+//! whose recorded process is no longer running is taken over.
+
+use chrono::{DateTime, Utc};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Age after which `--force` may take over a lock whose pid is still in use,
+/// e.g. reused by an unrelated process
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Held lock; released on drop
+#[derive(Debug)]
+pub struct CleanLock {
+    path: PathBuf,
+}
+
+impl CleanLock {
+    /// Default lock location (e.g. ~/.cache/cleanmymac-rs/clean.lock)
+    pub fn default_path() -> crate::Result<PathBuf> {
+        crate::paths::cache_dir()
+            .map(|p| p.join("cleanmymac-rs").join("clean.lock"))
+            .ok_or_else(|| crate::Error::Other("Could not determine cache directory".to_string()))
+    }
+
+    /// Acquire the lock at its default location
+    pub fn acquire_default(force: bool) -> crate::Result<Self> {
+        Self::acquire(Self::default_path()?, force)
+    }
+
+    /// Acquire the lock at `path`, failing if another process holds it
+    ///
+    /// A lock whose recorded process has exited is taken over. With `force`,
+    /// so is a lock older than [`STALE_LOCK_AGE`], which is assumed to be
+    /// left over from a crashed run whose pid has been reused.
+    pub fn acquire(path: impl Into<PathBuf>, force: bool) -> crate::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        match Self::create(&path) {
+            Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let holder = LockHolder::read(&path);
+                let dead = holder.as_ref().is_some_and(|h| !h.is_running());
+                let stale = holder.as_ref().is_none_or(|h| h.age() >= STALE_LOCK_AGE);
+                if !(dead || force && stale) {
+                    return Err(crate::Error::Locked {
+                        path,
+                        holder: holder
+                            .map_or_else(|| "unknown process".to_string(), |h| h.to_string()),
+                    });
+                }
+
+                tracing::warn!("Removing stale clean lock {}", path.display());
+                std::fs::remove_file(&path).map_err(|e| crate::Error::from_delete(&path, e))?;
+                Self::create(&path)
+            }
+            result => result,
+        }
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Atomically create the lock file and record this process in it
+    fn create(path: &Path) -> crate::Result<Self> {
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+        let lock = Self {
+            path: path.to_path_buf(),
+        };
+        writeln!(file, "{}\n{}", std::process::id(), Utc::now().to_rfc3339())?;
+        Ok(lock)
+    }
+}
+
+impl Drop for CleanLock {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("Failed to remove clean lock {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Process recorded in an existing lock file
+#[derive(Debug)]
+struct LockHolder {
+    pid: u32,
+    since: DateTime<Utc>,
+}
+
+impl LockHolder {
+    /// Parse the lock file, returning `None` if it is unreadable or malformed
+    fn read(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let since = DateTime::parse_from_rfc3339(lines.next()?.trim())
+            .ok()?
+            .with_timezone(&Utc);
+        Some(Self { pid, since })
+    }
+
+    /// Whether the recorded process is still running
+    fn is_running(&self) -> bool {
+        let pid = sysinfo::Pid::from_u32(self.pid);
+        let mut system = sysinfo::System::new();
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            true,
+            sysinfo::ProcessRefreshKind::nothing(),
+        );
+        system.process(pid).is_some()
+    }
+
+    /// How long the lock has been held
+    fn age(&self) -> Duration {
+        (Utc::now() - self.since).to_std().unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "pid {}, started {}",
+            self.pid,
+            self.since
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_holder_is_refused_until_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clean.lock");
+
+        let first = CleanLock::acquire(&path, false).unwrap();
+        let err = CleanLock::acquire(&path, false).unwrap_err();
+        assert!(matches!(err, crate::Error::Locked { .. }));
+        assert!(err.to_string().contains(&std::process::id().to_string()));
+
+        // A fresh lock is not stale, so --force does not take it over
+        assert!(CleanLock::acquire(&path, true).is_err());
+
+        drop(first);
+        assert!(!path.exists());
+        let second = CleanLock::acquire(&path, false).unwrap();
+        assert_eq!(second.path(), path);
+    }
+
+    /// Pid of a process that has already exited
+    fn exited_pid() -> u32 {
+        let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--list")
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        child.wait().unwrap();
+        child.id()
+    }
+
+    #[test]
+    fn test_lock_of_exited_process_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clean.lock");
+        let since = Utc::now().to_rfc3339();
+        std::fs::write(&path, format!("{}\n{}\n", exited_pid(), since)).unwrap();
+
+        let lock = CleanLock::acquire(&path, false).unwrap();
+        assert!(
+            std::fs::read_to_string(lock.path())
+                .unwrap()
+                .starts_with(&std::process::id().to_string())
+        );
+    }
+
+    #[test]
+    fn test_force_takes_over_stale_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clean.lock");
+        let since = Utc::now() - chrono::Duration::hours(3);
+        // Held by a running process, e.g. after its pid was reused
+        let pid = std::process::id();
+        std::fs::write(&path, format!("{}\n{}\n", pid, since.to_rfc3339())).unwrap();
+
+        assert!(CleanLock::acquire(&path, false).is_err());
+        let lock = CleanLock::acquire(&path, true).unwrap();
+        assert!(
+            std::fs::read_to_string(lock.path())
+                .unwrap()
+                .starts_with(&std::process::id().to_string())
+        );
+    }
+}
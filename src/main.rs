@@ -4,14 +4,34 @@
 //! on macOS and Linux systems. Built with Rust for performance and safety.
 
 use cleanmymac_rs::{
-    cleaner::Cleaner,
+    cleaner::{self, Cleaner, DisposalMode, report::CleanAudit, staging},
     config::Config,
-    rules::{get_all_rules, get_rules_by_category},
-    scanner::{FileScanner, ScanSummary, StorageAnalyzer},
-    ui::{Cli, Commands, OutputFormat, tui::App},
+    journal::{HistorySummary, Journal, JournalEntry},
+    lock::CleanLock,
+    rules::{
+        CleanItem, RuleSelection, ScanContext, exclude_categories, get_all_rules_with_config,
+        get_rules_by_category_with_config, read_selection_file, remove_path, unknown_selections,
+    },
+    scanner::{
+        CategorySort, DiskSpace, DuplicateGroup, FileScanner, ItemSort, PathChangeKind,
+        ProgressBarReporter, RuleStat, ScanDiff, ScanSummary, ScanWatcher, StorageAnalyzer,
+        StorageInfo, TreemapBuilder, fs_util::dir_size, item_json_line, total_reclaimable,
+    },
+    scheduler::{self, Cadence, Schedule},
+    ui::{
+        Cli, Commands, OutputFormat,
+        format::{available_width, display_path},
+        glyphs::{GlyphSet, glyphs},
+        notify,
+        tui::App,
+    },
 };
 use colored::*;
 use dialoguer::Confirm;
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 fn main() -> anyhow::Result<()> {
@@ -34,97 +54,550 @@ fn main() -> anyhow::Result<()> {
     if cli.no_color {
         colored::control::set_override(false);
     }
+    GlyphSet::detect(cli.ascii).select();
 
     // Load configuration
-    let config = if let Some(config_path) = &cli.config {
+    let mut config = if let Some(config_path) = &cli.config {
         Config::load_from(config_path)?
     } else {
         Config::load_or_default()
     };
 
+    // Apply the selected profile, failing early if it doesn't exist
+    if let Some(profile) = &cli.profile {
+        config.apply_profile(profile)?;
+        Config::select_profile(profile);
+    }
+    if cli.no_heuristic {
+        config.heuristic.enabled = false;
+    }
+    configure_threads(&config, cli.low_priority || config.general.io_nice);
+
     // Handle commands
     match cli.command {
         Commands::Scan {
             categories,
+            categories_file,
+            all,
+            config_categories: _,
+            exclude_category,
             format,
-            min_size,
+            min_size: _,
+            include_small,
+            min_confidence,
+            stream,
+            show_errors,
+            top,
+            sort,
+            sort_categories,
+            path,
+            watch,
+            timings,
+            save,
         } => {
-            run_scan(categories, format, min_size)?;
+            run_scan(
+                ScanOptions {
+                    selection: RuleSelection::from_flags(
+                        with_categories_file(categories, categories_file.as_deref(), &config)?,
+                        all,
+                    ),
+                    exclude_category,
+                    format,
+                    include_small,
+                    min_confidence,
+                    stream,
+                    show_errors,
+                    top,
+                    sort,
+                    sort_categories,
+                    path,
+                    watch,
+                    timings,
+                    save,
+                },
+                &config,
+            )?;
         }
         Commands::Clean {
             categories,
+            categories_file,
+            all,
+            config_categories: _,
+            exclude_category,
             dry_run,
+            include_small,
+            min_confidence,
+            format,
             yes,
+            yes_low,
             permanent,
+            stage,
+            report,
             interactive,
             quiet,
+            only_stale,
+            older_than,
+            max_total,
+            sudo,
+            path,
+            force,
         } => {
+            let stale_days = older_than.or(only_stale.then_some(config.heuristic.stale_days));
             run_clean(
-                categories,
-                dry_run,
-                yes,
-                permanent,
-                interactive,
-                quiet,
+                CleanOptions {
+                    selection: RuleSelection::from_flags(
+                        with_categories_file(categories, categories_file.as_deref(), &config)?,
+                        all,
+                    ),
+                    exclude_category,
+                    dry_run,
+                    include_small,
+                    min_confidence,
+                    format,
+                    yes,
+                    yes_low,
+                    permanent,
+                    stage,
+                    report,
+                    interactive,
+                    stale_days,
+                    max_total,
+                    sudo,
+                    path,
+                    quiet,
+                    force,
+                    verbose: cli.verbose,
+                },
+                &config,
+            )?;
+        }
+        Commands::Analyze {
+            path,
+            depth,
+            top,
+            format,
+            export_treemap,
+            one_file_system,
+            duplicates,
+            min_size,
+            only_ext,
+            exclude_ext,
+            quiet,
+        } => {
+            run_analyze(
+                AnalyzeOptions {
+                    path,
+                    depth,
+                    top,
+                    format,
+                    export_treemap,
+                    one_file_system,
+                    duplicates,
+                    min_size,
+                    only_ext,
+                    exclude_ext,
+                    quiet,
+                },
+                &config,
+            )?;
+        }
+        Commands::List {
+            category,
+            detailed,
+            id,
+            applicable_only,
+            format,
+        } => {
+            run_list(
+                ListOptions {
+                    category,
+                    detailed,
+                    show_id: id,
+                    applicable_only,
+                    format,
+                },
                 &config,
             )?;
         }
-        Commands::Analyze { path, depth, top } => {
-            run_analyze(path, depth, top)?;
+        Commands::PurgeStaging {
+            dir,
+            older_than,
+            dry_run,
+            yes,
+        } => {
+            run_purge_staging(&dir, older_than, dry_run, yes)?;
+        }
+        Commands::History { since, format } => {
+            run_history(since, format)?;
+        }
+        Commands::Verify { format } => {
+            run_verify(format)?;
         }
-        Commands::List { category, detailed } => {
-            run_list(category, detailed)?;
+        Commands::Diff {
+            before,
+            after,
+            top,
+            format,
+        } => {
+            run_diff(&before, after.as_deref(), top, format, &config)?;
+        }
+        Commands::Doctor { format } => {
+            run_doctor(format, &config)?;
+        }
+        Commands::Schedule {
+            every,
+            categories,
+            remove,
+            dry_run,
+        } => {
+            run_schedule(every, categories, remove, dry_run, &config)?;
         }
-        Commands::Config { init, show, path } => {
-            run_config(init, show, path)?;
+        Commands::Config {
+            init,
+            show,
+            validate,
+            edit,
+            path,
+        } => {
+            run_config(init, show, validate, edit, path)?;
         }
         Commands::Tui => {
-            run_tui()?;
+            let config_path = match &cli.config {
+                Some(path) => Some(std::path::PathBuf::from(path)),
+                None => Config::default_path().ok(),
+            };
+            run_tui(config, config_path)?;
         }
     }
 
     Ok(())
 }
 
-/// Run the scan command
-fn run_scan(
+/// Apply `general.parallel_threads` and low-priority mode before any rayon
+/// work starts
+fn configure_threads(config: &Config, low_priority: bool) {
+    if low_priority && let Err(e) = cleanmymac_rs::priority::lower() {
+        eprintln!(
+            "{}",
+            format!(
+                "{}  Could not lower the process priority: {}",
+                glyphs().warning,
+                e
+            )
+            .yellow()
+        );
+    }
+
+    let mut threads = config.general.parallel_threads;
+    if low_priority {
+        let cap = cleanmymac_rs::priority::low_priority_threads();
+        threads = if threads == 0 { cap } else { threads.min(cap) };
+    }
+    if threads == 0 {
+        return;
+    }
+    if let Err(e) = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .start_handler(move |_| {
+            if low_priority {
+                cleanmymac_rs::priority::background_thread();
+            }
+        })
+        .build_global()
+    {
+        tracing::debug!("Thread pool already initialized: {}", e);
+    }
+}
+
+/// Options for the scan command
+struct ScanOptions {
+    /// Rules to run, from --categories, --all or the config
+    selection: RuleSelection,
+    /// Categories removed from the selected set
+    exclude_category: Vec<String>,
+    format: OutputFormat,
+    /// Ignore rule thresholds
+    include_small: bool,
+    /// Minimum confidence of heuristic detections
+    min_confidence: Option<f64>,
+    stream: bool,
+    show_errors: bool,
+    top: usize,
+    /// Order of the items within each category
+    sort: ItemSort,
+    /// Order of the categories
+    sort_categories: CategorySort,
+    /// Only report items located under this directory
+    path: Option<std::path::PathBuf>,
+    /// Refresh the results when scanned directories change
+    watch: bool,
+    /// Print per-rule scan timings
+    timings: bool,
+    /// Save the results to this file
+    save: Option<std::path::PathBuf>,
+}
+
+/// Merge the entries of `--categories-file` into `--categories`, warning
+/// about entries that match no category or rule
+fn with_categories_file(
     categories: Option<Vec<String>>,
+    file: Option<&std::path::Path>,
+    config: &Config,
+) -> anyhow::Result<Option<Vec<String>>> {
+    let Some(file) = file else {
+        return Ok(categories);
+    };
+    let from_file = read_selection_file(file)?;
+    let unknown = unknown_selections(config, &from_file);
+    if !unknown.is_empty() {
+        eprintln!(
+            "{}",
+            format!(
+                "{}  Unknown categories or rules in {}: {}",
+                glyphs().warning,
+                file.display(),
+                unknown.join(", ")
+            )
+            .yellow()
+        );
+    }
+    let mut categories = categories.unwrap_or_default();
+    categories.extend(from_file);
+    Ok(Some(categories))
+}
+
+/// Run the scan command
+fn run_scan(options: ScanOptions, config: &Config) -> anyhow::Result<()> {
+    let ScanOptions {
+        selection,
+        exclude_category,
+        format,
+        include_small,
+        min_confidence,
+        stream,
+        show_errors,
+        top,
+        sort,
+        sort_categories,
+        path,
+        watch,
+        timings,
+        save,
+    } = options;
+    let scope = path.as_deref().map(resolve_scope).transpose()?;
+    // Newline-delimited JSON goes to stdout undecorated
+    let ndjson = matches!(format, OutputFormat::Ndjson);
+
+    if !ndjson {
+        println!(
+            "{}",
+            format!("\n{} Scanning for cleanable files...\n", glyphs().search)
+                .cyan()
+                .bold()
+        );
+        if !matches!(format, OutputFormat::Json) {
+            print_disk_space(scope.as_deref());
+        }
+    }
+
+    let rules = exclude_categories(selection.rules(config), &exclude_category);
+
+    // Clones of the context share the skipped-path list
+    let ctx = ScanContext::new(config.clone())
+        .include_small(include_small)
+        .min_confidence(min_confidence.unwrap_or_default());
+    if watch {
+        return run_scan_watch(rules, ctx, scope, format, top, (sort, sort_categories));
+    }
+    let scanner = FileScanner::new(rules).with_context(ctx.clone());
+    if ndjson {
+        // Print each rule's items as soon as it finishes
+        let mut found = scanner.scan_streaming(|_, items| {
+            for item in items {
+                if scope
+                    .as_deref()
+                    .is_none_or(|scope| in_scope(&item.path, scope))
+                {
+                    print_json_line(item);
+                }
+            }
+        })?;
+        report_skipped(&ctx.skipped_paths(), show_errors);
+        if let Some(save) = &save {
+            if let Some(scope) = &scope {
+                found.retain(|item| in_scope(&item.path, scope));
+            }
+            save_scan(found, save)?;
+        }
+        return Ok(());
+    }
+    let (mut items, stats) = if stream {
+        (scan_with_subtotals(&scanner, scope.as_deref())?, Vec::new())
+    } else {
+        scanner
+            .with_progress(ProgressBarReporter::new())
+            .scan_with_stats()?
+    };
+    if let Some(scope) = &scope {
+        items.retain(|item| in_scope(&item.path, scope));
+    }
+    report_skipped(&ctx.skipped_paths(), show_errors);
+    let saved = save.as_ref().map(|_| items.clone());
+    print_scan_results(items, format, top, (sort, sort_categories))?;
+    if timings {
+        print_rule_timings(&stats);
+    }
+    if let (Some(save), Some(items)) = (&save, saved) {
+        save_scan(items, save)?;
+    }
+    Ok(())
+}
+
+/// Show free space on the volume of `scope`, or of the home directory;
+/// skipped when it cannot be determined
+fn print_disk_space(scope: Option<&std::path::Path>) {
+    let space = match scope {
+        Some(scope) => DiskSpace::for_path(scope),
+        None => DiskSpace::home(),
+    };
+    if let Some(space) = space {
+        println!("{}", format!("{} Disk: {}", glyphs().disk, space).dimmed());
+    }
+}
+
+/// Write scan results to `path` for a later `diff`
+fn save_scan(items: Vec<CleanItem>, path: &std::path::Path) -> anyhow::Result<()> {
+    ScanSummary::from_items(items).save(path)?;
+    eprintln!("{} Scan saved to {}", glyphs().info.cyan(), path.display());
+    Ok(())
+}
+
+/// Print per-rule scan timings, slowest first
+fn print_rule_timings(stats: &[RuleStat]) {
+    println!(
+        "\n{}",
+        format!("{} Rule Timings", glyphs().clock).cyan().bold()
+    );
+    println!("{}", glyphs().line(60));
+    println!(
+        "{}",
+        format!("{:<32} {:>8} {:>6} {:>10}", "Rule", "Time", "Items", "Size").bold()
+    );
+    for stat in stats {
+        println!(
+            "{:<32} {:>8} {:>6} {:>10}",
+            stat.rule,
+            format!("{:.2}s", stat.elapsed.as_secs_f64()),
+            stat.items,
+            bytesize::ByteSize::b(stat.bytes).to_string()
+        );
+    }
+    let total: std::time::Duration = stats.iter().map(|s| s.elapsed).sum();
+    println!(
+        "\n{} {} rules, {:.2}s of scan time",
+        "Total:".bold(),
+        stats.len(),
+        total.as_secs_f64()
+    );
+}
+
+/// Keep scanning until Ctrl-C, redrawing the results whenever they change
+fn run_scan_watch(
+    rules: Vec<Box<dyn cleanmymac_rs::rules::CleanRule>>,
+    ctx: ScanContext,
+    scope: Option<std::path::PathBuf>,
     format: OutputFormat,
-    _min_size: Option<String>,
+    top: usize,
+    order: (ItemSort, CategorySort),
 ) -> anyhow::Result<()> {
-    println!("{}", "\n🔍 Scanning for cleanable files...\n".cyan().bold());
+    let stop = Arc::new(AtomicBool::new(false));
+    let signal = signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&stop))?;
 
-    let rules = if let Some(cats) = categories {
-        get_rules_by_category(&cats)
-    } else {
-        get_all_rules()
+    let redraw = |mut items: Vec<CleanItem>| -> anyhow::Result<()> {
+        if let Some(scope) = &scope {
+            items.retain(|item| in_scope(&item.path, scope));
+        }
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+            crossterm::cursor::MoveTo(0, 0)
+        )?;
+        println!(
+            "{}",
+            format!(
+                "{} Watching for changes (updated {}, Ctrl-C to stop)",
+                glyphs().search,
+                chrono::Local::now().format("%H:%M:%S")
+            )
+            .cyan()
+            .bold()
+        );
+        print_scan_results(items, format, top, order)
     };
 
-    let scanner = FileScanner::new(rules);
-    let items = scanner.scan()?;
+    let mut watcher = ScanWatcher::new(rules).with_context(ctx);
+    redraw(watcher.scan_all())?;
+    let mut result = Ok(());
+    watcher.run(&stop, |items| {
+        if let Err(e) = redraw(items) {
+            result = Err(e);
+            stop.store(true, Ordering::Relaxed);
+        }
+    });
 
-    if items.is_empty() {
-        println!("\n{}", "✨ No cleanable files found!".green());
+    signal_hook::low_level::unregister(signal);
+    println!("\n{}", "Stopped watching".dimmed());
+    result
+}
+
+/// Print an item as one line of JSON
+fn print_json_line(item: &CleanItem) {
+    match item_json_line(item) {
+        Ok(line) => println!("{}", line),
+        Err(e) => tracing::warn!("Failed to serialize {}: {}", item.path.display(), e),
+    }
+}
+
+/// Print scan results in the requested format, ordering the items within
+/// each category and the categories as given
+fn print_scan_results(
+    items: Vec<CleanItem>,
+    format: OutputFormat,
+    top: usize,
+    (sort, sort_categories): (ItemSort, CategorySort),
+) -> anyhow::Result<()> {
+    // An empty result is an empty stream in NDJSON
+    if items.is_empty() && !matches!(format, OutputFormat::Ndjson) {
+        println!(
+            "\n{}",
+            format!("{} No cleanable files found!", glyphs().sparkles).green()
+        );
         return Ok(());
     }
 
-    let summary = ScanSummary::from_items(items);
+    let mut summary = ScanSummary::from_items(items);
+    summary.sort(sort, sort_categories);
 
     match format {
         OutputFormat::Table => {
-            print_summary_table(&summary);
+            print_summary_table(&summary, top);
         }
         OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(&summary.by_category)?;
-            println!("{}", json);
+            println!("{}", summary.report_json()?);
+        }
+        OutputFormat::Ndjson => {
+            for (_, items) in &summary.by_category {
+                items.iter().for_each(print_json_line);
+            }
         }
         OutputFormat::List => {
+            let width = available_width(16);
             for (category, items) in &summary.by_category {
                 println!("\n{}:", category.bold());
                 for item in items {
                     println!(
                         "  {} ({})",
-                        item.path.display(),
+                        display_path(&item.path, width),
                         bytesize::ByteSize::b(item.size)
                     );
                 }
@@ -135,41 +608,138 @@ fn run_scan(
     Ok(())
 }
 
-/// Print summary as a table
-fn print_summary_table(summary: &ScanSummary) {
-    println!("\n{}", "📊 Scan Results".bold());
-    println!("{}", "═".repeat(60));
+/// Scan while printing running per-category subtotals as each rule finishes
+fn scan_with_subtotals(
+    scanner: &FileScanner,
+    scope: Option<&std::path::Path>,
+) -> anyhow::Result<Vec<CleanItem>> {
+    let mut subtotals: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+    let mut total = 0u64;
+
+    let items = scanner.scan_streaming(|rule_name, items| {
+        let items: Vec<&CleanItem> = items
+            .iter()
+            .filter(|item| scope.is_none_or(|scope| in_scope(&item.path, scope)))
+            .collect();
+        if items.is_empty() {
+            return;
+        }
+
+        let found: u64 = items.iter().map(|i| i.size).sum();
+        total += found;
+        for item in &items {
+            *subtotals.entry(item.category.to_string()).or_default() += item.size;
+        }
+
+        let category = items[0].category.to_string();
+        println!(
+            "  {} {} +{} ({} {}, running total {})",
+            glyphs().arrow.cyan(),
+            rule_name,
+            bytesize::ByteSize::b(found).to_string().green(),
+            category,
+            bytesize::ByteSize::b(subtotals[&category]),
+            bytesize::ByteSize::b(total).to_string().bold()
+        );
+    })?;
+
+    Ok(items)
+}
+
+/// Canonicalize the `--path` scope so it can be compared with item paths
+fn resolve_scope(path: &std::path::Path) -> anyhow::Result<std::path::PathBuf> {
+    let path = cleanmymac_rs::util::expand_tilde(&path.to_string_lossy());
+    if !path.is_dir() {
+        anyhow::bail!("--path {} is not a directory", path.display());
+    }
+    Ok(path.canonicalize()?)
+}
+
+/// Whether an item lies under the `--path` scope
+///
+/// Command-driven items such as Docker resources carry virtual paths and are
+/// therefore never in scope.
+fn in_scope(path: &std::path::Path, scope: &std::path::Path) -> bool {
+    path.starts_with(scope)
+        || path
+            .canonicalize()
+            .is_ok_and(|path| path.starts_with(scope))
+}
+
+/// Warn about paths the scan could not read, listing them if requested
+fn report_skipped(skipped: &[std::path::PathBuf], show_errors: bool) {
+    if skipped.is_empty() {
+        return;
+    }
+
+    eprintln!(
+        "{}",
+        format!(
+            "{}  {} paths skipped (permission denied) - results may be incomplete",
+            glyphs().warning,
+            skipped.len()
+        )
+        .yellow()
+    );
+    if show_errors {
+        for path in skipped {
+            eprintln!("  {} {}", glyphs().cross.red(), path.display());
+        }
+    } else {
+        eprintln!("{}", "   Rerun with --show-errors to list them.".dimmed());
+    }
+}
+
+/// Print summary as a table, showing the `top` largest items per category
+fn print_summary_table(summary: &ScanSummary, top: usize) {
+    println!("\n{}", format!("{} Scan Results", glyphs().stats).bold());
+    println!("{}", glyphs().double_line(60));
 
+    let width = available_width(20);
     for (category, items) in &summary.by_category {
         let cat_size: u64 = items.iter().map(|i| i.size).sum();
         println!(
             "\n{} {} ({} items, {})",
-            "▸".cyan(),
+            glyphs().arrow.cyan(),
             category.bold(),
             items.len(),
             bytesize::ByteSize::b(cat_size).to_string().green()
         );
 
-        for item in items.iter().take(5) {
+        for item in items.iter().take(top) {
             let risk_indicator = match item.risk_level {
-                cleanmymac_rs::rules::RiskLevel::Low => "●".green(),
-                cleanmymac_rs::rules::RiskLevel::Medium => "●".yellow(),
-                cleanmymac_rs::rules::RiskLevel::High => "●".red(),
+                cleanmymac_rs::rules::RiskLevel::Low => glyphs().dot.green(),
+                cleanmymac_rs::rules::RiskLevel::Medium => glyphs().dot.yellow(),
+                cleanmymac_rs::rules::RiskLevel::High => glyphs().dot.red(),
             };
             println!(
                 "    {} {} ({})",
                 risk_indicator,
-                item.path.display(),
+                display_path(&item.path, width),
                 bytesize::ByteSize::b(item.size)
             );
         }
 
-        if items.len() > 5 {
-            println!("    {} ...and {} more", "".dimmed(), items.len() - 5);
+        if items.len() > top {
+            println!("    {} ...and {} more", "".dimmed(), items.len() - top);
         }
     }
 
-    println!("\n{}", "═".repeat(60));
+    println!("\n{}", glyphs().double_line(60));
+    for (level, total) in &summary.by_risk {
+        let indicator = match level {
+            cleanmymac_rs::rules::RiskLevel::Low => glyphs().dot.green(),
+            cleanmymac_rs::rules::RiskLevel::Medium => glyphs().dot.yellow(),
+            cleanmymac_rs::rules::RiskLevel::High => glyphs().dot.red(),
+        };
+        println!(
+            "  {} {:<7} {:>12} ({} items)",
+            indicator,
+            level.to_string(),
+            bytesize::ByteSize::b(total.bytes).to_string(),
+            total.count
+        );
+    }
     println!(
         "{} {} items, {}",
         "Total:".bold(),
@@ -181,47 +751,283 @@ fn print_summary_table(summary: &ScanSummary) {
     );
 }
 
-/// Run the clean command
-fn run_clean(
-    categories: Option<Vec<String>>,
+/// Options for the clean command
+struct CleanOptions {
+    /// Rules to run, from --categories, --all or the config
+    selection: RuleSelection,
+    /// Categories removed from the selected set
+    exclude_category: Vec<String>,
     dry_run: bool,
+    /// Ignore rule thresholds
+    include_small: bool,
+    /// Minimum confidence of heuristic detections
+    min_confidence: Option<f64>,
+    format: OutputFormat,
     yes: bool,
+    /// Approve low-risk items without asking, prompting for riskier ones
+    yes_low: bool,
     permanent: bool,
+    /// Stage items under this directory instead of trashing or deleting them
+    stage: Option<std::path::PathBuf>,
+    /// Write a per-item audit report to this file
+    report: Option<std::path::PathBuf>,
     interactive: bool,
-    _quiet: bool,
-    config: &Config,
+    /// Only clean items last modified more than this many days ago
+    stale_days: Option<u32>,
+    /// Only clean the largest items that fit within this size (e.g. "10GB")
+    max_total: Option<String>,
+    /// Clean root-owned caches via their package manager under sudo
+    sudo: bool,
+    /// Only clean items located under this directory
+    path: Option<std::path::PathBuf>,
+    /// Print nothing but a final one-line summary (or JSON)
+    quiet: bool,
+    /// Take over a stale lock left by another clean
+    force: bool,
+    /// List every item of a dry run instead of per-category totals
+    verbose: bool,
+}
+
+/// Machine-readable outcome of a clean (`--format json`)
+#[derive(Serialize)]
+struct CleanReport {
+    dry_run: bool,
+    cleaned_count: usize,
+    bytes_freed: u64,
+    failed: Vec<FailedItem>,
+    /// Items cleaned differently than requested (e.g. deleted instead of trashed)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    warnings: Vec<CleanWarning>,
+    /// Items removed by something else between scan and clean
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    already_gone: Vec<std::path::PathBuf>,
+    /// Interrupted by Ctrl-C before all items were cleaned
+    cancelled: bool,
+}
+
+/// An item that was cleaned, but not the way it was asked to be
+#[derive(Serialize)]
+struct CleanWarning {
+    path: std::path::PathBuf,
+    warning: String,
+}
+
+/// An item that could not be cleaned
+#[derive(Serialize)]
+struct FailedItem {
+    path: std::path::PathBuf,
+    error: String,
+}
+
+/// Print the outcome of a clean as JSON or a single summary line
+fn print_clean_report(
+    result: &cleanmymac_rs::rules::CleanResult,
+    dry_run: bool,
+    json: bool,
 ) -> anyhow::Result<()> {
-    println!("{}", "\n🧹 Preparing to clean...\n".cyan().bold());
+    if json {
+        let report = CleanReport {
+            dry_run,
+            cleaned_count: result.cleaned_count,
+            bytes_freed: result.bytes_freed,
+            failed: result
+                .failed
+                .iter()
+                .map(|(path, error)| FailedItem {
+                    path: path.clone(),
+                    error: error.clone(),
+                })
+                .collect(),
+            warnings: result
+                .warnings
+                .iter()
+                .map(|(path, warning)| CleanWarning {
+                    path: path.clone(),
+                    warning: warning.clone(),
+                })
+                .collect(),
+            already_gone: result.already_gone.clone(),
+            cancelled: result.cancelled,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "{} {} items, {} {}, {} failed{}",
+            if dry_run { "Would clean" } else { "Cleaned" },
+            result.cleaned_count,
+            if dry_run { "would free" } else { "freed" },
+            bytesize::ByteSize::b(result.bytes_freed),
+            result.failed.len(),
+            if result.cancelled { " (cancelled)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// Run the clean command
+fn run_clean(options: CleanOptions, config: &Config) -> anyhow::Result<()> {
+    let CleanOptions {
+        selection,
+        exclude_category,
+        dry_run,
+        include_small,
+        min_confidence,
+        format,
+        yes,
+        yes_low,
+        permanent,
+        stage,
+        report,
+        interactive,
+        stale_days,
+        max_total,
+        sudo,
+        path,
+        quiet,
+        force,
+        verbose,
+    } = options;
+    let scope = path.as_deref().map(resolve_scope).transpose()?;
+    let max_total = max_total
+        .map(|size| {
+            cleanmymac_rs::util::parse_size(&size, false)
+                .ok_or_else(|| anyhow::anyhow!("Invalid --max-total: {}", size))
+        })
+        .transpose()?;
+
+    // Held until run_clean returns; dry runs delete nothing and need no lock
+    let _lock = if dry_run {
+        None
+    } else {
+        Some(CleanLock::acquire_default(force)?)
+    };
+
+    // JSON output keeps stdout free of everything but the JSON document
+    let json = matches!(format, OutputFormat::Json);
+    let json_manifest = dry_run && json;
+    let quiet = quiet || json;
+
+    if !quiet {
+        println!(
+            "{}",
+            format!("\n{} Preparing to clean...\n", glyphs().broom)
+                .cyan()
+                .bold()
+        );
+        print_disk_space(scope.as_deref());
+    }
+
+    let rules = exclude_categories(selection.rules(config), &exclude_category);
 
-    let rules = if let Some(cats) = categories {
-        get_rules_by_category(&cats)
+    // Restrict to stale items; command-driven items have no mtime and are excluded
+    let ctx = ScanContext::new(config.clone())
+        .include_small(include_small)
+        .min_confidence(min_confidence.unwrap_or_default())
+        .min_age(stale_days);
+    let mut scanner = FileScanner::new(rules).with_context(ctx.clone());
+    let mut items = if quiet {
+        scanner.scan_quiet()?
     } else {
-        get_all_rules()
+        scanner = scanner.with_progress(ProgressBarReporter::new());
+        scanner.scan()?
     };
+    if let Some(scope) = &scope {
+        items.retain(|item| in_scope(&item.path, scope));
+    }
+
+    if let Some(days) = stale_days
+        && !quiet
+    {
+        let too_recent = ctx
+            .too_recent_paths()
+            .iter()
+            .filter(|path| scope.as_deref().is_none_or(|scope| in_scope(path, scope)))
+            .count();
+        eprintln!(
+            "{} {} of {} items are older than {} days",
+            glyphs().info.cyan(),
+            items.len(),
+            items.len() + too_recent,
+            days
+        );
+    }
+
+    if let Some(budget) = max_total {
+        let found = items.len();
+        items = cleaner::select_within_budget(items, budget);
+        if !quiet {
+            eprintln!(
+                "{} Selected {} of {} items, {} of the requested {}",
+                glyphs().info.cyan(),
+                items.len(),
+                found,
+                bytesize::ByteSize::b(items.iter().map(|i| i.size).sum()),
+                bytesize::ByteSize::b(budget)
+            );
+        }
+    }
 
-    let scanner = FileScanner::new(rules);
-    let items = scanner.scan()?;
+    if json_manifest {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&cleaner::dry_run_manifest(&items))?
+        );
+        return Ok(());
+    }
 
     if items.is_empty() {
-        println!("\n{}", "✨ Nothing to clean!".green());
+        if quiet {
+            print_clean_report(&Default::default(), dry_run, json)?;
+        } else {
+            println!(
+                "\n{}",
+                format!("{} Nothing to clean!", glyphs().sparkles).green()
+            );
+        }
         return Ok(());
     }
 
+    // --yes-low skips the overall prompt below and has the cleaner ask for
+    // each riskier group instead; --yes approves everything and wins
+    let prompt_riskier = yes_low && !yes;
+
+    let disposal = match &stage {
+        Some(dir) => DisposalMode::Stage(dir.clone()),
+        None if !permanent && config.general.use_trash => DisposalMode::Trash,
+        None => DisposalMode::Delete,
+    };
+
+    // Set by Ctrl-C while cleaning so the cleaner stops after the current item
+    let interrupted = Arc::new(AtomicBool::new(false));
+
     // Show preview
     let cleaner = Cleaner::new()
-        .use_trash(!permanent && config.general.use_trash)
-        .confirm_high_risk(config.general.confirm_high_risk)
-        .dry_run(dry_run);
+        .disposal(disposal.clone())
+        .trash_by_risk(config.general.trash_by_risk)
+        .cancel_flag(Arc::clone(&interrupted))
+        .confirm_high_risk(config.general.confirm_high_risk || prompt_riskier)
+        .confirm_medium_risk(config.risk.confirm_medium_risk || prompt_riskier)
+        .dry_run(dry_run)
+        .use_sudo(sudo)
+        .quiet(quiet)
+        .verbose(verbose)
+        .protected_paths(config.safety.resolved_protected_paths())
+        .rules(scanner.into_rules());
 
-    cleaner.preview(&items);
+    if !quiet {
+        cleaner.preview(&items);
+    }
 
     // Filter items if interactive mode is enabled
     let items_to_clean = if interactive {
         println!(
             "\n{}",
-            "👉 Select items to clean (Space to toggle, Enter to confirm):"
-                .cyan()
-                .bold()
+            format!(
+                "{} Select items to clean (Space to toggle, Enter to confirm):",
+                glyphs().point
+            )
+            .cyan()
+            .bold()
         );
 
         let items_display: Vec<String> = items
@@ -242,7 +1048,10 @@ fn run_clean(
             .interact()?;
 
         if selections.is_empty() {
-            println!("\n{}", "❌ No items selected.".yellow());
+            println!(
+                "\n{}",
+                format!("{} No items selected.", glyphs().failure).yellow()
+            );
             return Ok(());
         }
 
@@ -251,20 +1060,28 @@ fn run_clean(
         items
     };
 
-    // Confirm unless --yes was passed or dry run
-    if !yes && !dry_run {
+    // Confirm unless --yes or --yes-low was passed or dry run
+    if !yes && !yes_low && !dry_run {
         let total_size = bytesize::ByteSize::b(items_to_clean.iter().map(|i| i.size).sum());
         let confirm = Confirm::new()
             .with_prompt(format!(
                 "\nDo you want to clean {} items ({})? {}",
                 items_to_clean.len(),
                 total_size,
-                if permanent {
-                    "(PERMANENT)"
-                } else if !config.general.use_trash {
-                    "(PERMANENT - Config)"
-                } else {
-                    "(to trash)"
+                match &disposal {
+                    DisposalMode::Stage(dir) => format!("(to staging {})", dir.display()),
+                    DisposalMode::Delete if permanent => "(PERMANENT)".to_string(),
+                    DisposalMode::Delete => "(PERMANENT - Config)".to_string(),
+                    DisposalMode::Trash => {
+                        let permanent_levels = config.general.trash_by_risk.permanent_levels();
+                        if permanent_levels.is_empty() {
+                            "(to trash)".to_string()
+                        } else {
+                            let levels: Vec<String> =
+                                permanent_levels.iter().map(|l| l.to_string()).collect();
+                            format!("(to trash; {} risk PERMANENT)", levels.join("/"))
+                        }
+                    }
                 }
             ))
             .default(false)
@@ -272,32 +1089,129 @@ fn run_clean(
             .unwrap_or(false);
 
         if !confirm {
-            println!("{}", "\n❌ Cancelled.".yellow());
+            println!("{}", format!("\n{} Cancelled.", glyphs().failure).yellow());
             return Ok(());
         }
     }
 
     // Execute cleaning (using items_to_clean now)
-    let result = cleaner.clean(&items_to_clean)?;
+    // A second Ctrl-C exits immediately, as it would without the handler
+    let signals = [
+        signal_hook::flag::register_conditional_shutdown(
+            signal_hook::consts::SIGINT,
+            130,
+            Arc::clone(&interrupted),
+        )?,
+        signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&interrupted))?,
+    ];
+    let result = cleaner.clean(&items_to_clean);
+    for signal in signals {
+        signal_hook::low_level::unregister(signal);
+    }
+    let result = result?;
+
+    // A failed report must not hide the outcome of the clean itself
+    if let Some(report) = &report {
+        let audit = CleanAudit::new(&items_to_clean, &result, dry_run, &disposal);
+        match audit.write(report) {
+            Ok(()) if !quiet => eprintln!(
+                "{} Report written to {}",
+                glyphs().info.cyan(),
+                report.display()
+            ),
+            Ok(()) => {}
+            Err(e) => tracing::warn!("Failed to write clean report: {}", e),
+        }
+    }
+
+    // Record the operation for `history`
+    if !dry_run && result.cleaned_count > 0 {
+        let entry = JournalEntry::from_clean(&items_to_clean, &result);
+        if let Err(e) = Journal::open_default().and_then(|journal| journal.append(&entry)) {
+            tracing::warn!("Failed to record clean in journal: {}", e);
+        }
+
+        if config.general.notify {
+            notify::notify_clean(&result);
+        }
+    }
 
     // Show results
-    if result.cancelled {
-        println!("{}", "\n❌ Cleaning cancelled.".yellow());
+    if quiet {
+        print_clean_report(&result, dry_run, json)?;
     } else {
+        if result.cancelled {
+            println!(
+                "\n{}",
+                format!(
+                    "{} Cleaning cancelled; {} items were left alone.",
+                    glyphs().failure,
+                    result.skipped.len()
+                )
+                .yellow()
+            );
+        }
         println!(
             "\n{} Cleaned {} items, freed {}",
-            "✅".green(),
+            glyphs().success.green(),
             result.cleaned_count,
             bytesize::ByteSize::b(result.bytes_freed)
                 .to_string()
                 .green()
                 .bold()
         );
+        if !dry_run {
+            print_disk_space(scope.as_deref());
+        }
+        if !result.already_gone.is_empty() {
+            println!(
+                "{}",
+                format!(
+                    "   {} items had already been removed since the scan",
+                    result.already_gone.len()
+                )
+                .dimmed()
+            );
+        }
+
+        if let DisposalMode::Stage(dir) = &disposal
+            && !dry_run
+            && result.cleaned_count > 0
+        {
+            println!(
+                "{} Staged under {}; remove with `cleanmymac-rs purge-staging {}`",
+                glyphs().info.cyan(),
+                dir.display(),
+                dir.display()
+            );
+        }
+
+        if !result.warnings.is_empty() {
+            println!(
+                "\n{}",
+                format!(
+                    "{}  Some items could not be cleaned as requested:",
+                    glyphs().warning
+                )
+                .yellow()
+            );
+            for (path, warning) in &result.warnings {
+                println!(
+                    "    {} {}: {}",
+                    glyphs().bullet.yellow(),
+                    path.display(),
+                    warning
+                );
+            }
+        }
 
         if !result.failed.is_empty() {
-            println!("\n{}", "⚠️  Some items failed to clean:".yellow());
+            println!(
+                "\n{}",
+                format!("{}  Some items failed to clean:", glyphs().warning).yellow()
+            );
             for (path, error) in &result.failed {
-                println!("    {} {}: {}", "✗".red(), path.display(), error);
+                println!("    {} {}: {}", glyphs().cross.red(), path.display(), error);
             }
         }
     }
@@ -305,26 +1219,124 @@ fn run_clean(
     Ok(())
 }
 
+/// Options for the analyze command
+struct AnalyzeOptions {
+    path: Option<String>,
+    depth: usize,
+    top: usize,
+    format: OutputFormat,
+    /// Write the directory tree as JSON to this file
+    export_treemap: Option<std::path::PathBuf>,
+    /// Stay on the file system of the analyzed directory
+    one_file_system: bool,
+    /// Look for duplicate files
+    duplicates: bool,
+    /// Minimum size of files checked for duplicates (e.g. "1MB")
+    min_size: String,
+    /// Only count files with these extensions
+    only_ext: Vec<String>,
+    /// Ignore files with these extensions
+    exclude_ext: Vec<String>,
+    /// Hide the progress spinner
+    quiet: bool,
+}
+
+/// Analyze `path` while a spinner shows the files counted so far
+fn analyze_with_spinner(
+    analyzer: &StorageAnalyzer,
+    path: &std::path::PathBuf,
+) -> anyhow::Result<StorageInfo> {
+    let spinner = indicatif::ProgressBar::new_spinner();
+    spinner.set_style(
+        indicatif::ProgressStyle::default_spinner()
+            .template("{spinner:.green} [{elapsed_precise}] {pos} files {msg}")
+            .unwrap_or_else(|_| indicatif::ProgressStyle::default_spinner())
+            .tick_chars(glyphs().tick_chars),
+    );
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+
+    let info = analyzer.analyze_with_progress(path, |files, bytes| {
+        spinner.set_position(files as u64);
+        // Formatting the size for every file would dominate small files
+        if files % 512 == 0 {
+            spinner.set_message(format!("({})", bytesize::ByteSize::b(bytes)));
+        }
+    });
+    spinner.finish_and_clear();
+    info
+}
+
 /// Run the analyze command
-fn run_analyze(path: Option<String>, depth: usize, top: usize) -> anyhow::Result<()> {
+fn run_analyze(options: AnalyzeOptions, config: &Config) -> anyhow::Result<()> {
+    let AnalyzeOptions {
+        path,
+        depth,
+        top,
+        format,
+        export_treemap,
+        one_file_system,
+        duplicates,
+        min_size,
+        only_ext,
+        exclude_ext,
+        quiet,
+    } = options;
     let target_path = if let Some(p) = path {
         std::path::PathBuf::from(p)
     } else {
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
+        cleanmymac_rs::paths::home_dir()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
     };
+    let json = matches!(format, OutputFormat::Json);
 
-    println!(
-        "{} {}\n",
-        "📊 Analyzing:".cyan().bold(),
-        target_path.display()
-    );
-
-    let analyzer = StorageAnalyzer::new().with_max_depth(depth).with_top_n(top);
-
-    let info = analyzer.analyze(&target_path)?;
+    if !json {
+        println!(
+            "{} {}\n",
+            format!("{} Analyzing:", glyphs().stats).cyan().bold(),
+            target_path.display()
+        );
+    }
+
+    if let Some(export_path) = export_treemap {
+        let tree = TreemapBuilder::new()
+            .max_depth(depth)
+            .build_tree(&target_path)?;
+        let file = std::fs::File::create(&export_path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &tree)?;
+        eprintln!(
+            "{} {}",
+            format!("{}  Treemap exported to", glyphs().map).green(),
+            export_path.display()
+        );
+    }
+
+    let mut analyzer = StorageAnalyzer::new()
+        .with_max_depth(depth)
+        .with_top_n(top)
+        .with_same_filesystem(one_file_system)
+        .with_excluded_extensions(&exclude_ext);
+    if !only_ext.is_empty() {
+        analyzer = analyzer.with_only_extensions(&only_ext);
+    }
+    if duplicates {
+        let min_size = cleanmymac_rs::util::parse_size(&min_size, false)
+            .ok_or_else(|| anyhow::anyhow!("Invalid --min-size: {}", min_size))?;
+        analyzer = analyzer.with_duplicates(min_size);
+    }
+
+    let info = if quiet || json {
+        analyzer.analyze(&target_path)?
+    } else {
+        analyze_with_spinner(&analyzer, &target_path)?
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+        return Ok(());
+    }
 
     println!("{}", "Storage Analysis".bold());
-    println!("{}", "═".repeat(60));
+    println!("{}", glyphs().double_line(60));
     println!(
         "Total size: {}",
         bytesize::ByteSize::b(info.total_size).to_string().green()
@@ -337,7 +1349,7 @@ fn run_analyze(path: Option<String>, depth: usize, top: usize) -> anyhow::Result
         for (path, size) in &info.largest_files {
             println!(
                 "  {} {} ({})",
-                "•".cyan(),
+                glyphs().bullet.cyan(),
                 path.display(),
                 bytesize::ByteSize::b(*size).to_string().yellow()
             );
@@ -345,6 +1357,15 @@ fn run_analyze(path: Option<String>, depth: usize, top: usize) -> anyhow::Result
     }
 
     if !info.by_extension.is_empty() {
+        println!("\n{}", "By Type:".bold());
+        for (group, size) in info.by_group_with(&config.analyze.extension_groups) {
+            println!(
+                "  {}: {}",
+                group,
+                bytesize::ByteSize::b(size).to_string().green()
+            );
+        }
+
         println!("\n{}", "Size by Extension (top 10):".bold());
         let mut extensions: Vec<_> = info.by_extension.iter().collect();
         extensions.sort_by(|a, b| b.1.cmp(a.1));
@@ -357,42 +1378,148 @@ fn run_analyze(path: Option<String>, depth: usize, top: usize) -> anyhow::Result
         }
     }
 
+    if duplicates {
+        print_duplicates(&info.duplicates);
+    }
+
     Ok(())
 }
 
+/// Print each set of duplicate files and the total reclaimable space
+fn print_duplicates(groups: &[DuplicateGroup]) {
+    println!("\n{}", "Duplicate Files:".bold());
+    if groups.is_empty() {
+        println!("  No duplicates found");
+        return;
+    }
+
+    for group in groups {
+        println!(
+            "  {} {} copies of {} ({} reclaimable)",
+            glyphs().arrow.cyan(),
+            group.paths.len(),
+            bytesize::ByteSize::b(group.size),
+            bytesize::ByteSize::b(group.reclaimable())
+                .to_string()
+                .yellow()
+        );
+        for path in &group.paths {
+            println!("      {}", path.display());
+        }
+    }
+    println!(
+        "\nRemoving all but one copy of each would free {}",
+        bytesize::ByteSize::b(total_reclaimable(groups))
+            .to_string()
+            .green()
+            .bold()
+    );
+}
+
+/// Options for the list command
+struct ListOptions {
+    category: Option<String>,
+    detailed: bool,
+    /// Show rule ids
+    show_id: bool,
+    /// Hide rules that would not run on this system
+    applicable_only: bool,
+    format: OutputFormat,
+}
+
+/// A rule as printed by `list --format json`
+#[derive(Serialize)]
+struct RuleListing {
+    id: String,
+    name: String,
+    category: String,
+    /// Name accepted by `--categories`
+    category_slug: String,
+    risk_level: String,
+    applicable: bool,
+    description: String,
+    paths: Vec<std::path::PathBuf>,
+}
+
 /// Run the list command
-fn run_list(category: Option<String>, detailed: bool) -> anyhow::Result<()> {
-    println!("{}", "\n📋 Available Cleanup Rules\n".cyan().bold());
+fn run_list(options: ListOptions, config: &Config) -> anyhow::Result<()> {
+    let ListOptions {
+        category,
+        detailed,
+        show_id,
+        applicable_only,
+        format,
+    } = options;
 
     let rules = if let Some(cat) = category {
-        get_rules_by_category(&[cat])
+        get_rules_by_category_with_config(config, &[cat])
     } else {
-        get_all_rules()
+        get_all_rules_with_config(config)
     };
+    // Checking applicability may run external tools, so do it once per rule
+    let rules: Vec<_> = rules
+        .into_iter()
+        .map(|rule| {
+            let applicable = rule.is_applicable();
+            (rule, applicable)
+        })
+        .filter(|(_, applicable)| *applicable || !applicable_only)
+        .collect();
+
+    if let OutputFormat::Json = format {
+        let listings: Vec<RuleListing> = rules
+            .iter()
+            .map(|(rule, applicable)| RuleListing {
+                id: rule.id().to_string(),
+                name: rule.name().to_string(),
+                category: rule.category().to_string(),
+                category_slug: rule.category().slug(),
+                risk_level: rule.risk_level().to_string(),
+                applicable: *applicable,
+                description: rule.description().to_string(),
+                paths: rule.scan_paths(),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&listings)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("\n{} Available Cleanup Rules\n", glyphs().clipboard)
+            .cyan()
+            .bold()
+    );
 
     if rules.is_empty() {
         println!("{}", "No rules found for the specified category.".yellow());
         return Ok(());
     }
 
-    for rule in &rules {
+    for (rule, applicable) in &rules {
         let risk_indicator = match rule.risk_level() {
-            cleanmymac_rs::rules::RiskLevel::Low => "●".green(),
-            cleanmymac_rs::rules::RiskLevel::Medium => "●".yellow(),
-            cleanmymac_rs::rules::RiskLevel::High => "●".red(),
+            cleanmymac_rs::rules::RiskLevel::Low => glyphs().dot.green(),
+            cleanmymac_rs::rules::RiskLevel::Medium => glyphs().dot.yellow(),
+            cleanmymac_rs::rules::RiskLevel::High => glyphs().dot.red(),
         };
 
-        let applicable = if rule.is_applicable() {
-            "✓".green()
+        let applicable = if *applicable {
+            glyphs().check.green()
         } else {
-            "✗".dimmed()
+            glyphs().cross.dimmed()
         };
 
+        let id = if show_id {
+            format!(" {}", rule.id()).dimmed()
+        } else {
+            "".normal()
+        };
         println!(
-            "{} {} {} [{}] ({})",
+            "{} {} {}{} [{}] ({})",
             applicable,
             risk_indicator,
             rule.name().bold(),
+            id,
             rule.category(),
             rule.risk_level()
         );
@@ -415,14 +1542,542 @@ fn run_list(category: Option<String>, detailed: bool) -> anyhow::Result<()> {
         "\n{} {} rules available ({} applicable)",
         "Total:".bold(),
         rules.len(),
-        rules.iter().filter(|r| r.is_applicable()).count()
+        rules.iter().filter(|(_, applicable)| *applicable).count()
+    );
+
+    let slugs: std::collections::BTreeSet<_> = rules
+        .iter()
+        .map(|(rule, _)| rule.category().slug())
+        .collect();
+    println!(
+        "{} {}",
+        "Categories (for --categories):".bold(),
+        slugs.into_iter().collect::<Vec<_>>().join(", ")
+    );
+
+    Ok(())
+}
+
+/// Run the purge-staging command
+fn run_purge_staging(
+    dir: &std::path::Path,
+    older_than: Option<u32>,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let cutoff = older_than
+        .map(|days| chrono::Local::now().naive_local() - chrono::Duration::days(days.into()));
+    let batches: Vec<_> = staging::list_batches(dir)?
+        .into_iter()
+        .filter(|batch| cutoff.is_none_or(|cutoff| batch.created < cutoff))
+        .map(|batch| {
+            let size = dir_size(&batch.path);
+            (batch, size)
+        })
+        .collect();
+
+    if batches.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "{} No staged batches to purge in {}",
+                glyphs().empty,
+                dir.display()
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    for (batch, size) in &batches {
+        println!(
+            "  {} {} ({})",
+            glyphs().bullet,
+            batch.path.display(),
+            bytesize::ByteSize::b(*size)
+        );
+    }
+    let total = bytesize::ByteSize::b(batches.iter().map(|(_, size)| size).sum());
+
+    if dry_run {
+        println!("\nWould purge {} batches, freeing {}", batches.len(), total);
+        return Ok(());
+    }
+
+    if !yes {
+        let confirm = Confirm::new()
+            .with_prompt(format!(
+                "Permanently delete {} staged batches ({})?",
+                batches.len(),
+                total
+            ))
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+        if !confirm {
+            println!("{}", format!("\n{} Cancelled.", glyphs().failure).yellow());
+            return Ok(());
+        }
+    }
+
+    let mut freed = 0;
+    for (batch, size) in &batches {
+        match remove_path(&batch.path, false) {
+            Ok(()) => freed += size,
+            Err(e) => println!(
+                "    {} {}: {}",
+                glyphs().cross.red(),
+                batch.path.display(),
+                e
+            ),
+        }
+    }
+    println!(
+        "\n{} Purged staging, freed {}",
+        glyphs().success.green(),
+        bytesize::ByteSize::b(freed).to_string().green().bold()
+    );
+
+    Ok(())
+}
+
+/// Run the history command
+fn run_history(since: Option<String>, format: OutputFormat) -> anyhow::Result<()> {
+    let since = since
+        .map(|s| {
+            chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(|e| {
+                anyhow::anyhow!("Invalid --since date '{}': {} (expected YYYY-MM-DD)", s, e)
+            })
+        })
+        .transpose()?;
+
+    let journal = Journal::open_default()?;
+    if !journal.exists() {
+        println!(
+            "{}",
+            format!(
+                "{} No history yet - run `cleanmymac-rs clean` to start reclaiming space.",
+                glyphs().empty
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    let summary = HistorySummary::from_entries(&journal.read_all()?, since);
+
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("\n{} Cleanup History", glyphs().trend)
+            .cyan()
+            .bold()
+    );
+    println!("{}", glyphs().double_line(60));
+    println!(
+        "Total freed: {} in {} operations ({} items)",
+        bytesize::ByteSize::b(summary.bytes_freed)
+            .to_string()
+            .green()
+            .bold(),
+        summary.operations,
+        summary.items_cleaned
+    );
+
+    if !summary.by_category.is_empty() {
+        println!("\n{}", "By category:".bold());
+        let mut categories: Vec<_> = summary.by_category.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1));
+        for (category, bytes) in categories {
+            println!(
+                "  {} {}: {}",
+                glyphs().arrow.cyan(),
+                category,
+                bytesize::ByteSize::b(*bytes).to_string().green()
+            );
+        }
+    }
+
+    if !summary.by_month.is_empty() {
+        println!("\n{}", "By month:".bold());
+        for (month, bytes) in &summary.by_month {
+            println!("  {} {}", month, bytesize::ByteSize::b(*bytes));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the verify command
+fn run_verify(format: OutputFormat) -> anyhow::Result<()> {
+    let journal = Journal::open_default()?;
+    let Some(entry) = journal.latest_with_paths()? else {
+        println!(
+            "{}",
+            format!(
+                "{} No cleaned paths recorded yet - run `cleanmymac-rs clean` first.",
+                glyphs().empty
+            )
+            .yellow()
+        );
+        return Ok(());
+    };
+
+    let mut status = entry.verify();
+    status.sort_by(|a, b| b.current_size.cmp(&a.current_size));
+
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!(
+            "\n{} Regrowth since clean on {}",
+            glyphs().trend,
+            entry
+                .timestamp
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        )
+        .cyan()
+        .bold()
+    );
+    println!("{}", glyphs().double_line(60));
+
+    for path in &status {
+        let marker = if path.exists {
+            "back".yellow()
+        } else {
+            "gone".green()
+        };
+        println!(
+            "  {} {} [{}] {} (was {})",
+            marker,
+            path.path.display(),
+            path.category,
+            bytesize::ByteSize::b(path.current_size).to_string().bold(),
+            bytesize::ByteSize::b(path.cleaned_size)
+        );
+    }
+
+    let regrown: Vec<_> = status.iter().filter(|p| p.exists).collect();
+    println!(
+        "\n{} {} of {} paths reappeared, {} in total",
+        "Total:".bold(),
+        regrown.len(),
+        status.len(),
+        bytesize::ByteSize::b(regrown.iter().map(|p| p.current_size).sum())
+            .to_string()
+            .yellow()
+            .bold()
+    );
+
+    Ok(())
+}
+
+/// Run the diff command
+fn run_diff(
+    before: &std::path::Path,
+    after: Option<&std::path::Path>,
+    top: usize,
+    format: OutputFormat,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let earlier = ScanSummary::load(before)?;
+    let later = match after {
+        Some(after) => ScanSummary::load(after)?,
+        None => {
+            let rules = RuleSelection::Config.rules(config);
+            let scanner = FileScanner::new(rules).with_context(ScanContext::new(config.clone()));
+            let items = if let OutputFormat::Json = format {
+                scanner.scan()?
+            } else {
+                scanner.with_progress(ProgressBarReporter::new()).scan()?
+            };
+            ScanSummary {
+                scanned_at: Some(chrono::Utc::now()),
+                ..ScanSummary::from_items(items)
+            }
+        }
+    };
+    let diff = ScanDiff::between(&earlier, &later);
+
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    let when = |summary: &ScanSummary| {
+        summary.scanned_at.map_or_else(
+            || "unknown time".to_string(),
+            |at| {
+                at.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            },
+        )
+    };
+    println!(
+        "{}",
+        format!(
+            "\n{} Changes from {} to {}",
+            glyphs().trend,
+            when(&earlier),
+            if after.is_some() {
+                when(&later)
+            } else {
+                "now".to_string()
+            }
+        )
+        .cyan()
+        .bold()
+    );
+    println!("{}", glyphs().double_line(60));
+
+    let changed: Vec<_> = diff.categories.iter().filter(|c| c.delta() != 0).collect();
+    if changed.is_empty() && diff.paths.is_empty() {
+        println!("  No changes.");
+        return Ok(());
+    }
+    for category in &changed {
+        println!(
+            "  {:<20} {:>12} {} {:<12} {}",
+            category.category,
+            bytesize::ByteSize::b(category.before).to_string(),
+            glyphs().arrow,
+            bytesize::ByteSize::b(category.after).to_string(),
+            signed_size(category.delta())
+        );
+    }
+
+    if !diff.paths.is_empty() {
+        println!("\n{}", "Paths:".bold());
+        for path in diff.paths.iter().take(top) {
+            let label = match path.change {
+                PathChangeKind::Added => "added  ".green(),
+                PathChangeKind::Removed => "removed".red(),
+                PathChangeKind::Grew => "grew   ".yellow(),
+                PathChangeKind::Shrank => "shrank ".cyan(),
+            };
+            println!(
+                "  {} {} [{}] {}",
+                label,
+                path.path.display(),
+                path.category,
+                signed_size(path.delta())
+            );
+        }
+        if diff.paths.len() > top {
+            println!("  ... and {} more", diff.paths.len() - top);
+        }
+    }
+
+    println!(
+        "\n{} {} {} {} ({})",
+        "Total:".bold(),
+        bytesize::ByteSize::b(diff.before_size),
+        glyphs().arrow,
+        bytesize::ByteSize::b(diff.after_size),
+        signed_size(diff.delta())
+    );
+
+    Ok(())
+}
+
+/// Format a byte delta with an explicit sign, coloured by direction
+fn signed_size(delta: i64) -> colored::ColoredString {
+    let size = bytesize::ByteSize::b(delta.unsigned_abs());
+    match delta.signum() {
+        1 => format!("+{}", size).yellow().bold(),
+        -1 => format!("-{}", size).green().bold(),
+        _ => size.to_string().normal(),
+    }
+}
+
+/// Run the schedule command
+fn run_schedule(
+    cadence: Cadence,
+    categories: Option<Vec<String>>,
+    remove: bool,
+    dry_run: bool,
+    config: &Config,
+) -> anyhow::Result<()> {
+    if remove {
+        let removed = scheduler::remove()?;
+        if removed.is_empty() {
+            println!("{}", "No schedule is installed.".yellow());
+        } else {
+            println!(
+                "{}",
+                format!("{}  Removed schedule:", glyphs().trash)
+                    .green()
+                    .bold()
+            );
+            for path in removed {
+                println!("  {}", path.display());
+            }
+        }
+        return Ok(());
+    }
+
+    let categories = categories.unwrap_or_else(|| scheduler::low_risk_categories(config));
+    if categories.is_empty() {
+        anyhow::bail!("No categories to schedule");
+    }
+
+    let schedule = Schedule::new(cadence, categories)?;
+
+    if dry_run {
+        for file in schedule.unit_files()? {
+            println!(
+                "{} {}",
+                "#".dimmed(),
+                file.path.display().to_string().dimmed()
+            );
+            println!("{}", file.contents);
+        }
+        return Ok(());
+    }
+
+    let installed = schedule.install()?;
+    println!(
+        "{} {} cleanup of: {}",
+        format!("{} Scheduled", glyphs().clock).green().bold(),
+        schedule.cadence.on_calendar(),
+        schedule.categories.join(", ")
+    );
+    for path in installed {
+        println!("  {}", path.display());
+    }
+    println!(
+        "{}",
+        "Remove it with `cleanmymac-rs schedule --remove`.".dimmed()
+    );
+
+    Ok(())
+}
+
+/// A scan path checked by a rule
+#[derive(Serialize)]
+struct PathDiagnosis {
+    path: std::path::PathBuf,
+    exists: bool,
+}
+
+/// Diagnostic report for a single rule
+#[derive(Serialize)]
+struct RuleDiagnosis {
+    name: String,
+    category: String,
+    /// Name accepted by `--categories`
+    category_slug: String,
+    risk_level: String,
+    applicable: bool,
+    reason: String,
+    paths: Vec<PathDiagnosis>,
+}
+
+/// Run the doctor command
+fn run_doctor(format: OutputFormat, config: &Config) -> anyhow::Result<()> {
+    let diagnoses: Vec<RuleDiagnosis> = get_all_rules_with_config(config)
+        .iter()
+        .map(|rule| {
+            let paths: Vec<PathDiagnosis> = rule
+                .scan_paths()
+                .into_iter()
+                .map(|path| PathDiagnosis {
+                    exists: path.exists(),
+                    path,
+                })
+                .collect();
+            let applicable = rule.is_applicable();
+            let existing = paths.iter().filter(|p| p.exists).count();
+
+            let reason = if applicable {
+                if paths.is_empty() {
+                    "Command-based rule; required tool is available".to_string()
+                } else {
+                    format!("{} of {} scan paths exist", existing, paths.len())
+                }
+            } else if paths.is_empty() {
+                "Required tool or service is not available".to_string()
+            } else if existing == 0 {
+                "None of the scan paths exist".to_string()
+            } else {
+                "Rule-specific check failed although some paths exist".to_string()
+            };
+
+            RuleDiagnosis {
+                name: rule.name().to_string(),
+                category: rule.category().to_string(),
+                category_slug: rule.category().slug(),
+                risk_level: rule.risk_level().to_string(),
+                applicable,
+                reason,
+                paths,
+            }
+        })
+        .collect();
+
+    if let OutputFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&diagnoses)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("\n{} Rule Diagnostics\n", glyphs().doctor)
+            .cyan()
+            .bold()
+    );
+
+    for diagnosis in &diagnoses {
+        let applicable = if diagnosis.applicable {
+            glyphs().check.green()
+        } else {
+            glyphs().cross.red()
+        };
+        println!(
+            "{} {} [{}] - {}",
+            applicable,
+            diagnosis.name.bold(),
+            diagnosis.category,
+            diagnosis.reason.dimmed()
+        );
+        for path in &diagnosis.paths {
+            let marker = if path.exists {
+                "found".green()
+            } else {
+                "missing".dimmed()
+            };
+            println!("    {} {}", marker, path.path.display());
+        }
+    }
+
+    println!(
+        "\n{} {} rules, {} applicable",
+        "Total:".bold(),
+        diagnoses.len(),
+        diagnoses.iter().filter(|d| d.applicable).count()
     );
 
     Ok(())
 }
 
 /// Run the config command
-fn run_config(init: bool, show: bool, path: Option<String>) -> anyhow::Result<()> {
+fn run_config(
+    init: bool,
+    show: bool,
+    validate: bool,
+    edit: bool,
+    path: Option<String>,
+) -> anyhow::Result<()> {
     if init {
         let config_path = if let Some(p) = path {
             std::path::PathBuf::from(p)
@@ -434,9 +2089,31 @@ fn run_config(init: bool, show: bool, path: Option<String>) -> anyhow::Result<()
         config.save_to(&config_path)?;
         println!(
             "{} Configuration saved to: {}",
-            "✅".green(),
+            glyphs().success.green(),
             config_path.display()
         );
+    } else if validate {
+        let config_path = if let Some(p) = path {
+            std::path::PathBuf::from(p)
+        } else {
+            Config::default_path()?
+        };
+        check_config(&config_path)?;
+    } else if edit {
+        let config_path = if let Some(p) = path {
+            std::path::PathBuf::from(p)
+        } else {
+            Config::default_path()?
+        };
+        if !config_path.exists() {
+            Config::default().save_to(&config_path)?;
+            println!(
+                "{} Created {} from defaults",
+                glyphs().success.green(),
+                config_path.display()
+            );
+        }
+        edit_config(&config_path)?;
     } else if show {
         let config = if let Some(p) = path {
             Config::load_from(&p)?
@@ -446,7 +2123,7 @@ fn run_config(init: bool, show: bool, path: Option<String>) -> anyhow::Result<()
 
         let toml_str = toml::to_string_pretty(&config)?;
         println!("{}", "Current Configuration:".bold());
-        println!("{}", "═".repeat(60));
+        println!("{}", glyphs().double_line(60));
         println!("{}", toml_str);
     } else {
         println!("{}", "Configuration Commands:".bold());
@@ -462,14 +2139,83 @@ fn run_config(init: bool, show: bool, path: Option<String>) -> anyhow::Result<()
             "  {} Initialize at custom path",
             "cleanmymac-rs config --init --path <PATH>".cyan()
         );
+        println!(
+            "  {} Check for errors and unknown keys",
+            "cleanmymac-rs config --validate".cyan()
+        );
+        println!(
+            "  {} Open in $EDITOR and check on exit",
+            "cleanmymac-rs config --edit".cyan()
+        );
     }
 
     Ok(())
 }
 
+/// Parse a configuration file, failing on syntax errors and listing
+/// unrecognized keys
+fn check_config(config_path: &std::path::Path) -> anyhow::Result<()> {
+    let content = std::fs::read_to_string(config_path)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", config_path.display(), e))?;
+
+    let unknown = Config::validate_str(&content)
+        .map_err(|e| anyhow::anyhow!("{} is invalid:\n{}", config_path.display(), e))?;
+    if unknown.is_empty() {
+        println!(
+            "{} {} is valid",
+            glyphs().success.green(),
+            config_path.display()
+        );
+    } else {
+        println!(
+            "{} {} is valid, but these keys are not recognized and are ignored:",
+            glyphs().warning.yellow(),
+            config_path.display()
+        );
+        for key in unknown {
+            println!("  {} {}", glyphs().bullet, key);
+        }
+    }
+    Ok(())
+}
+
+/// Open a configuration file in the user's editor and check it on exit,
+/// offering to reopen the editor while it does not parse
+fn edit_config(config_path: &std::path::Path) -> anyhow::Result<()> {
+    let (program, args) = cleanmymac_rs::util::editor_command();
+    loop {
+        let status = std::process::Command::new(&program)
+            .args(&args)
+            .arg(config_path)
+            .status()
+            .map_err(|e| anyhow::anyhow!("Cannot start editor '{}': {}", program, e))?;
+        if !status.success() {
+            anyhow::bail!("Editor '{}' exited with {}", program, status);
+        }
+
+        let Err(e) = check_config(config_path) else {
+            return Ok(());
+        };
+        eprintln!("{} {}", glyphs().failure.red(), e);
+        let again = std::io::stdin().is_terminal()
+            && Confirm::new()
+                .with_prompt("Open the editor again?")
+                .default(true)
+                .interact()?;
+        if !again {
+            anyhow::bail!(
+                "{} has errors; defaults are used until it is fixed",
+                config_path.display()
+            );
+        }
+    }
+}
+
 /// Run TUI mode
-fn run_tui() -> anyhow::Result<()> {
-    let mut app = App::new();
+///
+/// Settings changed in the TUI are saved to `config_path`.
+fn run_tui(config: Config, config_path: Option<std::path::PathBuf>) -> anyhow::Result<()> {
+    let mut app = App::with_config(config, config_path);
     app.run()?;
     Ok(())
 }
@@ -0,0 +1,102 @@
+//! Home, config, cache and data directory resolution
+//!
+//! Wraps `dirs` so that `$HOME` (and on Linux `$XDG_*_HOME`) always take
+//! precedence, and so a missing home directory is reported once instead of
+//! every rule silently finding nothing.
+
+use std::path::PathBuf;
+use std::sync::Once;
+
+/// Guards the missing-home warning so it is logged only once per process
+static HOME_WARNING: Once = Once::new();
+
+/// An absolute directory from environment variable `var`
+///
+/// Empty and relative values are ignored, as the XDG spec requires.
+pub(crate) fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+/// An XDG base directory override; only Linux and the BSDs follow XDG
+fn xdg_dir(var: &str) -> Option<PathBuf> {
+    if cfg!(all(unix, not(target_os = "macos"))) {
+        env_dir(var)
+    } else {
+        None
+    }
+}
+
+/// The user's home directory
+///
+/// Prefers `$HOME`, then the platform lookup. Logs a single warning when
+/// neither is available, e.g. in some containers and CI sandboxes.
+pub fn home_dir() -> Option<PathBuf> {
+    let home = env_dir("HOME").or_else(dirs::home_dir);
+    if home.is_none() {
+        HOME_WARNING.call_once(|| {
+            tracing::warn!(
+                "Could not determine the home directory; rules that look under it will find \
+                 nothing. Set $HOME to fix this."
+            );
+        });
+    }
+    home
+}
+
+/// The user's cache directory (e.g. ~/.cache or ~/Library/Caches)
+pub fn cache_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CACHE_HOME")
+        .or_else(dirs::cache_dir)
+        .or_else(|| home_dir().map(|home| home.join(".cache")))
+}
+
+/// The user's config directory (e.g. ~/.config or ~/Library/Application Support)
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME")
+        .or_else(dirs::config_dir)
+        .or_else(|| home_dir().map(|home| home.join(".config")))
+}
+
+/// The user's local data directory (e.g. ~/.local/share or %LOCALAPPDATA%)
+pub fn data_local_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME")
+        .or_else(dirs::data_local_dir)
+        .or_else(|| home_dir().map(|home| home.join(".local/share")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    #[test]
+    fn test_home_env_override() {
+        let home = tempfile::tempdir().unwrap();
+        with_env(&[("HOME", Some(home.path()))], || {
+            assert_eq!(home_dir().as_deref(), Some(home.path()));
+            assert!(config_dir().is_some());
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_xdg_overrides() {
+        let cache = tempfile::tempdir().unwrap();
+        let config = tempfile::tempdir().unwrap();
+        let data = tempfile::tempdir().unwrap();
+        with_env(
+            &[
+                ("XDG_CACHE_HOME", Some(cache.path())),
+                ("XDG_CONFIG_HOME", Some(config.path())),
+                ("XDG_DATA_HOME", Some(data.path())),
+            ],
+            || {
+                assert_eq!(cache_dir().as_deref(), Some(cache.path()));
+                assert_eq!(config_dir().as_deref(), Some(config.path()));
+                assert_eq!(data_local_dir().as_deref(), Some(data.path()));
+            },
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Low-priority mode for background runs (`--low-priority`, `general.io_nice`)
+//!
+//! Lowers the CPU and I/O priority of the process so a scheduled clean does
+//! not compete with interactive work. Must be called before any worker
+//! threads are started: threads inherit the priority of their creator.
+
+/// Niceness applied on Unix (0 is normal, 19 the lowest priority)
+#[cfg(all(unix, not(target_os = "macos")))]
+const NICE_LEVEL: libc::c_int = 10;
+
+/// Lower the CPU and I/O priority of the current process
+///
+/// On Linux this sets the niceness and the lowest best-effort I/O priority
+/// (like `nice -n 10 ionice -c2 -n7`); on macOS it moves the process into the
+/// background QoS band, which throttles both CPU and disk. Other platforms
+/// return an `Unsupported` error.
+pub fn lower() -> std::io::Result<()> {
+    imp::lower()
+}
+
+/// Apply background scheduling to the calling thread, for thread pools that
+/// may have been started before [`lower`] (a no-op where not needed)
+pub fn background_thread() {
+    imp::background_thread()
+}
+
+/// Worker threads to use in low-priority mode: half the available cores
+pub fn low_priority_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| (n.get() / 2).max(1))
+        .unwrap_or(1)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    /// `IOPRIO_WHO_PROCESS` from `linux/ioprio.h`
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    /// Best-effort class, lowest level
+    const IOPRIO_BE_LOWEST: libc::c_int = (2 << 13) | 7;
+
+    pub fn lower() -> std::io::Result<()> {
+        super::nice()?;
+        // SAFETY: plain syscall without pointers
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_BE_LOWEST,
+            )
+        };
+        if ret == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub fn background_thread() {}
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    pub fn lower() -> std::io::Result<()> {
+        // SAFETY: plain syscall without pointers
+        let ret = unsafe { libc::setpriority(libc::PRIO_DARWIN_PROCESS, 0, libc::PRIO_DARWIN_BG) };
+        if ret == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        background_thread();
+        Ok(())
+    }
+
+    pub fn background_thread() {
+        // SAFETY: only changes the calling thread's QoS class
+        unsafe {
+            libc::pthread_set_qos_class_self_np(libc::qos_class_t::QOS_CLASS_BACKGROUND, 0);
+        }
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+mod imp {
+    pub fn lower() -> std::io::Result<()> {
+        super::nice()
+    }
+
+    pub fn background_thread() {}
+}
+
+#[cfg(not(unix))]
+mod imp {
+    pub fn lower() -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "lowering the process priority is not supported on this platform",
+        ))
+    }
+
+    pub fn background_thread() {}
+}
+
+/// Raise the niceness of the process to [`NICE_LEVEL`]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn nice() -> std::io::Result<()> {
+    // SAFETY: plain syscall without pointers
+    let ret = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, NICE_LEVEL) };
+    if ret == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lower_sets_niceness_and_io_priority() {
+        // Runs on its own thread: Linux applies both to the calling thread only
+        std::thread::spawn(|| {
+            lower().unwrap();
+            // SAFETY: plain syscalls without pointers
+            let (nice, ioprio) = unsafe {
+                (
+                    libc::getpriority(libc::PRIO_PROCESS, 0),
+                    libc::syscall(libc::SYS_ioprio_get, 1, 0),
+                )
+            };
+            assert!(nice >= NICE_LEVEL, "nice {}", nice);
+            assert_eq!(ioprio, (2 << 13) | 7);
+        })
+        .join()
+        .unwrap();
+        assert!(low_priority_threads() >= 1);
+    }
+}
@@ -0,0 +1,303 @@
+//! Browser cache rules
+//!
+//! Firefox keeps each profile's disposable caches in a separate local
+//! directory (`~/Library/Caches/Firefox/Profiles/<profile>` on macOS,
+//! `~/.cache/mozilla/firefox/<profile>` on Linux, `%LOCALAPPDATA%` on Windows),
+//! away from history, cookies and logins. Only the named cache folders inside
+//! it are ever reported, never a profile directory itself.
+
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, remove_path};
+use std::path::{Path, PathBuf};
+
+/// Cache folders inside a profile's local directory that are safe to delete
+const FIREFOX_CACHE_DIRS: &[&str] = &["cache2", "startupCache", "shader-cache"];
+
+/// A profile listed in `profiles.ini`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FirefoxProfile {
+    /// `Name=`, shown in item descriptions
+    name: String,
+    /// `Path=`, relative to the profiles folder unless `IsRelative=0`
+    path: PathBuf,
+    /// `IsRelative=`
+    relative: bool,
+}
+
+/// Parse the `[ProfileN]` sections of a Firefox `profiles.ini`
+fn parse_profiles_ini(content: &str) -> Vec<FirefoxProfile> {
+    let mut profiles = Vec::new();
+    let mut current: Option<FirefoxProfile> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            profiles.extend(current.take().filter(|p| !p.path.as_os_str().is_empty()));
+            if line.starts_with("[Profile") {
+                current = Some(FirefoxProfile {
+                    name: String::new(),
+                    path: PathBuf::new(),
+                    relative: true,
+                });
+            }
+            continue;
+        }
+        let (Some(profile), Some((key, value))) = (current.as_mut(), line.split_once('=')) else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => profile.name = value.trim().to_string(),
+            "Path" => profile.path = PathBuf::from(value.trim()),
+            "IsRelative" => profile.relative = value.trim() != "0",
+            _ => {}
+        }
+    }
+    profiles.extend(current.filter(|p| !p.path.as_os_str().is_empty()));
+    profiles
+}
+
+/// Folder holding `profiles.ini` and the profiles' data
+fn firefox_profiles_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        crate::paths::home_dir().map(|home| home.join("Library/Application Support/Firefox"))
+    } else if cfg!(windows) {
+        crate::paths::config_dir().map(|dir| dir.join("Mozilla/Firefox"))
+    } else {
+        crate::paths::home_dir().map(|home| home.join(".mozilla/firefox"))
+    }
+}
+
+/// Folder holding the profiles' local (cache) directories
+fn firefox_cache_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "macos") {
+        crate::paths::home_dir().map(|home| home.join("Library/Caches/Firefox"))
+    } else if cfg!(windows) {
+        crate::paths::data_local_dir().map(|dir| dir.join("Mozilla/Firefox"))
+    } else {
+        crate::paths::cache_dir().map(|dir| dir.join("mozilla/firefox"))
+    }
+}
+
+/// Firefox cache rule
+///
+/// Enumerates profiles from `profiles.ini` and reports each profile's
+/// `cache2`, `startupCache` and `shader-cache` folders. Profile data such as
+/// `places.sqlite`, cookies and logins lives elsewhere and is never touched.
+pub struct FirefoxCacheRule;
+
+impl FirefoxCacheRule {
+    /// Local directory of each profile that exists, with the profile's name
+    fn profile_cache_dirs(&self) -> Vec<(String, PathBuf)> {
+        let (Some(profiles_dir), Some(cache_dir)) = (firefox_profiles_dir(), firefox_cache_dir())
+        else {
+            return Vec::new();
+        };
+        let Ok(content) = std::fs::read_to_string(profiles_dir.join("profiles.ini")) else {
+            return Vec::new();
+        };
+
+        parse_profiles_ini(&content)
+            .into_iter()
+            .filter_map(|profile| {
+                // Profiles outside the default folder keep their caches inside
+                let local = if profile.relative {
+                    cache_dir.join(&profile.path)
+                } else {
+                    profile.path.clone()
+                };
+                local.is_dir().then(|| (profile_label(&profile), local))
+            })
+            .collect()
+    }
+}
+
+/// Profile name for descriptions, falling back to its folder name
+fn profile_label(profile: &FirefoxProfile) -> String {
+    if !profile.name.is_empty() {
+        return profile.name.clone();
+    }
+    profile
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The cache folders under a profile's local directory
+fn cache_folders(local: &Path) -> impl Iterator<Item = (&'static str, PathBuf)> + '_ {
+    FIREFOX_CACHE_DIRS
+        .iter()
+        .map(move |sub| (*sub, local.join(sub)))
+}
+
+impl CleanRule for FirefoxCacheRule {
+    fn id(&self) -> &str {
+        "firefox_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Firefox Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Other("Browser".to_string())
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Firefox page, startup and shader caches of every profile (history, cookies and logins are kept)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        !self.profile_cache_dirs().is_empty()
+    }
+
+    // The whole cache folder, so `UserCacheRule` leaves `~/.cache/mozilla` alone
+    // even before any profile exists, plus profiles kept outside it
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = firefox_cache_dir().into_iter().collect();
+        for (_, local) in self.profile_cache_dirs() {
+            if !paths.iter().any(|path| local.starts_with(path)) {
+                paths.push(local);
+            }
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let min_size = ctx.min_size(self, 10);
+
+        for (profile, local) in self.profile_cache_dirs() {
+            for (sub, path) in cache_folders(&local) {
+                if !path.is_dir() {
+                    continue;
+                }
+                let size = ctx.dir_size(&path);
+                if size > min_size {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        format!("Firefox {} ({})", sub, profile),
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+
+        for item in items {
+            match remove_path(&item.path, to_trash) {
+                Ok(_) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += item.size;
+                }
+                Err(e) => {
+                    result.failed.push((item.path.clone(), e.to_string()));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    const PROFILES_INI: &str = "\
+[Install4F96D1932A9F858E]
+Default=Profiles/abcd1234.default-release
+Locked=1
+
+[Profile1]
+Name=default
+IsRelative=1
+Path=Profiles/wxyz9876.default
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/abcd1234.default-release
+Default=1
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+
+    #[test]
+    fn test_parse_profiles_ini() {
+        let profiles = parse_profiles_ini(PROFILES_INI);
+        assert_eq!(
+            profiles,
+            [
+                FirefoxProfile {
+                    name: "default".to_string(),
+                    path: PathBuf::from("Profiles/wxyz9876.default"),
+                    relative: true,
+                },
+                FirefoxProfile {
+                    name: "default-release".to_string(),
+                    path: PathBuf::from("Profiles/abcd1234.default-release"),
+                    relative: true,
+                },
+            ]
+        );
+        let absolute = parse_profiles_ini("[Profile0]\nIsRelative=0\nPath=/data/ff\n");
+        assert!(!absolute[0].relative);
+    }
+
+    #[test]
+    fn test_firefox_rule_reports_only_cache_folders() {
+        let home = tempfile::tempdir().unwrap();
+        with_env(
+            &[
+                ("HOME", Some(home.path())),
+                ("XDG_CACHE_HOME", None),
+                ("XDG_CONFIG_HOME", None),
+            ],
+            || {
+                let profiles_dir = firefox_profiles_dir().unwrap();
+                let cache_dir = firefox_cache_dir().unwrap();
+                std::fs::create_dir_all(&profiles_dir).unwrap();
+                std::fs::write(profiles_dir.join("profiles.ini"), PROFILES_INI).unwrap();
+
+                // Profile data, which must never be reported
+                let data = profiles_dir.join("Profiles/abcd1234.default-release");
+                std::fs::create_dir_all(&data).unwrap();
+                for file in ["places.sqlite", "cookies.sqlite", "logins.json"] {
+                    std::fs::write(data.join(file), vec![0u8; 64]).unwrap();
+                }
+
+                let local = cache_dir.join("Profiles/abcd1234.default-release");
+                for sub in ["cache2/entries", "startupCache", "safebrowsing"] {
+                    std::fs::create_dir_all(local.join(sub)).unwrap();
+                    std::fs::write(local.join(sub).join("data"), vec![0u8; 64]).unwrap();
+                }
+
+                let rule = FirefoxCacheRule;
+                assert!(rule.is_applicable());
+                let ctx = ScanContext::default().include_small(true);
+                let mut paths: Vec<_> = rule
+                    .scan(&ctx)
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| item.path)
+                    .collect();
+                paths.sort();
+                assert_eq!(paths, [local.join("cache2"), local.join("startupCache")]);
+                assert!(paths.iter().all(|path| !path.starts_with(&data)));
+            },
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Context shared with rules while scanning
+
+use super::{CleanItem, CleanRule};
+use crate::config::Config;
+use crate::scanner::fs_util::dir_size_reporting;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Settings a rule may consult while scanning
+///
+/// Clones share the list of skipped paths, so it can be read back after a
+/// parallel scan, and the memoized applicability of each rule.
+#[derive(Debug, Clone, Default)]
+pub struct ScanContext {
+    /// Active configuration
+    pub config: Config,
+    /// Report items of any size, ignoring every rule's threshold
+    pub include_small: bool,
+    /// Skip heuristic detections below this confidence (0.0 - 1.0)
+    pub min_confidence: f64,
+    /// Only report items last modified more than this many days ago
+    pub min_age_days: Option<u32>,
+    /// Items at, below or containing these paths are never reported (`ignore.paths`)
+    ignored: Vec<PathBuf>,
+    /// Paths skipped because they could not be read
+    skipped: Arc<Mutex<Vec<PathBuf>>>,
+    /// Paths of items left out for being newer than `min_age_days`
+    too_recent: Arc<Mutex<Vec<PathBuf>>>,
+    /// `is_applicable` results by rule id
+    applicable: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+impl ScanContext {
+    /// Create a scan context from a configuration
+    pub fn new(config: Config) -> Self {
+        Self {
+            include_small: false,
+            min_confidence: 0.0,
+            min_age_days: None,
+            ignored: config
+                .ignore
+                .paths
+                .iter()
+                .map(|path| crate::util::expand_tilde(&path.to_string_lossy()))
+                .collect(),
+            config,
+            skipped: Arc::default(),
+            too_recent: Arc::default(),
+            applicable: Arc::default(),
+        }
+    }
+
+    /// Set whether thresholds are ignored so small caches are reported too
+    pub fn include_small(mut self, value: bool) -> Self {
+        self.include_small = value;
+        self
+    }
+
+    /// Set the minimum confidence of heuristic detections
+    pub fn min_confidence(mut self, value: f64) -> Self {
+        self.min_confidence = value;
+        self
+    }
+
+    /// Whether `rule` applies, checked once per rule until
+    /// [`Self::clear_applicability`]
+    ///
+    /// Checks stat paths or spawn tools such as docker, so repeated calls
+    /// during one scan reuse the first answer.
+    pub fn is_applicable(&self, rule: &dyn CleanRule) -> bool {
+        let known = self
+            .applicable
+            .lock()
+            .ok()
+            .and_then(|map| map.get(rule.id()).copied());
+        if let Some(known) = known {
+            return known;
+        }
+        // Checked without holding the lock so rules are checked in parallel
+        let applicable = rule.is_applicable();
+        if let Ok(mut map) = self.applicable.lock() {
+            map.insert(rule.id().to_string(), applicable);
+        }
+        applicable
+    }
+
+    /// Forget memoized applicability, e.g. before a new scan
+    pub fn clear_applicability(&self) {
+        if let Ok(mut map) = self.applicable.lock() {
+            map.clear();
+        }
+    }
+
+    /// Size of `path`, recording any subpaths skipped for lack of permission
+    pub fn dir_size(&self, path: &Path) -> u64 {
+        dir_size_reporting(path, |denied| self.record_skipped(denied))
+    }
+
+    /// Record a path that could not be scanned because permission was denied
+    pub fn record_skipped(&self, path: &Path) {
+        if let Ok(mut skipped) = self.skipped.lock() {
+            skipped.push(path.to_path_buf());
+        }
+    }
+
+    /// Paths skipped so far, sorted and deduplicated
+    pub fn skipped_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.skipped.lock().map(|s| s.clone()).unwrap_or_default();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    /// Forget previously recorded skipped paths
+    pub fn clear_skipped(&self) {
+        if let Ok(mut skipped) = self.skipped.lock() {
+            skipped.clear();
+        }
+    }
+
+    /// Only report items older than `days` days; items without a modification
+    /// time, such as command-driven ones, are then left out
+    pub fn min_age(mut self, days: Option<u32>) -> Self {
+        self.min_age_days = days;
+        self
+    }
+
+    /// Directories searched for per-project build output
+    pub fn project_dirs(&self) -> Vec<PathBuf> {
+        self.config.scan.project_search_dirs()
+    }
+
+    /// Whether `path` lies under one of the `ignore.paths`, or contains one
+    /// so that cleaning it would remove the ignored path too
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignored
+            .iter()
+            .any(|ignored| path.starts_with(ignored) || ignored.starts_with(path))
+    }
+
+    /// Drop the items a rule found that are ignored or too recent
+    ///
+    /// Applied by the scanner to every rule's results, so rules need not
+    /// check these settings themselves.
+    pub fn filter_items(&self, items: Vec<CleanItem>) -> Vec<CleanItem> {
+        items
+            .into_iter()
+            .filter(|item| !self.is_ignored(&item.path))
+            .filter_map(|item| match self.min_age_days {
+                Some(days) => {
+                    let item = item.with_path_mtime();
+                    if item.is_stale(days) {
+                        Some(item)
+                    } else {
+                        if let Ok(mut too_recent) = self.too_recent.lock() {
+                            too_recent.push(item.path);
+                        }
+                        None
+                    }
+                }
+                None => Some(item),
+            })
+            .collect()
+    }
+
+    /// Paths of items [`Self::filter_items`] left out as too recent so far
+    pub fn too_recent_paths(&self) -> Vec<PathBuf> {
+        self.too_recent
+            .lock()
+            .map(|p| p.clone())
+            .unwrap_or_default()
+    }
+
+    /// Minimum size in bytes an item found by `rule` must exceed
+    ///
+    /// Uses the `[thresholds]` entry for the rule (by id or name) or its
+    /// category, falling back to `default_mb`. Always zero with `include_small`.
+    pub fn min_size(&self, rule: &dyn CleanRule, default_mb: u64) -> u64 {
+        if self.include_small {
+            return 0;
+        }
+        let category = rule.category().to_string();
+        self.config
+            .thresholds
+            .min_size_mb(rule.id(), rule.name(), &category)
+            .unwrap_or(default_mb)
+            * 1024
+            * 1024
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        CargoTargetRule, Category, GoCacheRule, RustupCacheRule, VSCodeExtensionsRule,
+    };
+    use crate::test_support::{env_read, with_env};
+
+    #[test]
+    fn test_default_threshold() {
+        let _env = env_read();
+        let ctx = ScanContext::default();
+        assert_eq!(ctx.min_size(&CargoTargetRule, 50), 50 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_rust_threshold_override() {
+        let _env = env_read();
+        let config: Config = toml::from_str(
+            r#"
+            [thresholds.categories]
+            rust = 5
+
+            [thresholds.rules]
+            rustup_toolchains = 200
+            "Go Module Cache" = 3
+            "#,
+        )
+        .unwrap();
+        let ctx = ScanContext::new(config);
+
+        // Category override applies to every Rust rule
+        assert_eq!(ctx.min_size(&CargoTargetRule, 50), 5 * 1024 * 1024);
+        // A rule entry wins over its category
+        assert_eq!(ctx.min_size(&RustupCacheRule, 500), 200 * 1024 * 1024);
+        // Display names still work
+        assert_eq!(ctx.min_size(&GoCacheRule, 0), 3 * 1024 * 1024);
+        // Other categories keep their default
+        assert_eq!(ctx.min_size(&VSCodeExtensionsRule, 0), 0);
+    }
+
+    #[test]
+    fn test_include_small_reports_caches_below_threshold() {
+        let home = tempfile::tempdir().unwrap();
+        let extensions = home.path().join(".vscode/extensions");
+        std::fs::create_dir_all(&extensions).unwrap();
+        std::fs::write(extensions.join("ext.vsix"), vec![0u8; 1024 * 1024]).unwrap();
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let ctx = ScanContext::default();
+            assert!(VSCodeExtensionsRule.scan(&ctx).unwrap().is_empty());
+
+            let items = VSCodeExtensionsRule.scan(&ctx.include_small(true)).unwrap();
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].path, extensions);
+        });
+    }
+
+    #[test]
+    fn test_filter_items_drops_ignored_and_recent_items() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept");
+        let ignored = dir.path().join("ignored");
+        for path in [&kept, &ignored] {
+            std::fs::create_dir_all(path.join("cache")).unwrap();
+        }
+        let item = |path: PathBuf| {
+            CleanItem::new(
+                path,
+                1,
+                "test",
+                crate::rules::RiskLevel::Low,
+                Category::System,
+            )
+        };
+        let items = || {
+            vec![
+                item(kept.join("cache")),
+                item(ignored.join("cache")),
+                item(ignored.clone()),
+                // Holds the ignored path, so cleaning it would remove that too
+                item(dir.path().to_path_buf()),
+            ]
+        };
+
+        // Defaults keep everything
+        assert_eq!(ScanContext::default().filter_items(items()).len(), 4);
+
+        let mut config = Config::default();
+        config.ignore.paths = vec![ignored.clone()];
+        let ctx = ScanContext::new(config);
+        let paths: Vec<_> = ctx
+            .filter_items(items())
+            .into_iter()
+            .map(|item| item.path)
+            .collect();
+        assert_eq!(paths, [kept.join("cache")]);
+
+        // Everything here was just created
+        let ctx = ctx.min_age(Some(1));
+        assert!(ctx.filter_items(items()).is_empty());
+        assert_eq!(ctx.too_recent_paths(), [kept.join("cache")]);
+    }
+
+    #[test]
+    fn test_skipped_paths_shared_between_clones() {
+        let _env = env_read();
+        let ctx = ScanContext::default();
+        let clone = ctx.clone();
+        clone.record_skipped(Path::new("/var/cache/b"));
+        clone.record_skipped(Path::new("/var/cache/a"));
+        clone.record_skipped(Path::new("/var/cache/b"));
+
+        assert_eq!(
+            ctx.skipped_paths(),
+            [PathBuf::from("/var/cache/a"), PathBuf::from("/var/cache/b")]
+        );
+        ctx.clear_skipped();
+        assert!(clone.skipped_paths().is_empty());
+    }
+}
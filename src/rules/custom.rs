@@ -0,0 +1,279 @@
+//! User-defined cleanup rules loaded from the config file
+
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, remove_path};
+use crate::config::CustomRuleConfig;
+use globset::GlobBuilder;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Characters that make a path component a glob pattern
+const GLOB_CHARS: &[char] = &['*', '?', '[', '{'];
+
+/// Rule built from a `[[custom_rules]]` config entry
+pub struct CustomRule {
+    /// Rule definition from the config
+    config: CustomRuleConfig,
+    /// Parsed category
+    category: Category,
+    /// Configured id, or one derived from the name
+    id: String,
+}
+
+impl CustomRule {
+    /// Create a rule from its config entry
+    pub fn new(config: CustomRuleConfig) -> Self {
+        let category = Category::from_name(&config.category);
+        let id = config
+            .id
+            .clone()
+            .unwrap_or_else(|| format!("custom_{}", id_from_name(&config.name)));
+        Self {
+            config,
+            category,
+            id,
+        }
+    }
+}
+
+/// Lowercase `name` with every run of other characters replaced by `_`
+fn id_from_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+impl CleanRule for CustomRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn category(&self) -> Category {
+        self.category.clone()
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        self.config.risk
+    }
+
+    fn description(&self) -> &str {
+        "User-defined rule from the config file"
+    }
+
+    fn is_applicable(&self) -> bool {
+        !self.scan_paths().is_empty()
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self
+            .config
+            .paths
+            .iter()
+            .flat_map(|pattern| expand_glob(pattern))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let min_size = ctx.min_size(self, self.config.min_size_mb);
+
+        for path in self.scan_paths() {
+            let size = if path.is_dir() {
+                ctx.dir_size(&path)
+            } else {
+                path.symlink_metadata().map(|m| m.len()).unwrap_or(0)
+            };
+            if size > 0 && size >= min_size {
+                items.push(CleanItem::new(
+                    path,
+                    size,
+                    format!("Custom rule: {}", self.config.name),
+                    self.risk_level(),
+                    self.category(),
+                ));
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+
+        for item in items {
+            let clean_result = remove_path(&item.path, to_trash);
+
+            match clean_result {
+                Ok(_) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += item.size;
+                }
+                Err(e) => {
+                    result.failed.push((item.path.clone(), e.to_string()));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Expand `~` and glob patterns into the existing paths they match
+///
+/// Relative patterns match nothing: they would depend on the directory the
+/// tool happens to run from.
+pub fn expand_glob(pattern: &str) -> Vec<PathBuf> {
+    let expanded = match pattern.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match crate::paths::home_dir() {
+            Some(home) => format!("{}{}", home.display(), rest),
+            None => return Vec::new(),
+        },
+        _ => pattern.to_string(),
+    };
+    let path = Path::new(&expanded);
+    if !path.is_absolute() {
+        tracing::warn!(
+            "Ignoring custom rule path {}: use an absolute or ~/ path",
+            pattern
+        );
+        return Vec::new();
+    }
+
+    // Walk from the longest prefix without glob characters
+    let mut base = PathBuf::new();
+    let mut glob_components = 0usize;
+    for component in path.components() {
+        let is_glob = matches!(component, Component::Normal(c)
+            if c.to_string_lossy().contains(GLOB_CHARS));
+        if glob_components > 0 || is_glob {
+            glob_components += 1;
+        } else {
+            base.push(component);
+        }
+    }
+
+    if glob_components == 0 {
+        return if path.exists() {
+            vec![path.to_path_buf()]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let Ok(glob) = GlobBuilder::new(&expanded).literal_separator(true).build() else {
+        tracing::warn!("Invalid glob pattern: {}", pattern);
+        return Vec::new();
+    };
+    let matcher = glob.compile_matcher();
+
+    let mut walker = WalkDir::new(&base).follow_links(false).min_depth(1);
+    if !expanded.contains("**") {
+        walker = walker.max_depth(glob_components);
+    }
+
+    let mut matches = Vec::new();
+    let mut entries = walker.into_iter();
+    while let Some(entry) = entries.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if matcher.is_match(entry.path()) {
+            matches.push(entry.path().to_path_buf());
+            // A matched directory is cleaned as a whole
+            if entry.file_type().is_dir() {
+                entries.skip_current_dir();
+            }
+        }
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::rules::get_all_rules_with_config;
+    use crate::test_support::{env_read, with_current_dir};
+
+    fn temp_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("logs/nested")).unwrap();
+        std::fs::write(root.join("logs/a.log"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("logs/b.log"), vec![0u8; 50]).unwrap();
+        std::fs::write(root.join("logs/keep.txt"), vec![0u8; 10]).unwrap();
+        std::fs::write(root.join("logs/nested/c.log"), vec![0u8; 25]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = temp_tree();
+        let root = dir.path().display();
+
+        let mut flat = expand_glob(&format!("{}/logs/*.log", root));
+        flat.sort();
+        assert_eq!(
+            flat,
+            [dir.path().join("logs/a.log"), dir.path().join("logs/b.log")]
+        );
+
+        assert_eq!(expand_glob(&format!("{}/**/*.log", root)).len(), 3);
+        assert_eq!(
+            expand_glob(&format!("{}/logs", root)),
+            [dir.path().join("logs")]
+        );
+        assert!(expand_glob(&format!("{}/missing/*", root)).is_empty());
+    }
+
+    #[test]
+    fn test_relative_patterns_match_nothing() {
+        let dir = temp_tree();
+        with_current_dir(dir.path(), || {
+            assert!(expand_glob("logs/*.log").is_empty());
+            assert!(expand_glob("logs").is_empty());
+            assert!(expand_glob("~logs").is_empty());
+        });
+    }
+
+    #[test]
+    fn test_custom_rule_from_config() {
+        let _env = env_read();
+        let dir = temp_tree();
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [[custom_rules]]
+            name = "Old logs"
+            paths = ["{}/logs/*.log"]
+            category = "logs"
+            risk = "low"
+            "#,
+            dir.path().display()
+        ))
+        .unwrap();
+
+        let rules = get_all_rules_with_config(&config);
+        let rule = rules.iter().find(|r| r.name() == "Old logs").unwrap();
+        assert_eq!(rule.category(), Category::Other("logs".to_string()));
+        assert_eq!(rule.risk_level(), RiskLevel::Low);
+        assert!(rule.is_applicable());
+
+        let items = rule.scan(&ScanContext::new(config.clone())).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items.iter().map(|i| i.size).sum::<u64>(), 150);
+
+        let result = rule.clean(&items, false).unwrap();
+        assert_eq!(result.cleaned_count, 2);
+        assert!(!dir.path().join("logs/a.log").exists());
+        assert!(dir.path().join("logs/keep.txt").exists());
+    }
+}
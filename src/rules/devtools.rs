@@ -1,11 +1,17 @@
 //! Cross-platform development tools cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
-use std::path::PathBuf;
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, PrunableRule, RiskLevel, ScanContext, ScanCost,
+    clean_prunable, prune_item, remove_path,
+};
+use crate::config::{Config, PruneConfig, PrunePolicy, ScanConfig};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
 use walkdir::WalkDir;
 
 /// Get all development tools rules
-pub fn get_devtools_rules() -> Vec<Box<dyn CleanRule>> {
+pub fn get_devtools_rules(config: &Config) -> Vec<Box<dyn CleanRule>> {
     vec![
         // Node.js
         Box::new(NpmCacheRule),
@@ -14,58 +20,60 @@ pub fn get_devtools_rules() -> Vec<Box<dyn CleanRule>> {
         Box::new(NvmCacheRule),
         Box::new(BunCacheRule),
         Box::new(DenoCacheRule),
+        Box::new(NodeModulesRule::new(&config.scan)),
         // Python
-        Box::new(PipCacheRule),
+        Box::new(PipCacheRule::new(&config.prune)),
         Box::new(UvCacheRule),
         Box::new(CondaCacheRule),
+        Box::new(PoetryCacheRule),
+        Box::new(PipenvCacheRule),
+        Box::new(PyenvVersionsRule),
+        Box::new(MlCacheRule),
         // Rust
         Box::new(CargoCacheRule),
         Box::new(CargoTargetRule),
         Box::new(RustupCacheRule),
         // Go
         Box::new(GoCacheRule),
+        Box::new(GoBuildCacheRule::default()),
         // Java
-        Box::new(GradleCacheRule),
+        Box::new(GradleCacheRule::new(&config.prune)),
         Box::new(MavenCacheRule),
         // Android
         Box::new(AndroidCacheRule),
-        // Docker
-        Box::new(DockerCacheRule),
         // IDE & Editors
         Box::new(VSCodeCacheRule),
+        Box::new(VSCodeWorkspaceStorageRule),
+        Box::new(VSCodeExtensionsRule),
         Box::new(CursorCacheRule),
+        Box::new(CursorWorkspaceStorageRule),
+        Box::new(CursorExtensionsRule),
         Box::new(JetBrainsCacheRule),
+        Box::new(LspCacheRule),
         // Mobile
         Box::new(FlutterCacheRule),
         Box::new(DartPubCacheRule),
         // Ruby
         Box::new(RubyCacheRule),
+        // .NET
+        Box::new(NuGetCacheRule),
+        Box::new(DotNetBuildRule::new(&config.scan)),
+        // PHP
+        Box::new(ComposerCacheRule),
+        // Bazel
+        Box::new(BazelCacheRule),
+        // Haskell & OCaml
+        Box::new(CabalStackCacheRule),
+        Box::new(OpamCacheRule),
     ]
 }
 
-/// Calculate directory size recursively
-fn dir_size(path: &std::path::Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|m| m.len())
-        .sum()
-}
-
 /// Common function to clean items
-fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
     let mut result = CleanResult::default();
 
     for item in items {
-        let clean_result = if to_trash {
-            trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-        } else if item.path.is_dir() {
-            std::fs::remove_dir_all(&item.path)
-        } else {
-            std::fs::remove_file(&item.path)
-        };
+        let clean_result = remove_path(&item.path, to_trash);
 
         match clean_result {
             Ok(_) => {
@@ -87,6 +95,10 @@ fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResul
 pub struct NpmCacheRule;
 
 impl CleanRule for NpmCacheRule {
+    fn id(&self) -> &str {
+        "npm_cache"
+    }
+
     fn name(&self) -> &str {
         "npm Cache"
     }
@@ -109,18 +121,18 @@ impl CleanRule for NpmCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".npm/_cacache"));
             paths.push(home.join(".npm/_logs"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     let desc = if path.to_string_lossy().contains("_logs") {
                         "npm logs"
@@ -140,7 +152,7 @@ impl CleanRule for NpmCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -149,6 +161,10 @@ impl CleanRule for NpmCacheRule {
 pub struct YarnCacheRule;
 
 impl CleanRule for YarnCacheRule {
+    fn id(&self) -> &str {
+        "yarn_cache"
+    }
+
     fn name(&self) -> &str {
         "Yarn Cache"
     }
@@ -171,18 +187,18 @@ impl CleanRule for YarnCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".yarn/cache"));
             paths.push(home.join(".cache/yarn"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -197,7 +213,7 @@ impl CleanRule for YarnCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -206,6 +222,10 @@ impl CleanRule for YarnCacheRule {
 pub struct PnpmCacheRule;
 
 impl CleanRule for PnpmCacheRule {
+    fn id(&self) -> &str {
+        "pnpm_store"
+    }
+
     fn name(&self) -> &str {
         "pnpm Store"
     }
@@ -228,18 +248,18 @@ impl CleanRule for PnpmCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".pnpm-store"));
             paths.push(home.join(".local/share/pnpm/store"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -254,7 +274,7 @@ impl CleanRule for PnpmCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -262,9 +282,26 @@ impl CleanRule for PnpmCacheRule {
 // ============ Python Rules ============
 
 /// pip cache rule
-pub struct PipCacheRule;
+#[derive(Default)]
+pub struct PipCacheRule {
+    /// Policy from `[prune.rules.pip_cache]`, if any
+    prune: Option<PrunePolicy>,
+}
+
+impl PipCacheRule {
+    /// Create the rule with its prune policy from `config`
+    pub fn new(config: &PruneConfig) -> Self {
+        let mut rule = Self::default();
+        rule.prune = config.policy(rule.id(), rule.name());
+        rule
+    }
+}
 
 impl CleanRule for PipCacheRule {
+    fn id(&self) -> &str {
+        "pip_cache"
+    }
+
     fn name(&self) -> &str {
         "pip Cache"
     }
@@ -287,10 +324,10 @@ impl CleanRule for PipCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(cache) = dirs::cache_dir() {
+        if let Some(cache) = crate::paths::cache_dir() {
             paths.push(cache.join("pip"));
         }
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".cache/pip"));
             // macOS location
             paths.push(home.join("Library/Caches/pip"));
@@ -298,11 +335,15 @@ impl CleanRule for PipCacheRule {
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                if let Some(policy) = &self.prune {
+                    items.extend(prune_item(self, ctx, &path, policy, "pip download cache"));
+                    continue;
+                }
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -317,8 +358,15 @@ impl CleanRule for PipCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
-        clean_items(items, to_trash)
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_prunable(self, items, to_trash)
+    }
+}
+
+// Each cached HTTP response and wheel is a separate file
+impl PrunableRule for PipCacheRule {
+    fn prune_policy(&self) -> Option<&PrunePolicy> {
+        self.prune.as_ref()
     }
 }
 
@@ -326,6 +374,10 @@ impl CleanRule for PipCacheRule {
 pub struct UvCacheRule;
 
 impl CleanRule for UvCacheRule {
+    fn id(&self) -> &str {
+        "uv_cache"
+    }
+
     fn name(&self) -> &str {
         "uv Cache"
     }
@@ -348,20 +400,20 @@ impl CleanRule for UvCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(cache) = dirs::cache_dir() {
+        if let Some(cache) = crate::paths::cache_dir() {
             paths.push(cache.join("uv"));
         }
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".cache/uv"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -376,7 +428,7 @@ impl CleanRule for UvCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -385,6 +437,10 @@ impl CleanRule for UvCacheRule {
 pub struct CondaCacheRule;
 
 impl CleanRule for CondaCacheRule {
+    fn id(&self) -> &str {
+        "conda_pkgs"
+    }
+
     fn name(&self) -> &str {
         "Conda Package Cache"
     }
@@ -407,7 +463,7 @@ impl CleanRule for CondaCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("anaconda3/pkgs"));
             paths.push(home.join("miniconda3/pkgs"));
             paths.push(home.join("miniforge3/pkgs"));
@@ -416,12 +472,12 @@ impl CleanRule for CondaCacheRule {
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
                     // > 100MB
                     items.push(CleanItem::new(
                         path,
@@ -436,23 +492,25 @@ impl CleanRule for CondaCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Rust Rules ============
+/// Poetry cache rule
+pub struct PoetryCacheRule;
 
-/// Cargo cache rule
-pub struct CargoCacheRule;
+impl CleanRule for PoetryCacheRule {
+    fn id(&self) -> &str {
+        "poetry_cache"
+    }
 
-impl CleanRule for CargoCacheRule {
     fn name(&self) -> &str {
-        "Cargo Registry Cache"
+        "Poetry Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Rust
+        Category::Python
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -460,7 +518,7 @@ impl CleanRule for CargoCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Cargo registry and git cache"
+        "Poetry package cache and artifacts"
     }
 
     fn is_applicable(&self) -> bool {
@@ -469,28 +527,26 @@ impl CleanRule for CargoCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".cargo/registry/cache"));
-            paths.push(home.join(".cargo/git/checkouts"));
+        if let Some(cache_dir) = crate::paths::env_dir("POETRY_CACHE_DIR") {
+            paths.push(cache_dir);
+        } else if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".cache/pypoetry"));
+            // macOS location
+            paths.push(home.join("Library/Caches/pypoetry"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 0 {
-                    let desc = if path.to_string_lossy().contains("git") {
-                        "Cargo git checkouts"
-                    } else {
-                        "Cargo registry cache"
-                    };
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        desc,
+                        "Poetry cache",
                         self.risk_level(),
                         self.category(),
                     ));
@@ -500,21 +556,25 @@ impl CleanRule for CargoCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// Cargo target directories rule
-pub struct CargoTargetRule;
+/// pipenv cache rule
+pub struct PipenvCacheRule;
+
+impl CleanRule for PipenvCacheRule {
+    fn id(&self) -> &str {
+        "pipenv_cache"
+    }
 
-impl CleanRule for CargoTargetRule {
     fn name(&self) -> &str {
-        "Rust Build Artifacts"
+        "pipenv Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Rust
+        Category::Python
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -522,102 +582,69 @@ impl CleanRule for CargoTargetRule {
     }
 
     fn description(&self) -> &str {
-        "Rust project target directories (build artifacts)"
+        "pipenv package and lock cache"
     }
 
     fn is_applicable(&self) -> bool {
-        true // Always applicable, will scan common locations
+        self.scan_paths().iter().any(|p| p.exists())
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        // Will scan home directory for Rust projects
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home);
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".cache/pipenv"));
+            // macOS location
+            paths.push(home.join("Library/Caches/pipenv"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-
-        // Common project locations
-        let search_dirs = if let Some(home) = dirs::home_dir() {
-            vec![
-                home.join("Projects"),
-                home.join("projects"),
-                home.join("Code"),
-                home.join("code"),
-                home.join("Development"),
-                home.join("dev"),
-                home.join("src"),
-            ]
-        } else {
-            vec![]
-        };
-
-        for search_dir in search_dirs {
-            if search_dir.exists() {
-                // Look for target directories
-                for entry in WalkDir::new(&search_dir)
-                    .max_depth(4)
-                    .into_iter()
-                    .filter_map(|e| e.ok())
-                {
-                    let path = entry.path();
-                    if path.is_dir() && path.file_name().map(|n| n == "target").unwrap_or(false) {
-                        // Check if this is a Cargo project
-                        let cargo_toml = path.parent().map(|p| p.join("Cargo.toml"));
-                        if cargo_toml.map(|p| p.exists()).unwrap_or(false) {
-                            let size = dir_size(path);
-                            if size > 50 * 1024 * 1024 {
-                                // > 50MB
-                                let project_name = path
-                                    .parent()
-                                    .and_then(|p| p.file_name())
-                                    .map(|n| n.to_string_lossy().to_string())
-                                    .unwrap_or_else(|| "unknown".to_string());
-                                items.push(CleanItem::new(
-                                    path.to_path_buf(),
-                                    size,
-                                    format!("Rust build: {}", project_name),
-                                    self.risk_level(),
-                                    self.category(),
-                                ));
-                            }
-                        }
-                    }
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "pipenv cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Go Rules ============
+/// pyenv installed versions rule
+pub struct PyenvVersionsRule;
 
-/// Go module cache rule
-pub struct GoCacheRule;
+impl CleanRule for PyenvVersionsRule {
+    fn id(&self) -> &str {
+        "pyenv_versions"
+    }
 
-impl CleanRule for GoCacheRule {
     fn name(&self) -> &str {
-        "Go Module Cache"
+        "pyenv Versions"
     }
 
     fn category(&self) -> Category {
-        Category::Go
+        Category::Python
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Low
+        RiskLevel::Medium // Removing versions affects projects pinned to them
     }
 
     fn description(&self) -> &str {
-        "Go module download cache"
+        "Python versions installed by pyenv"
     }
 
     fn is_applicable(&self) -> bool {
@@ -626,26 +653,33 @@ impl CleanRule for GoCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join("go/pkg/mod/cache"));
-        }
-        // Check GOPATH if set
-        if let Ok(gopath) = std::env::var("GOPATH") {
-            paths.push(PathBuf::from(gopath).join("pkg/mod/cache"));
+        if let Some(root) = crate::paths::env_dir("PYENV_ROOT") {
+            paths.push(root.join("versions"));
+        } else if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".pyenv/versions"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
-            if path.exists() {
-                let size = dir_size(&path);
-                if size > 0 {
+        for versions_dir in self.scan_paths() {
+            let Ok(entries) = std::fs::read_dir(&versions_dir) else {
+                continue;
+            };
+            // One item per version so unused ones can be picked individually
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if !path.is_dir() || path.is_symlink() {
+                    continue;
+                }
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
+                    let version = entry.file_name().to_string_lossy().to_string();
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "Go module cache",
+                        format!("pyenv Python {} (keep versions you use)", version),
                         self.risk_level(),
                         self.category(),
                     ));
@@ -655,31 +689,86 @@ impl CleanRule for GoCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Java Rules ============
+/// Machine learning model and notebook cache rule
+pub struct MlCacheRule;
 
-/// Gradle cache rule
-pub struct GradleCacheRule;
+impl MlCacheRule {
+    /// `var` as an absolute directory, falling back to `default`
+    fn env_or(var: &str, default: Option<PathBuf>) -> Option<PathBuf> {
+        crate::paths::env_dir(var).or(default)
+    }
+
+    /// ~/.cache, which Hugging Face and PyTorch use on every platform
+    fn xdg_cache() -> Option<PathBuf> {
+        Self::env_or(
+            "XDG_CACHE_HOME",
+            crate::paths::home_dir().map(|h| h.join(".cache")),
+        )
+    }
+
+    /// Jupyter's data directory, honouring JUPYTER_DATA_DIR
+    fn jupyter_dir() -> Option<PathBuf> {
+        let default = if cfg!(target_os = "macos") {
+            crate::paths::home_dir().map(|h| h.join("Library/Jupyter"))
+        } else if cfg!(windows) {
+            crate::paths::config_dir().map(|d| d.join("jupyter"))
+        } else {
+            crate::paths::data_local_dir().map(|d| d.join("jupyter"))
+        };
+        Self::env_or("JUPYTER_DATA_DIR", default)
+    }
+
+    /// Cache directories per framework with their descriptions
+    fn caches() -> Vec<(PathBuf, &'static str)> {
+        let home = crate::paths::home_dir();
+        [
+            (
+                Self::env_or("HF_HOME", Self::xdg_cache().map(|c| c.join("huggingface"))),
+                "Hugging Face models and datasets (re-downloaded on demand)",
+            ),
+            (
+                Self::env_or("TORCH_HOME", Self::xdg_cache().map(|c| c.join("torch"))),
+                "PyTorch hub models and checkpoints (re-downloaded on demand)",
+            ),
+            (
+                Self::env_or("KERAS_HOME", home.map(|h| h.join(".keras"))),
+                "Keras models and datasets (re-downloaded on demand)",
+            ),
+            (
+                Self::jupyter_dir(),
+                "Jupyter data (kernel specs, extensions, runtime files)",
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(path, description)| Some((path?, description)))
+        .collect()
+    }
+}
+
+impl CleanRule for MlCacheRule {
+    fn id(&self) -> &str {
+        "ml_caches"
+    }
 
-impl CleanRule for GradleCacheRule {
     fn name(&self) -> &str {
-        "Gradle Cache"
+        "ML Model Caches"
     }
 
     fn category(&self) -> Category {
-        Category::Java
+        Category::Python
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Low
+        RiskLevel::Medium // Model weights can take hours to download again
     }
 
     fn description(&self) -> &str {
-        "Gradle build cache and dependencies"
+        "Hugging Face, PyTorch and Keras model caches and Jupyter data"
     }
 
     fn is_applicable(&self) -> bool {
@@ -687,29 +776,20 @@ impl CleanRule for GradleCacheRule {
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".gradle/caches"));
-            paths.push(home.join(".gradle/wrapper/dists"));
-        }
-        paths
+        Self::caches().into_iter().map(|(path, _)| path).collect()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
+        // One item per framework so each can be kept or cleaned on its own
+        for (path, description) in Self::caches() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 0 {
-                    let desc = if path.to_string_lossy().contains("wrapper") {
-                        "Gradle wrapper distributions"
-                    } else {
-                        "Gradle cache"
-                    };
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        desc,
+                        description,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -719,29 +799,35 @@ impl CleanRule for GradleCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// Maven local repository rule
-pub struct MavenCacheRule;
+// ============ Rust Rules ============
+
+/// Cargo cache rule
+pub struct CargoCacheRule;
+
+impl CleanRule for CargoCacheRule {
+    fn id(&self) -> &str {
+        "cargo_registry"
+    }
 
-impl CleanRule for MavenCacheRule {
     fn name(&self) -> &str {
-        "Maven Local Repository"
+        "Cargo Registry Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Java
+        Category::Rust
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Medium
+        RiskLevel::Low
     }
 
     fn description(&self) -> &str {
-        "Maven local repository cache"
+        "Cargo registry and git cache"
     }
 
     fn is_applicable(&self) -> bool {
@@ -750,23 +836,28 @@ impl CleanRule for MavenCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".m2/repository"));
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".cargo/registry/cache"));
+            paths.push(home.join(".cargo/git/checkouts"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
-                    // > 100MB
+                let size = ctx.dir_size(&path);
+                if size > 0 {
+                    let desc = if path.to_string_lossy().contains("git") {
+                        "Cargo git checkouts"
+                    } else {
+                        "Cargo registry cache"
+                    };
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "Maven local repository",
+                        desc,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -776,23 +867,25 @@ impl CleanRule for MavenCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Android Rules ============
+/// Cargo target directories rule
+pub struct CargoTargetRule;
 
-/// Android SDK cache rule
-pub struct AndroidCacheRule;
+impl CleanRule for CargoTargetRule {
+    fn id(&self) -> &str {
+        "cargo_target"
+    }
 
-impl CleanRule for AndroidCacheRule {
     fn name(&self) -> &str {
-        "Android SDK Cache"
+        "Rust Build Artifacts"
     }
 
     fn category(&self) -> Category {
-        Category::Android
+        Category::Rust
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -800,216 +893,1172 @@ impl CleanRule for AndroidCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Android SDK and AVD cache files"
+        "Rust project target directories (build artifacts)"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
     }
 
     fn is_applicable(&self) -> bool {
-        self.scan_paths().iter().any(|p| p.exists())
+        true // Always applicable, will scan common locations
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
+        // Will scan home directory for Rust projects
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".android/cache"));
-            paths.push(home.join(".android/build-cache"));
-            // macOS location
-            paths.push(home.join("Library/Android/sdk/.downloadIntermediates"));
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home);
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
-            if path.exists() {
-                let size = dir_size(&path);
-                if size > 0 {
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "Android SDK cache",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+
+        for search_dir in ctx.project_dirs() {
+            if search_dir.exists() {
+                // Look for target directories
+                for entry in WalkDir::new(&search_dir)
+                    .follow_links(false)
+                    .max_depth(ctx.config.scan.project_max_depth)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
+                    let path = entry.path();
+                    if path.is_dir() && path.file_name().map(|n| n == "target").unwrap_or(false) {
+                        // Check if this is a Cargo project
+                        let cargo_toml = path.parent().map(|p| p.join("Cargo.toml"));
+                        if cargo_toml.map(|p| p.exists()).unwrap_or(false) {
+                            let size = ctx.dir_size(path);
+                            if size > ctx.min_size(self, 50) {
+                                // > 50MB
+                                let project_name = path
+                                    .parent()
+                                    .and_then(|p| p.file_name())
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| "unknown".to_string());
+                                items.push(CleanItem::new(
+                                    path.to_path_buf(),
+                                    size,
+                                    format!("Rust build: {}", project_name),
+                                    self.risk_level(),
+                                    self.category(),
+                                ));
+                            }
+                        }
+                    }
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Docker Rules ============
+// ============ Go Rules ============
+
+/// Go module cache rule
+pub struct GoCacheRule;
 
-/// Docker cache rule
-pub struct DockerCacheRule;
+impl CleanRule for GoCacheRule {
+    fn id(&self) -> &str {
+        "go_mod_cache"
+    }
 
-impl CleanRule for DockerCacheRule {
     fn name(&self) -> &str {
-        "Docker Cache"
+        "Go Module Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Docker
+        Category::Go
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Medium
+        RiskLevel::Low
     }
 
     fn description(&self) -> &str {
-        "Docker build cache and unused data"
+        "Go module download cache"
     }
 
     fn is_applicable(&self) -> bool {
-        // Check if docker command exists
-        std::process::Command::new("docker")
-            .arg("--version")
-            .output()
-            .is_ok()
+        self.scan_paths().iter().any(|p| p.exists())
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        // Docker manages its own storage, we'll use docker system df
-        vec![]
-    }
-
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
-        // Try to get docker system info
-        let output = std::process::Command::new("docker")
-            .args(["system", "df", "--format", "{{.Reclaimable}}"])
-            .output();
-
-        match output {
-            Ok(output) if output.status.success() => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let mut total_reclaimable = 0u64;
-
-                for line in stdout.lines() {
-                    // Parse sizes like "1.5GB", "500MB"
-                    let trimmed = line.trim().to_uppercase();
-                    if let Some(size) = parse_size(&trimmed) {
-                        total_reclaimable += size;
-                    }
-                }
-
-                if total_reclaimable > 100 * 1024 * 1024 {
-                    // > 100MB
-                    Ok(vec![CleanItem::new(
-                        PathBuf::from("/var/lib/docker"),
-                        total_reclaimable,
-                        "Docker reclaimable space (run 'docker system prune')",
-                        RiskLevel::Medium,
-                        Category::Docker,
-                    )])
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join("go/pkg/mod/cache"));
+        }
+        // Check GOPATH if set
+        if let Ok(gopath) = std::env::var("GOPATH") {
+            paths.push(PathBuf::from(gopath).join("pkg/mod/cache"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > 0 {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Go module cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Go build and test cache rule
+#[derive(Default)]
+pub struct GoBuildCacheRule {
+    /// Build cache location, looked up once per rule instance
+    cache_dir: OnceLock<Option<PathBuf>>,
+}
+
+impl GoBuildCacheRule {
+    /// Check if the go binary is available
+    fn go_available() -> bool {
+        Command::new("go")
+            .arg("version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Build cache location, running `go env GOCACHE` only the first time
+    fn cache_dir(&self) -> Option<PathBuf> {
+        self.cache_dir.get_or_init(Self::find_cache_dir).clone()
+    }
+
+    /// Build cache location, as reported by `go env GOCACHE` when possible
+    fn find_cache_dir() -> Option<PathBuf> {
+        let from_go = Command::new("go")
+            .args(["env", "GOCACHE"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+
+        match from_go.as_deref() {
+            // The cache is disabled
+            Some("off") => None,
+            Some(dir) if Path::new(dir).is_absolute() => Some(PathBuf::from(dir)),
+            _ if std::env::var_os("GOCACHE").is_some_and(|v| v == "off") => None,
+            // Go's default is the platform user cache directory
+            _ => crate::paths::env_dir("GOCACHE")
+                .or_else(|| crate::paths::cache_dir().map(|p| p.join("go-build"))),
+        }
+    }
+}
+
+impl CleanRule for GoBuildCacheRule {
+    fn id(&self) -> &str {
+        "go_build_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Go Build Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Go
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Go build and test cache (GOCACHE)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        self.cache_dir().into_iter().collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
+                    items.push(
+                        CleanItem::new(
+                            path,
+                            size,
+                            "Go build cache",
+                            self.risk_level(),
+                            self.category(),
+                        )
+                        .with_handler(self.id()),
+                    );
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+        let go = Self::go_available();
+
+        for item in items {
+            // Let Go trim the cache itself so it stays consistent
+            if go {
+                match Command::new("go").args(["clean", "-cache"]).output() {
+                    Ok(output) if output.status.success() => {
+                        result.cleaned_count += 1;
+                        result.bytes_freed += item.size;
+                        continue;
+                    }
+                    Ok(output) => {
+                        tracing::warn!(
+                            "go clean -cache failed: {}",
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to run go: {}", e);
+                    }
+                }
+            }
+
+            result.merge(clean_items(std::slice::from_ref(item), to_trash)?);
+        }
+
+        Ok(result)
+    }
+}
+
+// ============ Java Rules ============
+
+/// Gradle cache rule
+#[derive(Default)]
+pub struct GradleCacheRule {
+    /// Policy from `[prune.rules.gradle_cache]`, if any
+    prune: Option<PrunePolicy>,
+}
+
+impl GradleCacheRule {
+    /// Create the rule with its prune policy from `config`
+    pub fn new(config: &PruneConfig) -> Self {
+        let mut rule = Self::default();
+        rule.prune = config.policy(rule.id(), rule.name());
+        rule
+    }
+}
+
+impl CleanRule for GradleCacheRule {
+    fn id(&self) -> &str {
+        "gradle_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Gradle Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Java
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Gradle build cache and dependencies"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".gradle/caches"));
+            paths.push(home.join(".gradle/wrapper/dists"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let desc = if path.to_string_lossy().contains("wrapper") {
+                    "Gradle wrapper distributions"
                 } else {
-                    Ok(vec![])
+                    "Gradle cache"
+                };
+                if let Some(policy) = &self.prune {
+                    items.extend(prune_item(self, ctx, &path, policy, desc));
+                    continue;
+                }
+                let size = ctx.dir_size(&path);
+                if size > 0 {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        desc,
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_prunable(self, items, to_trash)
+    }
+}
+
+impl PrunableRule for GradleCacheRule {
+    fn prune_policy(&self) -> Option<&PrunePolicy> {
+        self.prune.as_ref()
+    }
+
+    /// Dependency versions and build cache entries of `caches`, and whole
+    /// wrapper distributions
+    fn prune_units(&self, cache: &Path) -> Vec<PathBuf> {
+        if cache.ends_with("wrapper/dists") {
+            return child_paths(cache);
+        }
+
+        let mut units = Vec::new();
+        // modules-2/files-2.1/<group>/<artifact>/<version>
+        for group in child_paths(&cache.join("modules-2/files-2.1")) {
+            for artifact in child_paths(&group) {
+                units.extend(child_paths(&artifact));
+            }
+        }
+        for entry in child_paths(cache) {
+            let is_build_cache = entry
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with("build-cache-"));
+            if is_build_cache {
+                units.extend(child_paths(&entry));
+            }
+        }
+        units
+    }
+}
+
+/// Entries directly inside `dir`, or none if it can't be read
+fn child_paths(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Maven local repository rule
+pub struct MavenCacheRule;
+
+impl CleanRule for MavenCacheRule {
+    fn id(&self) -> &str {
+        "maven_repository"
+    }
+
+    fn name(&self) -> &str {
+        "Maven Local Repository"
+    }
+
+    fn category(&self) -> Category {
+        Category::Java
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Maven local repository cache"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".m2/repository"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
+                    // > 100MB
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Maven local repository",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+// ============ Android Rules ============
+
+/// Android SDK cache rule
+pub struct AndroidCacheRule;
+
+impl CleanRule for AndroidCacheRule {
+    fn id(&self) -> &str {
+        "android_sdk_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Android SDK Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Android
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Android SDK and AVD cache files"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".android/cache"));
+            paths.push(home.join(".android/build-cache"));
+            // macOS location
+            paths.push(home.join("Library/Android/sdk/.downloadIntermediates"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > 0 {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Android SDK cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+// ============ IDE & Editor Rules ============
+
+/// Items for the directories among `paths` (each with its description) that
+/// exist and exceed the rule's size threshold
+fn editor_dir_items(
+    rule: &dyn CleanRule,
+    ctx: &ScanContext,
+    paths: Vec<(PathBuf, &str)>,
+) -> Vec<CleanItem> {
+    let mut items = Vec::new();
+    for (path, desc) in paths {
+        if path.exists() {
+            let size = ctx.dir_size(&path);
+            if size > ctx.min_size(rule, 10) {
+                items.push(CleanItem::new(
+                    path,
+                    size,
+                    desc,
+                    rule.risk_level(),
+                    rule.category(),
+                ));
+            }
+        }
+    }
+    items
+}
+
+/// VSCode cache rule
+///
+/// Only regenerable caches; workspace storage and extensions have their own
+/// rules so they can be selected, and confirmed, separately.
+pub struct VSCodeCacheRule;
+
+impl VSCodeCacheRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let Some(home) = crate::paths::home_dir() else {
+            return Vec::new();
+        };
+        let support = home.join("Library/Application Support/Code");
+        vec![
+            (support.join("Cache"), "VS Code cache"),
+            (
+                support.join("CachedExtensions"),
+                "VS Code extension metadata cache",
+            ),
+            (
+                support.join("CachedExtensionVSIXs"),
+                "VS Code extension download cache",
+            ),
+            (
+                home.join("Library/Caches/com.microsoft.VSCode"),
+                "VS Code cache",
+            ),
+        ]
+    }
+}
+
+impl CleanRule for VSCodeCacheRule {
+    fn id(&self) -> &str {
+        "vscode_cache"
+    }
+
+    fn name(&self) -> &str {
+        "VS Code Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "VS Code caches and extension download cache"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        Self::paths().into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(editor_dir_items(self, ctx, Self::paths()))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// VSCode workspace storage, logs and compiled code cache rule
+pub struct VSCodeWorkspaceStorageRule;
+
+impl VSCodeWorkspaceStorageRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let Some(home) = crate::paths::home_dir() else {
+            return Vec::new();
+        };
+        let support = home.join("Library/Application Support/Code");
+        vec![
+            (
+                support.join("User/workspaceStorage"),
+                "VS Code workspace storage",
+            ),
+            (support.join("logs"), "VS Code logs"),
+            (support.join("CachedData"), "VS Code compiled code cache"),
+        ]
+    }
+}
+
+impl CleanRule for VSCodeWorkspaceStorageRule {
+    fn id(&self) -> &str {
+        "vscode_workspace_storage"
+    }
+
+    fn name(&self) -> &str {
+        "VS Code Workspace Storage"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "VS Code per-workspace state, logs and compiled code cache"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        Self::paths().into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(editor_dir_items(self, ctx, Self::paths()))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// VSCode installed extensions rule
+pub struct VSCodeExtensionsRule;
+
+impl CleanRule for VSCodeExtensionsRule {
+    fn id(&self) -> &str {
+        "vscode_extensions"
+    }
+
+    fn name(&self) -> &str {
+        "VS Code Extensions"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Installed VS Code extensions (have to be reinstalled)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        crate::paths::home_dir()
+            .map(|home| vec![home.join(".vscode/extensions")])
+            .unwrap_or_default()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let paths = self
+            .scan_paths()
+            .into_iter()
+            .map(|path| (path, "VS Code extensions (consider cleaning unused)"))
+            .collect();
+        Ok(editor_dir_items(self, ctx, paths))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Cursor IDE cache rule
+pub struct CursorCacheRule;
+
+impl CursorCacheRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let Some(home) = crate::paths::home_dir() else {
+            return Vec::new();
+        };
+        vec![
+            (
+                home.join("Library/Application Support/Cursor/Cache"),
+                "Cursor cache",
+            ),
+            (
+                home.join("Library/Caches/com.todesktop.230313mzl4w4u92"),
+                "Cursor cache",
+            ),
+        ]
+    }
+}
+
+impl CleanRule for CursorCacheRule {
+    fn id(&self) -> &str {
+        "cursor_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Cursor Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Cursor AI IDE caches"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        Self::paths().into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(editor_dir_items(self, ctx, Self::paths()))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Cursor workspace storage, logs and compiled code cache rule
+pub struct CursorWorkspaceStorageRule;
+
+impl CursorWorkspaceStorageRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let Some(home) = crate::paths::home_dir() else {
+            return Vec::new();
+        };
+        let support = home.join("Library/Application Support/Cursor");
+        vec![
+            (
+                support.join("User/workspaceStorage"),
+                "Cursor workspace storage",
+            ),
+            (support.join("logs"), "Cursor logs"),
+            (support.join("CachedData"), "Cursor compiled code cache"),
+        ]
+    }
+}
+
+impl CleanRule for CursorWorkspaceStorageRule {
+    fn id(&self) -> &str {
+        "cursor_workspace_storage"
+    }
+
+    fn name(&self) -> &str {
+        "Cursor Workspace Storage"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Cursor per-workspace state, logs and compiled code cache"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        Self::paths().into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(editor_dir_items(self, ctx, Self::paths()))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Cursor installed extensions rule
+pub struct CursorExtensionsRule;
+
+impl CleanRule for CursorExtensionsRule {
+    fn id(&self) -> &str {
+        "cursor_extensions"
+    }
+
+    fn name(&self) -> &str {
+        "Cursor Extensions"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Installed Cursor extensions (have to be reinstalled)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        crate::paths::home_dir()
+            .map(|home| vec![home.join(".cursor/extensions")])
+            .unwrap_or_default()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let paths = self
+            .scan_paths()
+            .into_iter()
+            .map(|path| (path, "Cursor extensions"))
+            .collect();
+        Ok(editor_dir_items(self, ctx, paths))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// JetBrains IDEs cache rule
+pub struct JetBrainsCacheRule;
+
+impl CleanRule for JetBrainsCacheRule {
+    fn id(&self) -> &str {
+        "jetbrains_cache"
+    }
+
+    fn name(&self) -> &str {
+        "JetBrains Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Ide
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "JetBrains IDEs cache (IntelliJ, WebStorm, PyCharm, etc.)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            // Look for JetBrains cache directories
+            let cache_base = home.join("Library/Caches/JetBrains");
+            if cache_base.exists() {
+                paths.push(cache_base);
+            }
+            // Also check for individual IDE caches
+            let ides = [
+                "IntelliJIdea",
+                "WebStorm",
+                "PyCharm",
+                "CLion",
+                "GoLand",
+                "RustRover",
+                "DataGrip",
+            ];
+            for ide in &ides {
+                let pattern = home.join(format!("Library/Caches/{}", ide));
+                if pattern.exists() {
+                    paths.push(pattern);
+                }
+            }
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 50) {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "JetBrains IDE cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+// ============ Mobile Development Rules ============
+
+/// Language server cache rule (rust-analyzer, gopls, clangd)
+///
+/// Indexes and build metadata that the servers rebuild the next time an
+/// editor opens the project.
+pub struct LspCacheRule;
+
+impl LspCacheRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let Some(cache) = crate::paths::cache_dir() else {
+            return Vec::new();
+        };
+        vec![
+            (cache.join("rust-analyzer"), "rust-analyzer cache"),
+            (cache.join("gopls"), "gopls cache"),
+            (cache.join("clangd"), "clangd index"),
+        ]
+    }
+}
+
+impl CleanRule for LspCacheRule {
+    fn id(&self) -> &str {
+        "lsp_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Language Server Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Other("LSP".to_string())
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "rust-analyzer, gopls and clangd caches (rebuilt when the editor reopens)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        Self::paths().into_iter().map(|(path, _)| path).collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(editor_dir_items(self, ctx, Self::paths()))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Flutter cache rule
+pub struct FlutterCacheRule;
+
+impl CleanRule for FlutterCacheRule {
+    fn id(&self) -> &str {
+        "flutter_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Flutter Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Mobile
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Flutter SDK cache and artifacts"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join("flutter/bin/cache"));
+            paths.push(home.join(".flutter"));
+            paths.push(home.join("development/flutter/bin/cache"));
+        }
+        // Check FLUTTER_ROOT if set
+        if let Ok(flutter_root) = std::env::var("FLUTTER_ROOT") {
+            paths.push(PathBuf::from(flutter_root).join("bin/cache"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Flutter SDK cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Dart pub cache rule
+pub struct DartPubCacheRule;
+
+impl CleanRule for DartPubCacheRule {
+    fn id(&self) -> &str {
+        "dart_pub_cache"
+    }
+
+    fn name(&self) -> &str {
+        "Dart Pub Cache"
+    }
+
+    fn category(&self) -> Category {
+        Category::Mobile
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Dart package manager cache"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".pub-cache"));
+        }
+        // Check PUB_CACHE if set
+        if let Ok(pub_cache) = std::env::var("PUB_CACHE") {
+            paths.push(PathBuf::from(pub_cache));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 50) {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Dart pub cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
                 }
             }
-            _ => Ok(vec![]),
-        }
-    }
-
-    fn clean(&self, _items: &[CleanItem], _to_trash: bool) -> anyhow::Result<CleanResult> {
-        // Execute docker system prune
-        let output = std::process::Command::new("docker")
-            .args(["system", "prune", "-f"])
-            .output();
-
-        match output {
-            Ok(output) if output.status.success() => {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                // Try to parse reclaimed space from output
-                let bytes_freed = stdout
-                    .lines()
-                    .find(|l| l.contains("reclaimed"))
-                    .and_then(|l| {
-                        l.split_whitespace()
-                            .find(|s| {
-                                s.chars()
-                                    .next()
-                                    .map(|c| c.is_ascii_digit())
-                                    .unwrap_or(false)
-                            })
-                            .and_then(|s| parse_size(&s.to_uppercase()))
-                    })
-                    .unwrap_or(0);
+        }
+        Ok(items)
+    }
 
-                Ok(CleanResult {
-                    cleaned_count: 1,
-                    bytes_freed,
-                    failed: vec![],
-                    cancelled: false,
-                })
-            }
-            Ok(output) => Ok(CleanResult {
-                cleaned_count: 0,
-                bytes_freed: 0,
-                failed: vec![(
-                    PathBuf::from("docker"),
-                    String::from_utf8_lossy(&output.stderr).to_string(),
-                )],
-                cancelled: false,
-            }),
-            Err(e) => Ok(CleanResult {
-                cleaned_count: 0,
-                bytes_freed: 0,
-                failed: vec![(PathBuf::from("docker"), e.to_string())],
-                cancelled: false,
-            }),
-        }
-    }
-}
-
-/// Parse size strings like "1.5GB", "500MB", "1024KB"
-fn parse_size(s: &str) -> Option<u64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
-
-    let (num_part, unit) = if s.ends_with("GB") {
-        (s.trim_end_matches("GB"), 1024 * 1024 * 1024)
-    } else if s.ends_with("MB") {
-        (s.trim_end_matches("MB"), 1024 * 1024)
-    } else if s.ends_with("KB") {
-        (s.trim_end_matches("KB"), 1024)
-    } else if s.ends_with("B") {
-        (s.trim_end_matches("B"), 1)
-    } else {
-        return None;
-    };
-
-    num_part
-        .trim()
-        .parse::<f64>()
-        .ok()
-        .map(|n| (n * unit as f64) as u64)
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
 }
 
-// ============ IDE & Editor Rules ============
+// ============ Additional Node.js Tools ============
 
-/// VSCode cache rule
-pub struct VSCodeCacheRule;
+/// nvm cache rule
+pub struct NvmCacheRule;
+
+impl CleanRule for NvmCacheRule {
+    fn id(&self) -> &str {
+        "nvm_cache"
+    }
 
-impl CleanRule for VSCodeCacheRule {
     fn name(&self) -> &str {
-        "VS Code Cache"
+        "nvm Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("IDE".to_string())
+        Category::NodeJs
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Low
+        RiskLevel::Medium
     }
 
     fn description(&self) -> &str {
-        "VS Code extensions cache and logs"
+        "Node Version Manager installed versions"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1018,40 +2067,29 @@ impl CleanRule for VSCodeCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".vscode/extensions"));
-            paths.push(home.join("Library/Application Support/Code/Cache"));
-            paths.push(home.join("Library/Application Support/Code/CachedData"));
-            paths.push(home.join("Library/Application Support/Code/CachedExtensions"));
-            paths.push(home.join("Library/Application Support/Code/logs"));
-            paths.push(home.join("Library/Caches/com.microsoft.VSCode"));
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".nvm/versions"));
+            paths.push(home.join(".nvm/.cache"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 10 * 1024 * 1024 {
-                    let is_extensions = path.to_string_lossy().contains("extensions");
-                    let desc = if is_extensions {
-                        "VS Code extensions (consider cleaning unused)"
-                    } else if path.to_string_lossy().contains("logs") {
-                        "VS Code logs"
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
+                    let desc = if path.to_string_lossy().contains(".cache") {
+                        "nvm download cache"
                     } else {
-                        "VS Code cache"
+                        "nvm Node.js versions (keep versions you use)"
                     };
                     items.push(CleanItem::new(
                         path,
                         size,
                         desc,
-                        if is_extensions {
-                            RiskLevel::Medium
-                        } else {
-                            self.risk_level()
-                        },
+                        self.risk_level(),
                         self.category(),
                     ));
                 }
@@ -1060,21 +2098,25 @@ impl CleanRule for VSCodeCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// Cursor IDE cache rule
-pub struct CursorCacheRule;
+/// Bun cache rule
+pub struct BunCacheRule;
+
+impl CleanRule for BunCacheRule {
+    fn id(&self) -> &str {
+        "bun_cache"
+    }
 
-impl CleanRule for CursorCacheRule {
     fn name(&self) -> &str {
-        "Cursor Cache"
+        "Bun Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("IDE".to_string())
+        Category::NodeJs
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1082,7 +2124,7 @@ impl CleanRule for CursorCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Cursor AI IDE cache and logs"
+        "Bun package manager cache"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1091,39 +2133,23 @@ impl CleanRule for CursorCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".cursor/extensions"));
-            paths.push(home.join("Library/Application Support/Cursor/Cache"));
-            paths.push(home.join("Library/Application Support/Cursor/CachedData"));
-            paths.push(home.join("Library/Application Support/Cursor/logs"));
-            paths.push(home.join("Library/Caches/com.todesktop.230313mzl4w4u92"));
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".bun/install/cache"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 10 * 1024 * 1024 {
-                    let is_extensions = path.to_string_lossy().contains("extensions");
-                    let desc = if is_extensions {
-                        "Cursor extensions"
-                    } else if path.to_string_lossy().contains("logs") {
-                        "Cursor logs"
-                    } else {
-                        "Cursor cache"
-                    };
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 50) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        desc,
-                        if is_extensions {
-                            RiskLevel::Medium
-                        } else {
-                            self.risk_level()
-                        },
+                        "Bun package cache",
+                        self.risk_level(),
                         self.category(),
                     ));
                 }
@@ -1132,21 +2158,25 @@ impl CleanRule for CursorCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// JetBrains IDEs cache rule
-pub struct JetBrainsCacheRule;
+/// Deno cache rule
+pub struct DenoCacheRule;
+
+impl CleanRule for DenoCacheRule {
+    fn id(&self) -> &str {
+        "deno_cache"
+    }
 
-impl CleanRule for JetBrainsCacheRule {
     fn name(&self) -> &str {
-        "JetBrains Cache"
+        "Deno Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("IDE".to_string())
+        Category::NodeJs
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1154,7 +2184,7 @@ impl CleanRule for JetBrainsCacheRule {
     }
 
     fn description(&self) -> &str {
-        "JetBrains IDEs cache (IntelliJ, WebStorm, PyCharm, etc.)"
+        "Deno runtime cache"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1163,42 +2193,235 @@ impl CleanRule for JetBrainsCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            // Look for JetBrains cache directories
-            let cache_base = home.join("Library/Caches/JetBrains");
-            if cache_base.exists() {
-                paths.push(cache_base);
+        if let Some(cache) = crate::paths::cache_dir() {
+            paths.push(cache.join("deno"));
+        }
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".deno"));
+            paths.push(home.join("Library/Caches/deno"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        for path in self.scan_paths() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 50) {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Deno cache",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
             }
-            // Also check for individual IDE caches
-            let ides = [
-                "IntelliJIdea",
-                "WebStorm",
-                "PyCharm",
-                "CLion",
-                "GoLand",
-                "RustRover",
-                "DataGrip",
-            ];
-            for ide in &ides {
-                let pattern = home.join(format!("Library/Caches/{}", ide));
-                if pattern.exists() {
-                    paths.push(pattern);
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// `node_modules` directories of projects that have not been touched recently
+pub struct NodeModulesRule {
+    /// Project directories from `[scan] project_dirs`
+    project_dirs: Vec<PathBuf>,
+}
+
+impl Default for NodeModulesRule {
+    fn default() -> Self {
+        Self::new(&ScanConfig::default())
+    }
+}
+
+impl NodeModulesRule {
+    /// Create the rule searching the project directories of `config`
+    pub fn new(config: &ScanConfig) -> Self {
+        Self {
+            project_dirs: config.project_search_dirs(),
+        }
+    }
+
+    /// Latest modification time of the project's own files, ignoring `node_modules`
+    fn project_last_modified(project_dir: &Path) -> Option<std::time::SystemTime> {
+        let newest_entry = std::fs::read_dir(project_dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name() != "node_modules")
+            .filter_map(|e| e.metadata().and_then(|m| m.modified()).ok())
+            .max();
+        newest_entry.or_else(|| project_dir.metadata().and_then(|m| m.modified()).ok())
+    }
+}
+
+impl CleanRule for NodeModulesRule {
+    fn id(&self) -> &str {
+        "stale_node_modules"
+    }
+
+    fn name(&self) -> &str {
+        "Stale node_modules"
+    }
+
+    fn category(&self) -> Category {
+        Category::NodeJs
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "node_modules of projects untouched for heuristic.stale_days (reinstall to restore)"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        self.project_dirs.clone()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let stale_days = ctx.config.heuristic.stale_days;
+        let now = std::time::SystemTime::now();
+
+        for search_dir in ctx.project_dirs() {
+            if !search_dir.exists() {
+                continue;
+            }
+
+            let mut walker = WalkDir::new(&search_dir)
+                .follow_links(false)
+                .max_depth(ctx.config.scan.project_max_depth)
+                .into_iter();
+            while let Some(entry) = walker.next() {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                if !entry.file_type().is_dir() || entry.file_name() != "node_modules" {
+                    continue;
+                }
+                // Never descend, so nested node_modules are not listed separately
+                walker.skip_current_dir();
+
+                let path = entry.path();
+                let Some(project_dir) = path.parent() else {
+                    continue;
+                };
+                if !project_dir.join("package.json").exists() {
+                    continue;
+                }
+                let Some(modified) = Self::project_last_modified(project_dir) else {
+                    continue;
+                };
+                let age_days = now
+                    .duration_since(modified)
+                    .map(|d| d.as_secs() / (24 * 60 * 60))
+                    .unwrap_or(0);
+                if age_days < u64::from(stale_days) {
+                    continue;
+                }
+
+                let size = ctx.dir_size(path);
+                if size > ctx.min_size(self, 10) {
+                    let project_name = project_dir
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    let mut item = CleanItem::new(
+                        path.to_path_buf(),
+                        size,
+                        format!(
+                            "node_modules: {} (untouched for {} days)",
+                            project_name, age_days
+                        ),
+                        self.risk_level(),
+                        self.category(),
+                    );
+                    if let Ok(since_epoch) = modified.duration_since(std::time::UNIX_EPOCH) {
+                        item = item.with_last_modified(since_epoch.as_secs() as i64);
+                    }
+                    items.push(item);
                 }
             }
         }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+// ============ Rustup Rule ============
+
+/// Rustup cache rule
+pub struct RustupCacheRule;
+
+impl CleanRule for RustupCacheRule {
+    fn id(&self) -> &str {
+        "rustup_toolchains"
+    }
+
+    fn name(&self) -> &str {
+        "Rustup Toolchains"
+    }
+
+    fn category(&self) -> Category {
+        Category::Rust
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Rustup installed toolchains and components"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".rustup/toolchains"));
+            paths.push(home.join(".rustup/downloads"));
+            paths.push(home.join(".rustup/tmp"));
+        }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 50 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 500) {
+                    let desc = if path.to_string_lossy().contains("toolchains") {
+                        "Rustup toolchains (keep versions you use)"
+                    } else if path.to_string_lossy().contains("downloads") {
+                        "Rustup downloads cache"
+                    } else {
+                        "Rustup temporary files"
+                    };
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "JetBrains IDE cache",
+                        desc,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -1208,23 +2431,27 @@ impl CleanRule for JetBrainsCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Mobile Development Rules ============
+// ============ Ruby Rule ============
 
-/// Flutter cache rule
-pub struct FlutterCacheRule;
+/// Ruby cache rule
+pub struct RubyCacheRule;
+
+impl CleanRule for RubyCacheRule {
+    fn id(&self) -> &str {
+        "ruby_gems_cache"
+    }
 
-impl CleanRule for FlutterCacheRule {
     fn name(&self) -> &str {
-        "Flutter Cache"
+        "Ruby Gems Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("Mobile".to_string())
+        Category::Ruby
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1232,7 +2459,7 @@ impl CleanRule for FlutterCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Flutter SDK cache and artifacts"
+        "Ruby gems and bundle cache"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1241,28 +2468,31 @@ impl CleanRule for FlutterCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join("flutter/bin/cache"));
-            paths.push(home.join(".flutter"));
-            paths.push(home.join("development/flutter/bin/cache"));
-        }
-        // Check FLUTTER_ROOT if set
-        if let Ok(flutter_root) = std::env::var("FLUTTER_ROOT") {
-            paths.push(PathBuf::from(flutter_root).join("bin/cache"));
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".gem"));
+            paths.push(home.join(".bundle/cache"));
+            paths.push(home.join(".rbenv/versions"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 100) {
+                    let desc = if path.to_string_lossy().contains("rbenv") {
+                        "rbenv Ruby versions"
+                    } else if path.to_string_lossy().contains("bundle") {
+                        "Bundler cache"
+                    } else {
+                        "Ruby gems cache"
+                    };
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "Flutter SDK cache",
+                        desc,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -1272,21 +2502,52 @@ impl CleanRule for FlutterCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
-        clean_items(items, to_trash)
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+// ============ .NET Rules ============
+
+/// NuGet package cache rule
+pub struct NuGetCacheRule;
+
+impl NuGetCacheRule {
+    fn paths() -> Vec<(PathBuf, &'static str)> {
+        let mut paths = Vec::new();
+        // NUGET_PACKAGES replaces the default global packages folder
+        if let Some(packages) = crate::paths::env_dir("NUGET_PACKAGES") {
+            paths.push((packages, "NuGet global packages"));
+        } else if let Some(home) = crate::paths::home_dir() {
+            paths.push((home.join(".nuget/packages"), "NuGet global packages"));
+        }
+        // ~/.dotnet may hold the SDK itself, so only its caches are included
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push((home.join(".local/share/NuGet/v3-cache"), "NuGet HTTP cache"));
+            paths.push((
+                home.join(".local/share/NuGet/http-cache"),
+                "NuGet HTTP cache",
+            ));
+            paths.push((
+                home.join(".dotnet/toolResolverCache"),
+                ".NET tool resolver cache",
+            ));
+        }
+        paths
     }
 }
 
-/// Dart pub cache rule
-pub struct DartPubCacheRule;
+impl CleanRule for NuGetCacheRule {
+    fn id(&self) -> &str {
+        "nuget_cache"
+    }
 
-impl CleanRule for DartPubCacheRule {
     fn name(&self) -> &str {
-        "Dart Pub Cache"
+        "NuGet Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("Mobile".to_string())
+        Category::DotNet
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1294,7 +2555,7 @@ impl CleanRule for DartPubCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Dart package manager cache"
+        "NuGet global packages and HTTP cache"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1302,27 +2563,19 @@ impl CleanRule for DartPubCacheRule {
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".pub-cache"));
-        }
-        // Check PUB_CACHE if set
-        if let Ok(pub_cache) = std::env::var("PUB_CACHE") {
-            paths.push(PathBuf::from(pub_cache));
-        }
-        paths
+        Self::paths().into_iter().map(|(path, _)| path).collect()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
+        for (path, desc) in Self::paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 50 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "Dart pub cache",
+                        desc,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -1332,93 +2585,199 @@ impl CleanRule for DartPubCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Additional Node.js Tools ============
+/// .NET project build output rule
+pub struct DotNetBuildRule {
+    /// Project directories from `[scan] project_dirs`
+    project_dirs: Vec<PathBuf>,
+}
 
-/// nvm cache rule
-pub struct NvmCacheRule;
+impl Default for DotNetBuildRule {
+    fn default() -> Self {
+        Self::new(&ScanConfig::default())
+    }
+}
+
+impl DotNetBuildRule {
+    /// Create the rule searching the project directories of `config`
+    pub fn new(config: &ScanConfig) -> Self {
+        Self {
+            project_dirs: config.project_search_dirs(),
+        }
+    }
+
+    /// Whether a directory contains a .NET project or solution file
+    fn is_dotnet_project(dir: &std::path::Path) -> bool {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries.filter_map(|e| e.ok()).any(|e| {
+                    e.path()
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .map(|ext| matches!(ext, "csproj" | "fsproj" | "vbproj" | "sln"))
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl CleanRule for DotNetBuildRule {
+    fn id(&self) -> &str {
+        "dotnet_build"
+    }
 
-impl CleanRule for NvmCacheRule {
     fn name(&self) -> &str {
-        "nvm Cache"
+        ".NET Build Artifacts"
     }
 
     fn category(&self) -> Category {
-        Category::NodeJs
+        Category::DotNet
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Medium
+        RiskLevel::Low
     }
 
     fn description(&self) -> &str {
-        "Node Version Manager installed versions"
+        ".NET project obj/ and bin/ directories (build artifacts)"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
     }
 
     fn is_applicable(&self) -> bool {
-        self.scan_paths().iter().any(|p| p.exists())
+        true // Always applicable, will scan common locations
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".nvm/versions"));
-            paths.push(home.join(".nvm/.cache"));
-        }
-        paths
+        self.project_dirs.clone()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
-            if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
-                    let desc = if path.to_string_lossy().contains(".cache") {
-                        "nvm download cache"
-                    } else {
-                        "nvm Node.js versions (keep versions you use)"
+
+        for search_dir in ctx.project_dirs() {
+            if search_dir.exists() {
+                for entry in WalkDir::new(&search_dir)
+                    .follow_links(false)
+                    .max_depth(ctx.config.scan.project_max_depth)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
+                    let path = entry.path();
+                    let is_output_dir = entry.file_type().is_dir()
+                        && path
+                            .file_name()
+                            .map(|n| n == "obj" || n == "bin")
+                            .unwrap_or(false);
+                    if !is_output_dir {
+                        continue;
+                    }
+
+                    let Some(project_dir) = path.parent() else {
+                        continue;
                     };
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        desc,
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                    if Self::is_dotnet_project(project_dir) {
+                        let size = ctx.dir_size(path);
+                        if size > ctx.min_size(self, 10) {
+                            let project_name = project_dir
+                                .file_name()
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_else(|| "unknown".to_string());
+                            items.push(CleanItem::new(
+                                path.to_path_buf(),
+                                size,
+                                format!(".NET build: {}", project_name),
+                                self.risk_level(),
+                                self.category(),
+                            ));
+                        }
+                    }
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// Bun cache rule
-pub struct BunCacheRule;
+// ============ PHP Rules ============
+
+/// Composer cache and vendor directory rule
+pub struct ComposerCacheRule;
+
+impl ComposerCacheRule {
+    /// Per-project `vendor/` directories next to a `composer.json`
+    fn vendor_dirs(ctx: &ScanContext) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+
+        for search_dir in ctx.project_dirs() {
+            if !search_dir.exists() {
+                continue;
+            }
+
+            let mut walker = WalkDir::new(&search_dir)
+                .follow_links(false)
+                .max_depth(ctx.config.scan.project_max_depth)
+                .into_iter();
+            while let Some(entry) = walker.next() {
+                let Ok(entry) = entry else {
+                    continue;
+                };
+                let path = entry.path();
+                if entry.file_type().is_dir()
+                    && path.file_name().map(|n| n == "vendor").unwrap_or(false)
+                {
+                    // Don't descend into installed packages
+                    walker.skip_current_dir();
+                    let has_manifest = path
+                        .parent()
+                        .map(|p| p.join("composer.json").exists())
+                        .unwrap_or(false);
+                    if has_manifest {
+                        dirs.push(path.to_path_buf());
+                    }
+                }
+            }
+        }
+
+        dirs
+    }
+}
+
+impl CleanRule for ComposerCacheRule {
+    fn id(&self) -> &str {
+        "composer_cache"
+    }
 
-impl CleanRule for BunCacheRule {
     fn name(&self) -> &str {
-        "Bun Cache"
+        "Composer Cache"
     }
 
     fn category(&self) -> Category {
-        Category::NodeJs
+        Category::Php
     }
 
+    // Vendor directories are medium risk; the download cache alone is low
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Low
+        RiskLevel::Medium
     }
 
     fn description(&self) -> &str {
-        "Bun package manager cache"
+        "Composer download cache and project vendor directories"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
     }
 
     fn is_applicable(&self) -> bool {
@@ -1427,116 +2786,300 @@ impl CleanRule for BunCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".bun/install/cache"));
+        // COMPOSER_CACHE_DIR replaces the default cache locations
+        if let Some(cache_dir) = crate::paths::env_dir("COMPOSER_CACHE_DIR") {
+            paths.push(cache_dir);
+        } else if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join(".composer/cache"));
+            paths.push(home.join(".cache/composer"));
+            paths.push(home.join("Library/Caches/composer"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
+
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 50 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        "Bun package cache",
-                        self.risk_level(),
+                        "Composer download cache",
+                        RiskLevel::Low,
                         self.category(),
                     ));
                 }
             }
         }
+
+        // Vendor directories must be reinstalled with `composer install`
+        for path in Self::vendor_dirs(ctx) {
+            let size = ctx.dir_size(&path);
+            if size > ctx.min_size(self, 10) {
+                let project_name = path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                items.push(CleanItem::new(
+                    path,
+                    size,
+                    format!("Composer vendor: {}", project_name),
+                    RiskLevel::Medium,
+                    self.category(),
+                ));
+            }
+        }
+
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-/// Deno cache rule
-pub struct DenoCacheRule;
+// ============ Bazel Rules ============
+
+/// Bazel output base and cache rule
+pub struct BazelCacheRule;
+
+impl BazelCacheRule {
+    /// Bazel output user roots (`_bazel_<user>` directories)
+    fn output_user_roots() -> Vec<PathBuf> {
+        let mut parents = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            parents.push(home.join(".cache/bazel"));
+        }
+        // macOS default output root
+        parents.push(PathBuf::from("/private/var/tmp"));
+
+        parents
+            .into_iter()
+            .filter_map(|parent| std::fs::read_dir(parent).ok())
+            .flat_map(|entries| entries.filter_map(|e| e.ok()))
+            .filter(|e| e.file_name().to_string_lossy().starts_with("_bazel_"))
+            .map(|e| e.path())
+            .filter(|p| p.is_dir() && !p.is_symlink())
+            .collect()
+    }
+
+    /// Output bases are named by the MD5 of their workspace path
+    fn is_output_base(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.len() == 32 && n.chars().all(|c| c.is_ascii_hexdigit()))
+            .unwrap_or(false)
+    }
+
+    /// Workspace an output base belongs to, as recorded by Bazel
+    fn workspace_of(output_base: &Path) -> Option<PathBuf> {
+        std::fs::read_to_string(output_base.join("DO_NOT_BUILD_HERE"))
+            .ok()
+            .map(|s| PathBuf::from(s.trim()))
+            .filter(|p| p.is_dir())
+    }
+
+    /// Check if the bazel binary is available
+    fn bazel_available() -> bool {
+        Command::new("bazel")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Remove the `bazel-*` convenience symlinks left dangling in a workspace
+    fn remove_dangling_symlinks(workspace: &Path) {
+        if let Ok(entries) = std::fs::read_dir(workspace) {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                let is_convenience_link =
+                    entry.file_name().to_string_lossy().starts_with("bazel-") && path.is_symlink();
+                if is_convenience_link && !path.exists() {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+    }
+}
+
+impl CleanRule for BazelCacheRule {
+    fn id(&self) -> &str {
+        "bazel_cache"
+    }
 
-impl CleanRule for DenoCacheRule {
     fn name(&self) -> &str {
-        "Deno Cache"
+        "Bazel Cache"
     }
 
     fn category(&self) -> Category {
-        Category::NodeJs
+        Category::Bazel
     }
 
+    // Output bases are medium risk; the repository/disk cache alone is low
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Low
+        RiskLevel::Medium
     }
 
     fn description(&self) -> &str {
-        "Deno runtime cache"
+        "Bazel repository/disk caches and per-workspace output bases"
     }
 
     fn is_applicable(&self) -> bool {
-        self.scan_paths().iter().any(|p| p.exists())
+        !Self::output_user_roots().is_empty()
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(cache) = dirs::cache_dir() {
-            paths.push(cache.join("deno"));
-        }
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".deno"));
-            paths.push(home.join("Library/Caches/deno"));
-        }
-        paths
+        Self::output_user_roots()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
-            if path.exists() {
-                let size = dir_size(&path);
-                if size > 50 * 1024 * 1024 {
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "Deno cache",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+
+        for root in Self::output_user_roots() {
+            let Ok(entries) = std::fs::read_dir(&root) else {
+                continue;
+            };
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if !entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    continue;
+                }
+
+                // dir_size doesn't follow symlinks, so the bazel-* links and
+                // execroot links into the cache are not counted twice
+                if entry.file_name() == "cache" {
+                    let size = ctx.dir_size(&path);
+                    if size > ctx.min_size(self, 0) {
+                        items.push(
+                            CleanItem::new(
+                                path,
+                                size,
+                                "Bazel repository/disk cache",
+                                RiskLevel::Low,
+                                self.category(),
+                            )
+                            .with_handler(self.id()),
+                        );
+                    }
+                } else if Self::is_output_base(&path) {
+                    let size = ctx.dir_size(&path);
+                    if size > ctx.min_size(self, 50) {
+                        let workspace = Self::workspace_of(&path)
+                            .map(|w| w.display().to_string())
+                            .unwrap_or_else(|| "unknown workspace".to_string());
+                        items.push(
+                            CleanItem::new(
+                                path,
+                                size,
+                                format!("Bazel output base: {}", workspace),
+                                RiskLevel::Medium,
+                                self.category(),
+                            )
+                            .with_handler(self.id()),
+                        );
+                    }
                 }
             }
         }
+
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
-        clean_items(items, to_trash)
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+        let bazel = Self::bazel_available();
+
+        for item in items {
+            let workspace = Self::workspace_of(&item.path);
+
+            // Let Bazel shut down its server and expunge the output base itself
+            if let (true, Some(workspace)) = (bazel, &workspace) {
+                match Command::new("bazel")
+                    .args(["clean", "--expunge"])
+                    .current_dir(workspace)
+                    .output()
+                {
+                    Ok(output) if output.status.success() => {
+                        result.cleaned_count += 1;
+                        result.bytes_freed += item.size;
+                        continue;
+                    }
+                    Ok(output) => {
+                        tracing::warn!(
+                            "bazel clean --expunge failed in {}: {}",
+                            workspace.display(),
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to run bazel: {}", e);
+                    }
+                }
+            }
+
+            result.merge(clean_items(std::slice::from_ref(item), to_trash)?);
+            if let Some(workspace) = &workspace {
+                Self::remove_dangling_symlinks(workspace);
+            }
+        }
+
+        Ok(result)
     }
 }
 
-// ============ Rustup Rule ============
+// ============ Haskell & OCaml Rules ============
 
-/// Rustup cache rule
-pub struct RustupCacheRule;
+/// Cabal and Stack cache rule
+pub struct CabalStackCacheRule;
+
+impl CabalStackCacheRule {
+    /// Stack root, honouring STACK_ROOT
+    fn stack_root() -> Option<PathBuf> {
+        crate::paths::env_dir("STACK_ROOT")
+            .or_else(|| crate::paths::home_dir().map(|h| h.join(".stack")))
+    }
+
+    /// Cache directories with their descriptions
+    fn caches() -> Vec<(PathBuf, &'static str)> {
+        let mut caches = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            caches.push((home.join(".cabal/packages"), "Cabal package cache"));
+            // Newer cabal-install versions follow the XDG layout
+            caches.push((home.join(".cache/cabal/packages"), "Cabal package cache"));
+        }
+        if let Some(stack) = Self::stack_root() {
+            caches.push((stack.join("indices"), "Stack package indices"));
+            caches.push((stack.join("snapshots"), "Stack snapshot builds"));
+        }
+        caches
+    }
+}
+
+impl CleanRule for CabalStackCacheRule {
+    fn id(&self) -> &str {
+        "cabal_stack_cache"
+    }
 
-impl CleanRule for RustupCacheRule {
     fn name(&self) -> &str {
-        "Rustup Toolchains"
+        "Cabal/Stack Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Rust
+        Category::Haskell
     }
 
     fn risk_level(&self) -> RiskLevel {
-        RiskLevel::Medium
+        RiskLevel::Low
     }
 
     fn description(&self) -> &str {
-        "Rustup installed toolchains and components"
+        "Cabal package cache and Stack indices and snapshots"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1544,32 +3087,19 @@ impl CleanRule for RustupCacheRule {
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".rustup/toolchains"));
-            paths.push(home.join(".rustup/downloads"));
-            paths.push(home.join(".rustup/tmp"));
-        }
-        paths
+        Self::caches().into_iter().map(|(path, _)| path).collect()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        for path in self.scan_paths() {
+        for (path, description) in Self::caches() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 500 * 1024 * 1024 {
-                    let desc = if path.to_string_lossy().contains("toolchains") {
-                        "Rustup toolchains (keep versions you use)"
-                    } else if path.to_string_lossy().contains("downloads") {
-                        "Rustup downloads cache"
-                    } else {
-                        "Rustup temporary files"
-                    };
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
                     items.push(CleanItem::new(
                         path,
                         size,
-                        desc,
+                        description,
                         self.risk_level(),
                         self.category(),
                     ));
@@ -1579,23 +3109,42 @@ impl CleanRule for RustupCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
-// ============ Ruby Rule ============
+/// opam download cache and log rule
+pub struct OpamCacheRule;
 
-/// Ruby cache rule
-pub struct RubyCacheRule;
+impl OpamCacheRule {
+    /// opam root, honouring OPAMROOT
+    fn opam_root() -> Option<PathBuf> {
+        crate::paths::env_dir("OPAMROOT")
+            .or_else(|| crate::paths::home_dir().map(|h| h.join(".opam")))
+    }
+
+    /// Check if the opam binary is available
+    fn opam_available() -> bool {
+        Command::new("opam")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
+impl CleanRule for OpamCacheRule {
+    fn id(&self) -> &str {
+        "opam_cache"
+    }
 
-impl CleanRule for RubyCacheRule {
     fn name(&self) -> &str {
-        "Ruby Gems Cache"
+        "opam Cache"
     }
 
     fn category(&self) -> Category {
-        Category::Other("Ruby".to_string())
+        Category::OCaml
     }
 
     fn risk_level(&self) -> RiskLevel {
@@ -1603,7 +3152,7 @@ impl CleanRule for RubyCacheRule {
     }
 
     fn description(&self) -> &str {
-        "Ruby gems and bundle cache"
+        "opam download cache and logs"
     }
 
     fn is_applicable(&self) -> bool {
@@ -1611,42 +3160,493 @@ impl CleanRule for RubyCacheRule {
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.join(".gem"));
-            paths.push(home.join(".bundle/cache"));
-            paths.push(home.join(".rbenv/versions"));
-        }
-        paths
+        Self::opam_root()
+            .map(|root| vec![root.join("download-cache"), root.join("log")])
+            .unwrap_or_default()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
-                    let desc = if path.to_string_lossy().contains("rbenv") {
-                        "rbenv Ruby versions"
-                    } else if path.to_string_lossy().contains("bundle") {
-                        "Bundler cache"
-                    } else {
-                        "Ruby gems cache"
-                    };
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        desc,
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                let size = ctx.dir_size(&path);
+                if size > ctx.min_size(self, 0) {
+                    items.push(
+                        CleanItem::new(
+                            path,
+                            size,
+                            "opam cache",
+                            self.risk_level(),
+                            self.category(),
+                        )
+                        .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        // `opam clean` clears the download cache and logs in one go
+        if !items.is_empty() && Self::opam_available() {
+            match Command::new("opam").args(["clean", "--yes"]).output() {
+                Ok(output) if output.status.success() => {
+                    return Ok(CleanResult {
+                        cleaned_count: items.len(),
+                        bytes_freed: items.iter().map(|i| i.size).sum(),
+                        ..Default::default()
+                    });
+                }
+                Ok(output) => {
+                    tracing::warn!(
+                        "opam clean failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to run opam: {}", e);
+                }
+            }
+        }
+
         clean_items(items, to_trash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{env_read, with_env};
+
+    #[test]
+    fn test_bazel_output_base_detection() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("0123456789abcdef0123456789abcdef");
+        std::fs::create_dir(&base).unwrap();
+        std::fs::write(
+            base.join("DO_NOT_BUILD_HERE"),
+            dir.path().display().to_string(),
+        )
+        .unwrap();
+
+        assert!(BazelCacheRule::is_output_base(&base));
+        assert!(!BazelCacheRule::is_output_base(&dir.path().join("cache")));
+        assert_eq!(
+            BazelCacheRule::workspace_of(&base),
+            Some(dir.path().to_path_buf())
+        );
+    }
+
+    #[test]
+    fn test_go_build_cache_from_env_without_go_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("go-build");
+        std::fs::create_dir_all(cache.join("00")).unwrap();
+        std::fs::write(cache.join("00/0000-d"), vec![0u8; 1024]).unwrap();
+        let empty_path = dir.path().join("bin");
+
+        with_env(
+            &[
+                ("PATH", Some(empty_path.as_path())),
+                ("GOCACHE", Some(cache.as_path())),
+            ],
+            || {
+                let items = GoBuildCacheRule::default()
+                    .scan(&ScanContext::default())
+                    .unwrap();
+                assert_eq!(items.len(), 1);
+                assert_eq!(items[0].path, cache);
+                assert_eq!(items[0].handler.as_deref(), Some("go_build_cache"));
+            },
+        );
+        with_env(
+            &[
+                ("PATH", Some(empty_path.as_path())),
+                ("GOCACHE", Some(Path::new("off"))),
+            ],
+            || {
+                assert!(GoBuildCacheRule::default().scan_paths().is_empty());
+            },
+        );
+
+        // A relative GOCACHE is ignored in favour of the default location
+        with_env(
+            &[
+                ("PATH", Some(empty_path.as_path())),
+                ("XDG_CACHE_HOME", Some(dir.path())),
+                ("GOCACHE", Some(Path::new("go-cache"))),
+            ],
+            || {
+                assert_eq!(
+                    GoBuildCacheRule::default().scan_paths(),
+                    std::slice::from_ref(&cache)
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_haskell_and_ocaml_rules_applicable_with_their_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let env = [
+            ("HOME", Some(home.path())),
+            ("STACK_ROOT", None),
+            ("OPAMROOT", None),
+        ];
+
+        with_env(&env, || {
+            assert!(!CabalStackCacheRule.is_applicable());
+            assert!(!OpamCacheRule.is_applicable());
+        });
+
+        std::fs::create_dir_all(home.path().join(".stack/indices")).unwrap();
+        with_env(&env, || {
+            assert!(CabalStackCacheRule.is_applicable());
+            assert!(!OpamCacheRule.is_applicable());
+        });
+
+        std::fs::create_dir_all(home.path().join(".opam/download-cache")).unwrap();
+        with_env(&env, || assert!(OpamCacheRule.is_applicable()));
+
+        // Relative roots fall back to the defaults under the home directory
+        let relative = [
+            ("HOME", Some(home.path())),
+            ("STACK_ROOT", Some(Path::new("stack"))),
+            ("OPAMROOT", Some(Path::new("opam"))),
+        ];
+        with_env(&relative, || {
+            assert_eq!(
+                CabalStackCacheRule::stack_root(),
+                Some(home.path().join(".stack"))
+            );
+            assert_eq!(OpamCacheRule::opam_root(), Some(home.path().join(".opam")));
+        });
+    }
+
+    #[test]
+    fn test_ml_caches_honour_env_overrides() {
+        let home = tempfile::tempdir().unwrap();
+        let hf = home.path().join("hf");
+        std::fs::create_dir_all(hf.join("hub")).unwrap();
+        std::fs::write(hf.join("hub/model.safetensors"), vec![0u8; 1024]).unwrap();
+        let keras = home.path().join(".keras/models");
+        std::fs::create_dir_all(&keras).unwrap();
+        std::fs::write(keras.join("weights.h5"), vec![0u8; 512]).unwrap();
+
+        let env = [
+            ("HOME", Some(home.path())),
+            ("HF_HOME", Some(hf.as_path())),
+            ("TORCH_HOME", None),
+            ("KERAS_HOME", None),
+            ("XDG_CACHE_HOME", None),
+            ("XDG_DATA_HOME", None),
+            ("JUPYTER_DATA_DIR", None),
+        ];
+        with_env(&env, || {
+            assert!(MlCacheRule.is_applicable());
+            let ctx = ScanContext::default().include_small(true);
+            let mut items = MlCacheRule.scan(&ctx).unwrap();
+            items.sort_by(|a, b| a.path.cmp(&b.path));
+
+            let found: Vec<_> = items.iter().map(|i| (i.path.clone(), i.size)).collect();
+            assert_eq!(
+                found,
+                [(home.path().join(".keras"), 512), (hf.clone(), 1024)]
+            );
+            assert!(items.iter().all(|i| i.risk_level == RiskLevel::Medium));
+        });
+    }
+
+    #[test]
+    fn test_ml_caches_ignore_empty_and_relative_env() {
+        let home = tempfile::tempdir().unwrap();
+        let env = [
+            ("HOME", Some(home.path())),
+            ("XDG_CACHE_HOME", Some(Path::new(""))),
+            ("HF_HOME", Some(Path::new("relative/hf"))),
+            ("TORCH_HOME", Some(Path::new(""))),
+            ("KERAS_HOME", Some(Path::new("keras"))),
+            ("JUPYTER_DATA_DIR", Some(Path::new(""))),
+        ];
+        with_env(&env, || {
+            let paths = MlCacheRule.scan_paths();
+            assert_eq!(paths.len(), 4);
+            assert!(
+                paths.iter().all(|p| p.starts_with(home.path())),
+                "{:?}",
+                paths
+            );
+            assert_eq!(paths[0], home.path().join(".cache/huggingface"));
+        });
+    }
+
+    #[test]
+    fn test_pyenv_root_must_be_absolute() {
+        let home = tempfile::tempdir().unwrap();
+        let default = home.path().join(".pyenv/versions");
+        for root in ["", "relative/pyenv"] {
+            let env = [
+                ("HOME", Some(home.path())),
+                ("PYENV_ROOT", Some(Path::new(root))),
+            ];
+            with_env(&env, || {
+                assert_eq!(
+                    PyenvVersionsRule.scan_paths(),
+                    std::slice::from_ref(&default)
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn test_nuget_packages_override_is_described_by_path() {
+        let home = tempfile::tempdir().unwrap();
+        let packages = home.path().join("nuget-cache");
+        std::fs::create_dir_all(&packages).unwrap();
+        std::fs::write(packages.join("pkg.nupkg"), vec![0u8; 64]).unwrap();
+
+        with_env(
+            &[
+                ("HOME", Some(home.path())),
+                ("NUGET_PACKAGES", Some(&packages)),
+            ],
+            || {
+                let items = NuGetCacheRule.scan(&ScanContext::default()).unwrap();
+                assert_eq!(items.len(), 1);
+                assert_eq!(items[0].path, packages);
+                assert_eq!(items[0].description, "NuGet global packages");
+            },
+        );
+
+        // A relative override falls back to the default location
+        with_env(
+            &[
+                ("HOME", Some(home.path())),
+                ("NUGET_PACKAGES", Some(Path::new("packages"))),
+            ],
+            || {
+                assert_eq!(
+                    NuGetCacheRule.scan_paths()[0],
+                    home.path().join(".nuget/packages")
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_editor_rules_keep_extensions_separate() {
+        let home = tempfile::tempdir().unwrap();
+        let support = home.path().join("Library/Application Support");
+        let dirs = [
+            home.path().join(".vscode/extensions"),
+            support.join("Code/Cache"),
+            support.join("Code/CachedData"),
+            support.join("Code/User/workspaceStorage"),
+            support.join("Code/logs"),
+            home.path().join(".cursor/extensions"),
+            support.join("Cursor/Cache"),
+            support.join("Cursor/User/workspaceStorage"),
+        ];
+        for dir in &dirs {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(dir.join("data"), vec![0u8; 64]).unwrap();
+        }
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let ctx = ScanContext::default().include_small(true);
+            let found = |rule: &dyn CleanRule| -> Vec<(PathBuf, RiskLevel)> {
+                let mut items: Vec<_> = rule
+                    .scan(&ctx)
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| (item.path, item.risk_level))
+                    .collect();
+                items.sort();
+                items
+            };
+            let low = |path: &PathBuf| (path.clone(), RiskLevel::Low);
+
+            assert_eq!(found(&VSCodeCacheRule), [low(&dirs[1])]);
+            assert_eq!(
+                found(&VSCodeWorkspaceStorageRule),
+                [low(&dirs[2]), low(&dirs[3]), low(&dirs[4])]
+            );
+            assert_eq!(
+                found(&VSCodeExtensionsRule),
+                [(dirs[0].clone(), RiskLevel::Medium)]
+            );
+            assert_eq!(found(&CursorCacheRule), [low(&dirs[6])]);
+            assert_eq!(found(&CursorWorkspaceStorageRule), [low(&dirs[7])]);
+            assert_eq!(
+                found(&CursorExtensionsRule),
+                [(dirs[5].clone(), RiskLevel::Medium)]
+            );
+        });
+    }
+
+    #[test]
+    fn test_lsp_cache_rule_finds_language_server_caches() {
+        let home = tempfile::tempdir().unwrap();
+        let cache = home.path().join(".cache");
+        with_env(
+            &[
+                ("HOME", Some(home.path())),
+                ("XDG_CACHE_HOME", Some(cache.as_path())),
+            ],
+            || {
+                assert!(!LspCacheRule.is_applicable());
+
+                let cache = crate::paths::cache_dir().unwrap();
+                for name in ["rust-analyzer", "clangd/index"] {
+                    std::fs::create_dir_all(cache.join(name)).unwrap();
+                    std::fs::write(cache.join(name).join("data"), vec![0u8; 64]).unwrap();
+                }
+                assert!(LspCacheRule.is_applicable());
+
+                let ctx = ScanContext::default().include_small(true);
+                let mut found: Vec<_> = LspCacheRule
+                    .scan(&ctx)
+                    .unwrap()
+                    .into_iter()
+                    .map(|item| (item.path, item.category.to_string()))
+                    .collect();
+                found.sort();
+                assert_eq!(
+                    found,
+                    [
+                        (cache.join("clangd"), "LSP".to_string()),
+                        (cache.join("rust-analyzer"), "LSP".to_string())
+                    ]
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_node_modules_only_stale_projects() {
+        let home = tempfile::tempdir().unwrap();
+        let projects = home.path().join("Projects");
+        let old_project = projects.join("old-app");
+        let fresh_project = projects.join("fresh-app");
+        for project in [&old_project, &fresh_project] {
+            std::fs::create_dir_all(project.join("node_modules/dep/node_modules/inner")).unwrap();
+            std::fs::write(project.join("node_modules/dep/index.js"), vec![0u8; 1024]).unwrap();
+            std::fs::write(project.join("package.json"), "{}").unwrap();
+        }
+        let long_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(90 * 86400);
+        std::fs::File::options()
+            .write(true)
+            .open(old_project.join("package.json"))
+            .unwrap()
+            .set_modified(long_ago)
+            .unwrap();
+
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [thresholds.rules]
+            "Stale node_modules" = 0
+            "#,
+        )
+        .unwrap();
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let rule = NodeModulesRule::new(&config.scan);
+            let items = rule.scan(&ScanContext::new(config)).unwrap();
+
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].path, old_project.join("node_modules"));
+            assert!(items[0].description.contains("old-app"));
+            assert!(items[0].description.contains("90 days"));
+            assert_eq!(items[0].risk_level, RiskLevel::Medium);
+        });
+    }
+
+    #[test]
+    fn test_cargo_target_depth_and_project_dirs_configurable() {
+        let home = tempfile::tempdir().unwrap();
+        let project = home.path().join("git/org/team/area/group/app");
+        std::fs::create_dir_all(project.join("target/debug")).unwrap();
+        std::fs::write(project.join("Cargo.toml"), "[package]").unwrap();
+        std::fs::write(project.join("target/debug/app"), vec![0u8; 1024]).unwrap();
+
+        let config = |depth: usize| -> Config {
+            toml::from_str(&format!(
+                r#"
+                [scan]
+                project_dirs = ["~/git"]
+                project_max_depth = {}
+
+                [thresholds.rules]
+                "Rust Build Artifacts" = 0
+                "#,
+                depth
+            ))
+            .unwrap()
+        };
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            // target sits six levels below ~/git
+            let shallow = CargoTargetRule.scan(&ScanContext::new(config(4))).unwrap();
+            assert!(shallow.is_empty());
+
+            let deep = CargoTargetRule.scan(&ScanContext::new(config(6))).unwrap();
+            assert_eq!(deep.len(), 1);
+            assert_eq!(deep[0].path, project.join("target"));
+        });
+    }
+
+    #[test]
+    fn test_project_rules_use_the_given_project_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir(home.path().join("git")).unwrap();
+        let config: Config = toml::from_str(
+            r#"
+            [scan]
+            project_dirs = ["~/git"]
+            "#,
+        )
+        .unwrap();
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            assert!(!NodeModulesRule::default().is_applicable());
+
+            let rule = NodeModulesRule::new(&config.scan);
+            assert!(rule.is_applicable());
+            assert_eq!(rule.scan_paths(), [home.path().join("git")]);
+        });
+    }
+
+    #[test]
+    fn test_composer_applicable_with_cache_in_home() {
+        let home = tempfile::tempdir().unwrap();
+
+        with_env(
+            &[("HOME", Some(home.path())), ("COMPOSER_CACHE_DIR", None)],
+            || {
+                assert!(!ComposerCacheRule.is_applicable());
+
+                std::fs::create_dir_all(home.path().join(".cache/composer/files")).unwrap();
+                assert!(ComposerCacheRule.is_applicable());
+            },
+        );
+
+        // Empty and relative overrides fall back to the default locations
+        for value in ["", "composer-cache"] {
+            with_env(
+                &[
+                    ("HOME", Some(home.path())),
+                    ("COMPOSER_CACHE_DIR", Some(Path::new(value))),
+                ],
+                || {
+                    assert!(
+                        ComposerCacheRule
+                            .scan_paths()
+                            .contains(&home.path().join(".cache/composer"))
+                    );
+                },
+            );
+        }
+    }
+}
@@ -1,13 +1,160 @@
 //! Docker cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
-use std::path::PathBuf;
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext};
+use crate::util::parse_size;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
+
+/// Prefix used for the virtual paths of Docker items
+const VIRTUAL_PREFIX: &str = "docker://";
+
+/// A single row of `docker system df --format "{{json .}}"`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerDfEntry {
+    /// Resource type ("Images", "Containers", "Local Volumes", "Build Cache")
+    #[serde(rename = "Type")]
+    kind: String,
+    /// Total number of objects of this type
+    #[serde(default)]
+    total_count: String,
+    /// Number of objects in use
+    #[serde(default)]
+    active: String,
+    /// Reclaimable size, e.g. "1.2GB (50%)"
+    #[serde(default)]
+    reclaimable: String,
+}
+
+/// Kind of Docker resource that can be pruned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockerResource {
+    /// Unused images
+    Images,
+    /// Build cache
+    BuildCache,
+    /// Stopped containers
+    Containers,
+    /// Unused local volumes
+    Volumes,
+}
+
+impl DockerResource {
+    /// All resource kinds, in display order
+    pub const ALL: [DockerResource; 4] = [
+        DockerResource::Images,
+        DockerResource::BuildCache,
+        DockerResource::Containers,
+        DockerResource::Volumes,
+    ];
+
+    /// Map a `docker system df` type column to a resource
+    fn from_df_type(kind: &str) -> Option<Self> {
+        match kind {
+            "Images" => Some(Self::Images),
+            "Build Cache" => Some(Self::BuildCache),
+            "Containers" => Some(Self::Containers),
+            "Local Volumes" => Some(Self::Volumes),
+            _ => None,
+        }
+    }
+
+    /// Short machine name used in the virtual path
+    fn slug(&self) -> &'static str {
+        match self {
+            Self::Images => "images",
+            Self::BuildCache => "build-cache",
+            Self::Containers => "containers",
+            Self::Volumes => "volumes",
+        }
+    }
+
+    /// Virtual path identifying this resource in a `CleanItem`
+    pub fn virtual_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}{}", VIRTUAL_PREFIX, self.slug()))
+    }
+
+    /// Recover the resource from a virtual path
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let slug = path.to_str()?.strip_prefix(VIRTUAL_PREFIX)?;
+        Self::ALL.into_iter().find(|r| r.slug() == slug)
+    }
+
+    /// Arguments for the matching `docker ... prune` subcommand
+    fn prune_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Images => &["image", "prune", "-a", "-f"],
+            Self::BuildCache => &["builder", "prune", "-a", "-f"],
+            Self::Containers => &["container", "prune", "-f"],
+            Self::Volumes => &["volume", "prune", "-f"],
+        }
+    }
+
+    /// Risk of pruning this resource
+    pub fn risk_level(&self) -> RiskLevel {
+        match self {
+            // Volumes hold application data, never prune them without confirmation
+            Self::Volumes => RiskLevel::High,
+            _ => RiskLevel::Medium,
+        }
+    }
+
+    /// Human readable description
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Images => "Unused Docker images",
+            Self::BuildCache => "Docker build cache",
+            Self::Containers => "Stopped Docker containers",
+            Self::Volumes => "Unused Docker volumes (may contain data)",
+        }
+    }
+}
 
 /// Docker cleanup rule
-pub struct DockerRule;
+///
+/// A single `docker system df` call both checks that the daemon is running
+/// and sizes every resource type, so a scan spawns docker only once.
+pub struct DockerRule {
+    /// Rows fetched by `is_applicable`, consumed by the following `scan`
+    df: Mutex<Option<Vec<DockerDfEntry>>>,
+    /// Runs `docker system df`; replaced in tests to count spawns
+    query: fn() -> crate::Result<Vec<DockerDfEntry>>,
+}
+
+impl Default for DockerRule {
+    fn default() -> Self {
+        Self {
+            df: Mutex::default(),
+            query: Self::system_df,
+        }
+    }
+}
+
+impl DockerRule {
+    /// Query `docker system df` and parse it into structured rows
+    fn system_df() -> crate::Result<Vec<DockerDfEntry>> {
+        let output = Command::new("docker")
+            .args(["system", "df", "--format", "{{json .}}"])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(crate::Error::Other(format!(
+                "docker system df failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(parse_df_output(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
 
 impl CleanRule for DockerRule {
+    fn id(&self) -> &str {
+        "docker"
+    }
+
     fn name(&self) -> &str {
         "Docker Cleanup"
     }
@@ -21,121 +168,82 @@ impl CleanRule for DockerRule {
     }
 
     fn description(&self) -> &str {
-        "Dangling images, stopped containers, and unused networks"
+        "Unused images, build cache, stopped containers, and unused volumes"
     }
 
     fn is_applicable(&self) -> bool {
-        // Check if docker command exists and is running
-        Command::new("docker")
-            .arg("info")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
+        // Fails unless the docker command exists and the daemon is running
+        let Ok(entries) = (self.query)() else {
+            return false;
+        };
+        if let Ok(mut df) = self.df.lock() {
+            *df = Some(entries);
+        }
+        true
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         Vec::new() // Not path based
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, _ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
-        // Check size of reclaimable space
-        let output = Command::new("docker")
-            .args(["system", "df", "--format", "{{.Type}}\t{{.Reclaimable}}"])
-            .output()?;
+        let cached = self.df.lock().ok().and_then(|mut df| df.take());
+        let entries = match cached {
+            Some(entries) => entries,
+            None => (self.query)()?,
+        };
 
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let _total_size = 0;
-            let _details = String::new();
-
-            for line in stdout.lines() {
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() >= 2 {
-                    // unexpected format parsing is tricky with units,
-                    // attempting to parse "1.2GB" or similar is complex without a library.
-                    // For now, let's rely on a simpler check for count of objects.
-                }
-            }
-        }
-
-        // Alternative: Count dangling images
-        let images_out = Command::new("docker")
-            .args(["images", "-f", "dangling=true", "--format", "{{.Size}}"])
-            .output()?;
+        for entry in entries {
+            let Some(resource) = DockerResource::from_df_type(&entry.kind) else {
+                continue;
+            };
 
-        if images_out.status.success() {
-            let stdout = String::from_utf8_lossy(&images_out.stdout);
-            let mut size = 0;
-            let mut count = 0;
-
-            for line in stdout.lines() {
-                // Parse size roughly (e.g. "100MB")
-                // This is a bit fragile without a proper size parser.
-                // Let's assume 0 size for safety if parsing fails, but count items.
-                size += parse_docker_size(line);
-                count += 1;
+            let size = parse_reclaimable(&entry.reclaimable);
+            if size == 0 {
+                continue;
             }
 
-            if count > 0 {
-                items.push(CleanItem::new(
-                    PathBuf::from("Docker Dangling Imagess"), // Virtual path
+            items.push(
+                CleanItem::new(
+                    resource.virtual_path(),
                     size,
-                    format!("{} dangling images", count),
-                    self.risk_level(),
+                    format!(
+                        "{} ({} total, {} active)",
+                        resource.description(),
+                        entry.total_count,
+                        entry.active
+                    ),
+                    resource.risk_level(),
                     self.category(),
-                ));
-            }
-        }
-
-        // Check stopped containers
-        let containers_out = Command::new("docker")
-            .args(["ps", "-a", "-f", "status=exited", "-q"])
-            .output()?;
-
-        if containers_out.status.success() {
-            let count = String::from_utf8_lossy(&containers_out.stdout)
-                .lines()
-                .count();
-            if count > 0 {
-                items.push(CleanItem::new(
-                    PathBuf::from("Docker Stopped Containers"),
-                    0, // Hard to get exact reclaimable size easily
-                    format!("{} stopped containers", count),
-                    self.risk_level(),
-                    self.category(),
-                ));
-            }
+                )
+                .with_handler(self.id()),
+            );
         }
 
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
         for item in items {
-            let mut cmd = Command::new("docker");
-            if item.path.to_string_lossy().contains("Images") {
-                cmd.args(["image", "prune", "-f"]);
-            } else if item.path.to_string_lossy().contains("Containers") {
-                cmd.args(["container", "prune", "-f"]);
-            } else {
+            let Some(resource) = DockerResource::from_path(&item.path) else {
                 continue;
-            }
+            };
 
-            match cmd.output() {
+            match Command::new("docker").args(resource.prune_args()).output() {
+                Ok(output) if output.status.success() => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    result.cleaned_count += 1;
+                    result.bytes_freed += parse_reclaimed(&stdout).unwrap_or(item.size);
+                }
                 Ok(output) => {
-                    if output.status.success() {
-                        result.cleaned_count += 1;
-                        result.bytes_freed += item.size;
-                    } else {
-                        result.failed.push((
-                            item.path.clone(),
-                            String::from_utf8_lossy(&output.stderr).to_string(),
-                        ));
-                    }
+                    result.failed.push((
+                        item.path.clone(),
+                        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                    ));
                 }
                 Err(e) => {
                     result.failed.push((item.path.clone(), e.to_string()));
@@ -147,23 +255,85 @@ impl CleanRule for DockerRule {
     }
 }
 
-fn parse_docker_size(size_str: &str) -> u64 {
-    let s = size_str.trim();
-    if s.is_empty() {
-        return 0;
-    }
-
-    let bytes = if s.ends_with("GB") {
-        s.trim_end_matches("GB").parse::<f64>().unwrap_or(0.0) * 1_000_000_000.0
-    } else if s.ends_with("MB") {
-        s.trim_end_matches("MB").parse::<f64>().unwrap_or(0.0) * 1_000_000.0
-    } else if s.ends_with("KB") {
-        s.trim_end_matches("KB").parse::<f64>().unwrap_or(0.0) * 1_000.0
-    } else if s.ends_with("B") {
-        s.trim_end_matches("B").parse::<f64>().unwrap_or(0.0)
-    } else {
-        0.0
-    };
-
-    bytes as u64
+/// Parse the newline-delimited JSON output of `docker system df`
+fn parse_df_output(stdout: &str) -> Vec<DockerDfEntry> {
+    stdout
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// Parse a reclaimable column such as "1.2GB (50%)"
+///
+/// Docker reports sizes with decimal (1000-based) units.
+fn parse_reclaimable(s: &str) -> u64 {
+    s.split_whitespace()
+        .next()
+        .and_then(|size| parse_size(size, false))
+        .unwrap_or(0)
+}
+
+/// Parse the "Total reclaimed space: 1.2GB" line printed by `docker ... prune`
+fn parse_reclaimed(stdout: &str) -> Option<u64> {
+    stdout
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Total reclaimed space:"))
+        .and_then(|s| parse_size(s, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const DF_OUTPUT: &str = r#"{"Active":"2","Reclaimable":"1.5GB (60%)","Size":"2.5GB","TotalCount":"5","Type":"Images"}
+{"Active":"1","Reclaimable":"0B (0%)","Size":"10MB","TotalCount":"1","Type":"Containers"}
+{"Active":"0","Reclaimable":"300MB (100%)","Size":"300MB","TotalCount":"3","Type":"Local Volumes"}
+{"Active":"0","Reclaimable":"42.1kB","Size":"42.1kB","TotalCount":"7","Type":"Build Cache"}
+"#;
+
+    #[test]
+    fn test_df_output_sized_per_resource() {
+        let sizes: Vec<_> = parse_df_output(DF_OUTPUT)
+            .iter()
+            .filter_map(|entry| {
+                let resource = DockerResource::from_df_type(&entry.kind)?;
+                Some((resource, parse_reclaimable(&entry.reclaimable)))
+            })
+            .collect();
+
+        assert_eq!(
+            sizes,
+            [
+                (DockerResource::Images, 1_500_000_000),
+                (DockerResource::Containers, 0),
+                (DockerResource::Volumes, 300_000_000),
+                (DockerResource::BuildCache, 42_100),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_spawns_docker_once() {
+        let _env = env_read();
+        static SPAWNS: AtomicUsize = AtomicUsize::new(0);
+        let rule = DockerRule {
+            query: || {
+                SPAWNS.fetch_add(1, Ordering::Relaxed);
+                Ok(parse_df_output(DF_OUTPUT))
+            },
+            ..Default::default()
+        };
+
+        // As a scan followed by `list`-style checks would call it
+        let ctx = ScanContext::default();
+        assert!(ctx.is_applicable(&rule));
+        let items = rule.scan(&ctx).unwrap();
+        assert!(ctx.is_applicable(&rule));
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(SPAWNS.load(Ordering::Relaxed), 1);
+    }
 }
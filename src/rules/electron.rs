@@ -0,0 +1,184 @@
+//! Electron application cache rule
+//!
+//! Electron apps keep Chromium caches inside their data folder
+//! (`~/Library/Application Support/<App>` on macOS, `~/.config/<app>` on Linux).
+
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, remove_path};
+use std::path::PathBuf;
+
+/// Known Electron apps: display name and data folder name
+pub const ELECTRON_APPS: &[(&str, &str)] = &[
+    ("Slack", "Slack"),
+    ("Discord", "discord"),
+    ("Spotify", "Spotify"),
+    ("Microsoft Teams", "Microsoft Teams"),
+    ("Notion", "Notion"),
+    ("Obsidian", "obsidian"),
+];
+
+/// Chromium cache subdirectories that are safe to delete
+const CACHE_SUBPATHS: &[&str] = &[
+    "Cache",
+    "Code Cache",
+    "GPUCache",
+    "Service Worker/CacheStorage",
+];
+
+/// Electron apps cache rule (Slack, Discord, Spotify, Teams, Notion, Obsidian)
+pub struct ElectronAppCacheRule;
+
+impl ElectronAppCacheRule {
+    /// Data folder of each known app that exists, with its display name
+    fn app_dirs(&self) -> Vec<(&'static str, PathBuf)> {
+        // Electron keeps app data in the platform config directory
+        let Some(base) = crate::paths::config_dir() else {
+            return Vec::new();
+        };
+
+        let mut dirs = Vec::new();
+        for (name, folder) in ELECTRON_APPS {
+            let candidates = if cfg!(target_os = "linux") {
+                // Linux builds usually use a lowercase folder; Teams nests under Microsoft
+                vec![
+                    base.join(folder),
+                    base.join(folder.to_lowercase()),
+                    base.join("Microsoft").join(folder),
+                ]
+            } else {
+                vec![base.join(folder)]
+            };
+            if let Some(dir) = candidates.into_iter().find(|p| p.is_dir()) {
+                dirs.push((*name, dir));
+            }
+        }
+        dirs
+    }
+}
+
+impl CleanRule for ElectronAppCacheRule {
+    fn id(&self) -> &str {
+        "electron_app_caches"
+    }
+
+    fn name(&self) -> &str {
+        "Electron App Caches"
+    }
+
+    fn category(&self) -> Category {
+        Category::Electron
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Chromium caches of Electron apps (Slack, Discord, Spotify, Teams, Notion, Obsidian)"
+    }
+
+    fn is_applicable(&self) -> bool {
+        !self.app_dirs().is_empty()
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        self.app_dirs()
+            .into_iter()
+            .flat_map(|(_, dir)| CACHE_SUBPATHS.iter().map(move |sub| dir.join(sub)))
+            .collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let min_size = ctx.min_size(self, 5);
+
+        for (app_name, dir) in self.app_dirs() {
+            for sub in CACHE_SUBPATHS {
+                let path = dir.join(sub);
+                if !path.is_dir() {
+                    continue;
+                }
+                let size = ctx.dir_size(&path);
+                if size > min_size {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        format!("{} {}", app_name, sub),
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+
+        for item in items {
+            match remove_path(&item.path, to_trash) {
+                Ok(_) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += item.size;
+                }
+                Err(e) => {
+                    result.failed.push((item.path.clone(), e.to_string()));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    #[test]
+    fn test_electron_caches_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = if cfg!(target_os = "macos") {
+            dir.path().join("Library/Application Support")
+        } else {
+            dir.path().join(".config")
+        };
+        let slack = base.join("Slack");
+        std::fs::create_dir_all(slack.join("Service Worker/CacheStorage")).unwrap();
+        std::fs::create_dir_all(slack.join("GPUCache")).unwrap();
+        std::fs::create_dir_all(slack.join("storage")).unwrap();
+        std::fs::write(
+            slack.join("Service Worker/CacheStorage/blob"),
+            vec![0u8; 2048],
+        )
+        .unwrap();
+        std::fs::write(slack.join("GPUCache/data"), vec![0u8; 1024]).unwrap();
+        std::fs::write(slack.join("storage/db"), vec![0u8; 4096]).unwrap();
+
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [thresholds.rules]
+            "Electron App Caches" = 0
+            "#,
+        )
+        .unwrap();
+
+        with_env(
+            &[("HOME", Some(dir.path())), ("XDG_CONFIG_HOME", None)],
+            || {
+                let rule = ElectronAppCacheRule;
+                assert!(rule.is_applicable());
+
+                let mut items = rule.scan(&ScanContext::new(config.clone())).unwrap();
+                items.sort_by(|a, b| a.path.cmp(&b.path));
+                assert_eq!(items.len(), 2);
+                assert_eq!(items[0].path, slack.join("GPUCache"));
+                assert_eq!(items[1].path, slack.join("Service Worker/CacheStorage"));
+                assert_eq!(items[1].size, 2048);
+                assert_eq!(items[0].category, Category::Electron);
+            },
+        );
+    }
+}
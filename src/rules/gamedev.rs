@@ -0,0 +1,371 @@
+//! Game engine cache rules (Unity, Unreal Engine)
+//!
+//! Both engines keep large regenerable caches inside each project, plus a
+//! shared cache in the user's home directory.
+
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, ScanCost, remove_path,
+};
+use crate::config::{Config, ScanConfig};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Get all game development rules
+pub fn get_gamedev_rules(config: &Config) -> Vec<Box<dyn CleanRule>> {
+    vec![
+        Box::new(UnityCacheRule::new(&config.scan)),
+        Box::new(UnrealCacheRule::new(&config.scan)),
+    ]
+}
+
+/// Unity project subdirectories rebuilt on import
+const UNITY_PROJECT_CACHES: &[&str] = &["Library"];
+
+/// Unreal project subdirectories rebuilt on load or build
+const UNREAL_PROJECT_CACHES: &[&str] = &["DerivedDataCache", "Intermediate", "Saved"];
+
+/// Common function to clean items
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+    let mut result = CleanResult::default();
+
+    for item in items {
+        match remove_path(&item.path, to_trash) {
+            Ok(_) => {
+                result.cleaned_count += 1;
+                result.bytes_freed += item.size;
+            }
+            Err(e) => {
+                result.failed.push((item.path.clone(), e.to_string()));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Project directories under the search dirs for which `is_project` holds
+///
+/// Walks stop at a project, so caches inside it are never mistaken for
+/// nested projects.
+fn find_projects(ctx: &ScanContext, is_project: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let mut projects = Vec::new();
+    for search_dir in ctx.project_dirs() {
+        if !search_dir.exists() {
+            continue;
+        }
+
+        let mut walker = WalkDir::new(&search_dir)
+            .follow_links(false)
+            .max_depth(ctx.config.scan.project_max_depth)
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if entry.file_type().is_dir() && is_project(entry.path()) {
+                projects.push(entry.path().to_path_buf());
+                walker.skip_current_dir();
+            }
+        }
+    }
+    projects
+}
+
+/// Report each existing cache subdirectory of the given projects
+fn project_cache_items(
+    rule: &dyn CleanRule,
+    ctx: &ScanContext,
+    projects: Vec<PathBuf>,
+    subdirs: &[&str],
+    label: &str,
+) -> Vec<CleanItem> {
+    let mut items = Vec::new();
+    for project in projects {
+        let project_name = project
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        for sub in subdirs {
+            let path = project.join(sub);
+            if !path.is_dir() {
+                continue;
+            }
+            let size = ctx.dir_size(&path);
+            if size > ctx.min_size(rule, 50) {
+                items.push(CleanItem::new(
+                    path,
+                    size,
+                    format!("{} {}: {}", label, sub, project_name),
+                    rule.risk_level(),
+                    rule.category(),
+                ));
+            }
+        }
+    }
+    items
+}
+
+/// Unity project Library folders and the Asset Store download cache
+pub struct UnityCacheRule {
+    /// Project directories from `[scan] project_dirs`
+    project_dirs: Vec<PathBuf>,
+}
+
+impl Default for UnityCacheRule {
+    fn default() -> Self {
+        Self::new(&ScanConfig::default())
+    }
+}
+
+impl UnityCacheRule {
+    /// Create the rule searching the project directories of `config`
+    pub fn new(config: &ScanConfig) -> Self {
+        Self {
+            project_dirs: config.project_search_dirs(),
+        }
+    }
+
+    /// Whether `dir` is a Unity project
+    fn is_project(dir: &Path) -> bool {
+        dir.join("ProjectSettings/ProjectVersion.txt").is_file()
+    }
+
+    /// Global Asset Store package cache
+    fn asset_store_cache() -> Option<PathBuf> {
+        let home = crate::paths::home_dir()?;
+        if cfg!(target_os = "macos") {
+            Some(home.join("Library/Unity/Asset Store-5.x"))
+        } else {
+            Some(home.join(".local/share/unity3d/Asset Store-5.x"))
+        }
+    }
+}
+
+impl CleanRule for UnityCacheRule {
+    fn id(&self) -> &str {
+        "unity_caches"
+    }
+
+    fn name(&self) -> &str {
+        "Unity Caches"
+    }
+
+    fn category(&self) -> Category {
+        Category::GameDev
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Unity project Library folders (reimported on open) and the Asset Store cache"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.project_dirs.clone();
+        paths.extend(Self::asset_store_cache());
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = project_cache_items(
+            self,
+            ctx,
+            find_projects(ctx, Self::is_project),
+            UNITY_PROJECT_CACHES,
+            "Unity",
+        );
+
+        if let Some(cache) = Self::asset_store_cache().filter(|p| p.is_dir()) {
+            let size = ctx.dir_size(&cache);
+            if size > ctx.min_size(self, 50) {
+                items.push(CleanItem::new(
+                    cache,
+                    size,
+                    "Unity Asset Store cache",
+                    self.risk_level(),
+                    self.category(),
+                ));
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Unreal Engine project caches and the shared DerivedDataCache
+pub struct UnrealCacheRule {
+    /// Project directories from `[scan] project_dirs`
+    project_dirs: Vec<PathBuf>,
+}
+
+impl Default for UnrealCacheRule {
+    fn default() -> Self {
+        Self::new(&ScanConfig::default())
+    }
+}
+
+impl UnrealCacheRule {
+    /// Create the rule searching the project directories of `config`
+    pub fn new(config: &ScanConfig) -> Self {
+        Self {
+            project_dirs: config.project_search_dirs(),
+        }
+    }
+
+    /// Whether `dir` contains a `.uproject` file
+    fn is_project(dir: &Path) -> bool {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .any(|e| e.path().extension().is_some_and(|ext| ext == "uproject"))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Epic's per-user data folder
+    fn epic_dir() -> Option<PathBuf> {
+        let home = crate::paths::home_dir()?;
+        if cfg!(target_os = "macos") {
+            Some(home.join("Library/Application Support/Epic"))
+        } else {
+            Some(home.join(".config/Epic"))
+        }
+    }
+
+    /// Shared DerivedDataCache used by every project
+    fn shared_ddc() -> Option<PathBuf> {
+        Self::epic_dir().map(|dir| dir.join("UnrealEngine/Common/DerivedDataCache"))
+    }
+}
+
+impl CleanRule for UnrealCacheRule {
+    fn id(&self) -> &str {
+        "unreal_caches"
+    }
+
+    fn name(&self) -> &str {
+        "Unreal Engine Caches"
+    }
+
+    fn category(&self) -> Category {
+        Category::GameDev
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Unreal project DerivedDataCache, Intermediate and Saved folders and the shared DerivedDataCache"
+    }
+
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.project_dirs.clone();
+        paths.extend(Self::epic_dir());
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = project_cache_items(
+            self,
+            ctx,
+            find_projects(ctx, Self::is_project),
+            UNREAL_PROJECT_CACHES,
+            "Unreal",
+        );
+
+        if let Some(ddc) = Self::shared_ddc().filter(|p| p.is_dir()) {
+            let size = ctx.dir_size(&ddc);
+            if size > ctx.min_size(self, 50) {
+                items.push(CleanItem::new(
+                    ddc,
+                    size,
+                    "Unreal shared DerivedDataCache",
+                    self.risk_level(),
+                    self.category(),
+                ));
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    #[test]
+    fn test_engine_projects_detected() {
+        let home = tempfile::tempdir().unwrap();
+        let projects = home.path().join("Projects");
+
+        let unity = projects.join("MyGame");
+        std::fs::create_dir_all(unity.join("ProjectSettings")).unwrap();
+        std::fs::write(
+            unity.join("ProjectSettings/ProjectVersion.txt"),
+            "m_EditorVersion: 2022.3",
+        )
+        .unwrap();
+        std::fs::create_dir_all(unity.join("Library/Artifacts")).unwrap();
+        std::fs::write(unity.join("Library/Artifacts/blob"), vec![0u8; 1024]).unwrap();
+
+        let unreal = projects.join("Shooter");
+        std::fs::create_dir_all(unreal.join("Intermediate")).unwrap();
+        std::fs::create_dir_all(unreal.join("Content")).unwrap();
+        std::fs::write(unreal.join("Shooter.uproject"), "{}").unwrap();
+        std::fs::write(unreal.join("Intermediate/obj"), vec![0u8; 1024]).unwrap();
+        std::fs::write(unreal.join("Content/map.umap"), vec![0u8; 1024]).unwrap();
+
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [thresholds.categories]
+            gamedev = 0
+            "#,
+        )
+        .unwrap();
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let unity_rule = UnityCacheRule::new(&config.scan);
+            let unreal_rule = UnrealCacheRule::new(&config.scan);
+            let ctx = ScanContext::new(config);
+
+            let unity_items = unity_rule.scan(&ctx).unwrap();
+            assert_eq!(unity_items.len(), 1);
+            assert_eq!(unity_items[0].path, unity.join("Library"));
+
+            let unreal_items = unreal_rule.scan(&ctx).unwrap();
+            assert_eq!(unreal_items.len(), 1);
+            assert_eq!(unreal_items[0].path, unreal.join("Intermediate"));
+            assert_eq!(unreal_items[0].risk_level, RiskLevel::Medium);
+        });
+    }
+}
@@ -1,6 +1,9 @@
 //! Heuristic detection for automatically discovering cache directories
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, ScanCost, remove_path,
+};
+use crate::config::{HeuristicConfig, ScanConfig};
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 use walkdir::WalkDir;
@@ -11,22 +14,46 @@ const DEFAULT_SIZE_THRESHOLD: u64 = 100 * 1024 * 1024;
 /// Default stale days threshold
 const DEFAULT_STALE_DAYS: u32 = 30;
 
-/// Patterns that indicate a cache directory
-const CACHE_PATTERNS: &[&str] = &[
-    "cache",
-    "Cache",
-    ".cache",
-    "caches",
-    "Caches",
-    "tmp",
-    "temp",
-    "Temp",
-    "Temporary",
-];
-
-/// Patterns that indicate temporary files
-#[allow(dead_code)]
-const TEMP_EXTENSIONS: &[&str] = &["tmp", "temp", "log", "bak", "old", "orig", "swp", "swo"];
+/// Name patterns that mark caches and temporary files
+#[derive(Debug, Clone)]
+struct CachePatterns {
+    /// Lowercase fragments of cache directory names
+    names: Vec<String>,
+    /// Lowercase extensions of temporary files
+    temp_extensions: Vec<String>,
+}
+
+impl Default for CachePatterns {
+    fn default() -> Self {
+        Self::from_config(&HeuristicConfig::default())
+    }
+}
+
+impl CachePatterns {
+    /// Built-in patterns extended or replaced by `[heuristic]`
+    fn from_config(config: &HeuristicConfig) -> Self {
+        let lowercase = |list: Vec<String>| list.iter().map(|s| s.to_lowercase()).collect();
+        Self {
+            names: lowercase(config.all_cache_patterns()),
+            temp_extensions: lowercase(config.all_temp_extensions()),
+        }
+    }
+
+    /// Check if a directory name matches cache patterns
+    fn is_cache_name(&self, name: &str) -> bool {
+        let lower = name.to_lowercase();
+        self.names.iter().any(|p| lower.contains(p.as_str()))
+    }
+
+    /// Check if a file has a temporary extension
+    fn is_temp_file(&self, name: &str) -> bool {
+        if let Some((_, ext)) = name.rsplit_once('.') {
+            self.temp_extensions.contains(&ext.to_lowercase())
+        } else {
+            false
+        }
+    }
+}
 
 /// Heuristic detection rule
 #[derive(Debug)]
@@ -35,6 +62,10 @@ pub struct HeuristicRule {
     size_threshold: u64,
     /// Stale days threshold
     stale_days: u32,
+    /// What counts as a cache
+    patterns: CachePatterns,
+    /// Project directories from `[scan] project_dirs`
+    project_dirs: Vec<PathBuf>,
 }
 
 impl Default for HeuristicRule {
@@ -42,6 +73,8 @@ impl Default for HeuristicRule {
         Self {
             size_threshold: DEFAULT_SIZE_THRESHOLD,
             stale_days: DEFAULT_STALE_DAYS,
+            patterns: CachePatterns::default(),
+            project_dirs: ScanConfig::default().project_search_dirs(),
         }
     }
 }
@@ -52,52 +85,68 @@ impl HeuristicRule {
         Self {
             size_threshold,
             stale_days,
+            ..Default::default()
         }
     }
 
-    /// Check if a directory name matches cache patterns
-    fn is_cache_name(name: &str) -> bool {
-        let lower = name.to_lowercase();
-        CACHE_PATTERNS
-            .iter()
-            .any(|p| lower.contains(&p.to_lowercase()))
-    }
-
-    /// Check if a file has a temporary extension
-    #[allow(dead_code)]
-    fn is_temp_file(name: &str) -> bool {
-        if let Some(ext) = name.rsplit('.').next() {
-            TEMP_EXTENSIONS.contains(&ext.to_lowercase().as_str())
-        } else {
-            false
+    /// Create a heuristic rule from the `[heuristic]` configuration,
+    /// searching the project directories of `scan`
+    pub fn from_config(config: &HeuristicConfig, scan: &ScanConfig) -> Self {
+        Self {
+            size_threshold: config.size_threshold_mb * 1024 * 1024,
+            stale_days: config.stale_days,
+            patterns: CachePatterns::from_config(config),
+            project_dirs: scan.project_search_dirs(),
         }
     }
 
-    /// Calculate directory size
-    fn dir_size(path: &std::path::Path) -> u64 {
-        WalkDir::new(path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .filter_map(|e| e.metadata().ok())
-            .map(|m| m.len())
-            .sum()
+    /// Detector classifying candidates with this rule's thresholds
+    fn detector(&self) -> HeuristicDetector {
+        HeuristicDetector {
+            size_threshold: self.size_threshold,
+            stale_days: self.stale_days,
+            patterns: self.patterns.clone(),
+        }
     }
 
-    /// Check if a path was last modified before the stale threshold
-    fn is_stale(&self, path: &std::path::Path) -> bool {
-        if let Ok(metadata) = path.metadata() {
-            if let Ok(modified) = metadata.modified() {
-                let threshold =
-                    SystemTime::now() - Duration::from_secs(self.stale_days as u64 * 24 * 60 * 60);
-                return modified < threshold;
-            }
+    /// Item for a cache candidate of `size` bytes, explaining why it was
+    /// detected, or `None` if its confidence is below the scan's minimum
+    ///
+    /// Stale candidates are low risk unless `risk` says otherwise.
+    fn classified_item(
+        &self,
+        ctx: &ScanContext,
+        path: &std::path::Path,
+        size: u64,
+        risk: Option<RiskLevel>,
+    ) -> Option<CleanItem> {
+        let name = path.file_name()?.to_str()?;
+        let classification = self.detector().classify(path, name, size);
+        if classification.confidence < ctx.min_confidence {
+            return None;
         }
-        false
+        let risk = risk.unwrap_or(if classification.is_stale {
+            RiskLevel::Low
+        } else {
+            RiskLevel::Medium
+        });
+        Some(CleanItem::new(
+            classification.path.clone(),
+            size,
+            classification.explain(),
+            risk,
+            Category::Heuristic,
+        ))
     }
 
-    /// Scan a directory for heuristically detected caches
-    fn scan_directory(&self, base_path: &std::path::Path) -> Vec<CleanItem> {
+    /// Scan a directory for heuristically detected caches and temporary files
+    /// at least `min_size` bytes large
+    fn scan_directory(
+        &self,
+        ctx: &ScanContext,
+        base_path: &std::path::Path,
+        min_size: u64,
+    ) -> Vec<CleanItem> {
         let mut items = Vec::new();
 
         if !base_path.exists() || !base_path.is_dir() {
@@ -106,7 +155,8 @@ impl HeuristicRule {
 
         // Look for cache directories
         for entry in WalkDir::new(base_path)
-            .max_depth(3)
+            .follow_links(false)
+            .max_depth(ctx.config.scan.project_max_depth)
             .into_iter()
             .filter_map(|e| e.ok())
         {
@@ -120,29 +170,23 @@ impl HeuristicRule {
             // Check if this is a cache directory by name
             if path.is_dir() {
                 if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if Self::is_cache_name(name) {
-                        let size = Self::dir_size(path);
-                        if size >= self.size_threshold {
-                            let is_stale = self.is_stale(path);
-                            let risk = if is_stale {
-                                RiskLevel::Low
-                            } else {
-                                RiskLevel::Medium
-                            };
-
-                            items.push(CleanItem::new(
-                                path.to_path_buf(),
-                                size,
-                                format!(
-                                    "Heuristically detected cache{}",
-                                    if is_stale { " (stale)" } else { "" }
-                                ),
-                                risk,
-                                Category::Heuristic,
-                            ));
+                    if self.patterns.is_cache_name(name) {
+                        let size = ctx.dir_size(path);
+                        if size >= min_size {
+                            items.extend(self.classified_item(ctx, path, size, None));
                         }
                     }
                 }
+            } else if entry.file_type().is_file()
+                && let Some(name) = path.file_name().and_then(|n| n.to_str())
+                && self.patterns.is_temp_file(name)
+                // Already covered by a cache directory reported above it
+                && !items.iter().any(|item| path.starts_with(&item.path))
+            {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if size >= min_size {
+                    items.extend(self.classified_item(ctx, path, size, None));
+                }
             }
         }
 
@@ -151,6 +195,10 @@ impl HeuristicRule {
 }
 
 impl CleanRule for HeuristicRule {
+    fn id(&self) -> &str {
+        "heuristic"
+    }
+
     fn name(&self) -> &str {
         "Heuristic Detection"
     }
@@ -167,6 +215,10 @@ impl CleanRule for HeuristicRule {
         "Automatically detected cache and temporary directories"
     }
 
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
+    }
+
     fn is_applicable(&self) -> bool {
         true
     }
@@ -175,34 +227,22 @@ impl CleanRule for HeuristicRule {
         let mut paths = Vec::new();
 
         // Common locations to scan for caches
-        if let Some(home) = dirs::home_dir() {
-            paths.push(home.clone());
-
-            // Common project directories
-            for dir in &[
-                "Projects",
-                "projects",
-                "Code",
-                "code",
-                "Development",
-                "dev",
-                "src",
-            ] {
-                let p = home.join(dir);
-                if p.exists() {
-                    paths.push(p);
-                }
-            }
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home);
+
+            // Project directories
+            paths.extend(self.project_dirs.iter().filter(|p| p.exists()).cloned());
         }
 
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
+        let min_size = ctx.min_size(self, self.size_threshold / (1024 * 1024));
 
         // Scan home directory (with limited depth)
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             // Scan direct children of home for cache directories
             if let Ok(entries) = std::fs::read_dir(&home) {
                 for entry in entries.filter_map(|e| e.ok()) {
@@ -210,19 +250,19 @@ impl CleanRule for HeuristicRule {
                     if path.is_dir() {
                         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
                             // Skip already-handled directories
-                            if name.starts_with('.') && !Self::is_cache_name(name) {
+                            if name.starts_with('.') && !self.patterns.is_cache_name(name) {
                                 continue;
                             }
 
-                            if Self::is_cache_name(name) {
-                                let size = Self::dir_size(&path);
-                                if size >= self.size_threshold {
-                                    items.push(CleanItem::new(
-                                        path,
+                            if self.patterns.is_cache_name(name) {
+                                let size = ctx.dir_size(&path);
+                                if size >= min_size {
+                                    // Top-level home directories stay medium risk
+                                    items.extend(self.classified_item(
+                                        ctx,
+                                        &path,
                                         size,
-                                        "Heuristically detected cache directory",
-                                        RiskLevel::Medium,
-                                        Category::Heuristic,
+                                        Some(RiskLevel::Medium),
                                     ));
                                 }
                             }
@@ -230,13 +270,12 @@ impl CleanRule for HeuristicRule {
                     }
                 }
             }
+        }
 
-            // Scan project directories for large temp/cache directories
-            for dir in &["Projects", "projects", "Code", "code", "Development", "dev"] {
-                let project_dir = home.join(dir);
-                if project_dir.exists() {
-                    items.extend(self.scan_directory(&project_dir));
-                }
+        // Scan project directories for large temp/cache directories
+        for project_dir in ctx.project_dirs() {
+            if project_dir.exists() {
+                items.extend(self.scan_directory(ctx, &project_dir, min_size));
             }
         }
 
@@ -247,17 +286,11 @@ impl CleanRule for HeuristicRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
         for item in items {
-            let clean_result = if to_trash {
-                trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-            } else if item.path.is_dir() {
-                std::fs::remove_dir_all(&item.path)
-            } else {
-                std::fs::remove_file(&item.path)
-            };
+            let clean_result = remove_path(&item.path, to_trash);
 
             match clean_result {
                 Ok(_) => {
@@ -289,10 +322,24 @@ pub struct CacheClassification {
     pub is_stale: bool,
 }
 
+impl CacheClassification {
+    /// Reason, confidence and staleness in one line, e.g.
+    /// "Directory name 'cache' matches cache pattern (confidence 0.9, stale)"
+    pub fn explain(&self) -> String {
+        format!(
+            "{} (confidence {:.1}{})",
+            self.reason,
+            self.confidence,
+            if self.is_stale { ", stale" } else { "" }
+        )
+    }
+}
+
 /// Heuristic detector for discovering unknown caches
 pub struct HeuristicDetector {
     size_threshold: u64,
     stale_days: u32,
+    patterns: CachePatterns,
 }
 
 impl Default for HeuristicDetector {
@@ -300,6 +347,7 @@ impl Default for HeuristicDetector {
         Self {
             size_threshold: DEFAULT_SIZE_THRESHOLD,
             stale_days: DEFAULT_STALE_DAYS,
+            patterns: CachePatterns::default(),
         }
     }
 }
@@ -310,6 +358,16 @@ impl HeuristicDetector {
         Self {
             size_threshold: size_threshold_mb * 1024 * 1024,
             stale_days,
+            ..Default::default()
+        }
+    }
+
+    /// Create a detector from the `[heuristic]` configuration
+    pub fn from_config(config: &HeuristicConfig) -> Self {
+        Self {
+            size_threshold: config.size_threshold_mb * 1024 * 1024,
+            stale_days: config.stale_days,
+            patterns: CachePatterns::from_config(config),
         }
     }
 
@@ -322,15 +380,20 @@ impl HeuristicDetector {
         let name = path.file_name()?.to_str()?;
 
         // Check if name matches cache patterns
-        if !HeuristicRule::is_cache_name(name) {
+        if !self.patterns.is_cache_name(name) {
             return None;
         }
 
-        let size = HeuristicRule::dir_size(path);
+        let size = crate::scanner::fs_util::dir_size(path);
         if size < self.size_threshold {
             return None;
         }
 
+        Some(self.classify(path, name, size))
+    }
+
+    /// Classify a cache candidate named `name` whose size is already known
+    fn classify(&self, path: &std::path::Path, name: &str, size: u64) -> CacheClassification {
         // Calculate confidence based on various factors
         let mut confidence: f64 = 0.5;
 
@@ -357,13 +420,20 @@ impl HeuristicDetector {
             false
         };
 
-        Some(CacheClassification {
+        // Files are only candidates because of their extension
+        let reason = if path.is_file() {
+            format!("File name '{}' has a temporary file extension", name)
+        } else {
+            format!("Directory name '{}' matches cache pattern", name)
+        };
+
+        CacheClassification {
             path: path.to_path_buf(),
             size,
-            reason: format!("Directory name '{}' matches cache pattern", name),
+            reason,
             confidence: confidence.min(1.0),
             is_stale,
-        })
+        }
     }
 
     /// Discover potential caches in a directory
@@ -371,6 +441,7 @@ impl HeuristicDetector {
         let mut results = Vec::new();
 
         for entry in WalkDir::new(root)
+            .follow_links(false)
             .max_depth(4)
             .into_iter()
             .filter_map(|e| e.ok())
@@ -383,3 +454,107 @@ impl HeuristicDetector {
         results
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    #[test]
+    fn test_scan_explains_and_filters_by_confidence() {
+        let home = tempfile::tempdir().unwrap();
+        for name in ["cache", "tmp-builds"] {
+            std::fs::create_dir(home.path().join(name)).unwrap();
+            std::fs::write(home.path().join(name).join("blob"), b"data").unwrap();
+        }
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let rule = HeuristicRule::default();
+            let ctx = ScanContext::default().include_small(true);
+
+            let items = rule.scan(&ctx).unwrap();
+            let descriptions: Vec<_> = items.iter().map(|i| i.description.as_str()).collect();
+            assert_eq!(
+                descriptions,
+                [
+                    "Directory name 'cache' matches cache pattern (confidence 0.8)",
+                    "Directory name 'tmp-builds' matches cache pattern (confidence 0.5)",
+                ]
+            );
+
+            let items = rule.scan(&ctx.min_confidence(0.7)).unwrap();
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].path, home.path().join("cache"));
+        });
+    }
+
+    #[test]
+    fn test_temp_files_in_project_dirs_use_configured_extensions() {
+        let home = tempfile::tempdir().unwrap();
+        let project = home.path().join("code/app");
+        std::fs::create_dir_all(project.join("cache")).unwrap();
+        for name in ["dump.tmp", "model.partial", "cache/inner.tmp", "README"] {
+            std::fs::write(project.join(name), b"data").unwrap();
+        }
+        let scan: crate::config::Config = toml::from_str(
+            r#"
+            [scan]
+            project_dirs = ["~/code"]
+            "#,
+        )
+        .unwrap();
+        let found = |config: &HeuristicConfig| -> Vec<PathBuf> {
+            let ctx = ScanContext::default().include_small(true);
+            let items = HeuristicRule::from_config(config, &scan.scan)
+                .scan(&ctx)
+                .unwrap();
+            items.into_iter().map(|item| item.path).collect()
+        };
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let mut config = HeuristicConfig::default();
+            // Files inside a reported cache directory are not listed again
+            assert_eq!(
+                found(&config),
+                [project.join("cache"), project.join("dump.tmp")]
+            );
+
+            config.extra_temp_extensions = vec!["PARTIAL".to_string()];
+            assert_eq!(
+                found(&config),
+                [
+                    project.join("cache"),
+                    project.join("dump.tmp"),
+                    project.join("model.partial")
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_extra_patterns_extend_the_defaults() {
+        let home = tempfile::tempdir().unwrap();
+        for name in [".myapp-stash", "tmp"] {
+            std::fs::create_dir(home.path().join(name)).unwrap();
+            std::fs::write(home.path().join(name).join("blob"), b"data").unwrap();
+        }
+        let found = |config: &HeuristicConfig| -> Vec<PathBuf> {
+            let ctx = ScanContext::default().include_small(true);
+            let items = HeuristicRule::from_config(config, &ScanConfig::default())
+                .scan(&ctx)
+                .unwrap();
+            items.into_iter().map(|item| item.path).collect()
+        };
+
+        with_env(&[("HOME", Some(home.path()))], || {
+            let mut config = HeuristicConfig::default();
+            assert_eq!(found(&config), [home.path().join("tmp")]);
+
+            config.extra_cache_patterns = vec!["MyApp-Stash".to_string()];
+            assert_eq!(
+                found(&config),
+                [home.path().join(".myapp-stash"), home.path().join("tmp")]
+            );
+        });
+    }
+}
@@ -1,45 +1,35 @@
 //! Linux-specific cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, ElectronAppCacheRule, RiskLevel, ScanContext,
+    remove_path,
+};
+use crate::config::{Config, LinuxConfig};
+use crate::scanner::fs_util::dir_size;
+use crate::util::parse_size;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Get all Linux-specific rules
-pub fn get_linux_rules() -> Vec<Box<dyn CleanRule>> {
+pub fn get_linux_rules(config: &Config) -> Vec<Box<dyn CleanRule>> {
     vec![
         Box::new(AptCacheRule),
         Box::new(DnfCacheRule),
         Box::new(PacmanCacheRule),
         Box::new(SnapCacheRule),
         Box::new(FlatpakCacheRule),
-        Box::new(JournalLogsRule),
+        Box::new(JournalLogsRule::new(&config.linux)),
         Box::new(UserCacheRule),
+        Box::new(ElectronAppCacheRule),
     ]
 }
 
-/// Calculate directory size recursively
-fn dir_size(path: &std::path::Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|m| m.len())
-        .sum()
-}
-
 /// Common function to clean items
-fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
     let mut result = CleanResult::default();
 
     for item in items {
-        let clean_result = if to_trash {
-            trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-        } else if item.path.is_dir() {
-            std::fs::remove_dir_all(&item.path)
-        } else {
-            std::fs::remove_file(&item.path)
-        };
+        let clean_result = remove_path(&item.path, to_trash);
 
         match clean_result {
             Ok(_) => {
@@ -55,10 +45,54 @@ fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResul
     Ok(result)
 }
 
+/// Run a package manager command via `sudo` to clean `items`
+///
+/// Bytes freed are measured by re-sizing each item's directory afterwards,
+/// since tools like `paccache` keep part of the cache.
+fn clean_with_sudo(program: &str, args: &[&str], items: &[CleanItem]) -> CleanResult {
+    let mut result = CleanResult::default();
+
+    // Inherit stdio so sudo can prompt for a password
+    match Command::new("sudo").arg(program).args(args).status() {
+        Ok(status) if status.success() => {
+            for item in items {
+                let remaining = if item.path.exists() {
+                    dir_size(&item.path)
+                } else {
+                    0
+                };
+                result.cleaned_count += 1;
+                result.bytes_freed += item.size.saturating_sub(remaining);
+            }
+        }
+        Ok(status) => {
+            for item in items {
+                result.failed.push((
+                    item.path.clone(),
+                    format!("sudo {} {} exited with {}", program, args.join(" "), status),
+                ));
+            }
+        }
+        Err(e) => {
+            for item in items {
+                result
+                    .failed
+                    .push((item.path.clone(), format!("Failed to run sudo: {}", e)));
+            }
+        }
+    }
+
+    result
+}
+
 /// APT cache rule (Debian/Ubuntu)
 pub struct AptCacheRule;
 
 impl CleanRule for AptCacheRule {
+    fn id(&self) -> &str {
+        "apt_cache"
+    }
+
     fn name(&self) -> &str {
         "APT Package Cache"
     }
@@ -83,41 +117,52 @@ impl CleanRule for AptCacheRule {
         vec![PathBuf::from("/var/cache/apt/archives")]
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
                 // Only scan .deb files, not the lock file or partial directory
-                if let Ok(entries) = std::fs::read_dir(&path) {
-                    let mut total_size = 0u64;
-                    let mut deb_count = 0;
+                let entries = match std::fs::read_dir(&path) {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        if e.kind() == std::io::ErrorKind::PermissionDenied {
+                            ctx.record_skipped(&path);
+                        }
+                        continue;
+                    }
+                };
 
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let entry_path = entry.path();
-                        if entry_path.extension().map(|e| e == "deb").unwrap_or(false) {
-                            if let Ok(metadata) = entry_path.metadata() {
-                                total_size += metadata.len();
-                                deb_count += 1;
-                            }
+                let mut total_size = 0u64;
+                let mut deb_count = 0;
+
+                for entry in entries.filter_map(|e| e.ok()) {
+                    let entry_path = entry.path();
+                    if entry_path.extension().map(|e| e == "deb").unwrap_or(false) {
+                        if let Ok(metadata) = entry_path.metadata() {
+                            total_size += metadata.len();
+                            deb_count += 1;
                         }
                     }
+                }
 
-                    if total_size > 0 {
-                        items.push(CleanItem::new(
+                if total_size > 0 {
+                    items.push(
+                        CleanItem::new(
                             path,
                             total_size,
                             format!("APT cache ({} packages)", deb_count),
                             self.risk_level(),
                             self.category(),
-                        ));
-                    }
+                        )
+                        .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
         // For APT cache, we should use apt-get clean instead
         let mut result = CleanResult::default();
 
@@ -145,12 +190,24 @@ impl CleanRule for AptCacheRule {
 
         Ok(result)
     }
+
+    fn requires_root(&self) -> bool {
+        true
+    }
+
+    fn clean_elevated(&self, items: &[CleanItem]) -> crate::Result<CleanResult> {
+        Ok(clean_with_sudo("apt-get", &["clean"], items))
+    }
 }
 
 /// DNF/YUM cache rule (Fedora/RHEL)
 pub struct DnfCacheRule;
 
 impl CleanRule for DnfCacheRule {
+    fn id(&self) -> &str {
+        "dnf_cache"
+    }
+
     fn name(&self) -> &str {
         "DNF/YUM Package Cache"
     }
@@ -178,34 +235,49 @@ impl CleanRule for DnfCacheRule {
         ]
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "DNF/YUM package cache",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                    items.push(
+                        CleanItem::new(
+                            path,
+                            size,
+                            "DNF/YUM package cache",
+                            self.risk_level(),
+                            self.category(),
+                        )
+                        .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
+
+    fn requires_root(&self) -> bool {
+        true
+    }
+
+    fn clean_elevated(&self, items: &[CleanItem]) -> crate::Result<CleanResult> {
+        Ok(clean_with_sudo("dnf", &["clean", "all"], items))
+    }
 }
 
 /// Pacman cache rule (Arch Linux)
 pub struct PacmanCacheRule;
 
 impl CleanRule for PacmanCacheRule {
+    fn id(&self) -> &str {
+        "pacman_cache"
+    }
+
     fn name(&self) -> &str {
         "Pacman Package Cache"
     }
@@ -230,34 +302,49 @@ impl CleanRule for PacmanCacheRule {
         vec![PathBuf::from("/var/cache/pacman/pkg")]
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "Pacman package cache",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                    items.push(
+                        CleanItem::new(
+                            path,
+                            size,
+                            "Pacman package cache",
+                            self.risk_level(),
+                            self.category(),
+                        )
+                        .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
+
+    fn requires_root(&self) -> bool {
+        true
+    }
+
+    fn clean_elevated(&self, items: &[CleanItem]) -> crate::Result<CleanResult> {
+        Ok(clean_with_sudo("paccache", &["-r"], items))
+    }
 }
 
 /// Snap cache rule
 pub struct SnapCacheRule;
 
 impl CleanRule for SnapCacheRule {
+    fn id(&self) -> &str {
+        "snap_cache"
+    }
+
     fn name(&self) -> &str {
         "Snap Cache"
     }
@@ -280,13 +367,13 @@ impl CleanRule for SnapCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("snap"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for base_path in self.scan_paths() {
             if base_path.exists() {
@@ -298,8 +385,8 @@ impl CleanRule for SnapCacheRule {
                             // Check common/.cache
                             let cache_path = app_path.join("common/.cache");
                             if cache_path.exists() {
-                                let size = dir_size(&cache_path);
-                                if size > 1024 * 1024 {
+                                let size = ctx.dir_size(&cache_path);
+                                if size > ctx.min_size(self, 1) {
                                     let app_name = app_path
                                         .file_name()
                                         .map(|n| n.to_string_lossy().to_string())
@@ -321,7 +408,7 @@ impl CleanRule for SnapCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -330,6 +417,10 @@ impl CleanRule for SnapCacheRule {
 pub struct FlatpakCacheRule;
 
 impl CleanRule for FlatpakCacheRule {
+    fn id(&self) -> &str {
+        "flatpak_cache"
+    }
+
     fn name(&self) -> &str {
         "Flatpak Cache"
     }
@@ -352,13 +443,13 @@ impl CleanRule for FlatpakCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join(".var/app"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for base_path in self.scan_paths() {
             if base_path.exists() {
@@ -368,8 +459,8 @@ impl CleanRule for FlatpakCacheRule {
                         if app_path.is_dir() {
                             let cache_path = app_path.join("cache");
                             if cache_path.exists() {
-                                let size = dir_size(&cache_path);
-                                if size > 1024 * 1024 {
+                                let size = ctx.dir_size(&cache_path);
+                                if size > ctx.min_size(self, 1) {
                                     let app_name = app_path
                                         .file_name()
                                         .map(|n| n.to_string_lossy().to_string())
@@ -391,15 +482,49 @@ impl CleanRule for FlatpakCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
 /// Systemd journal logs rule
-pub struct JournalLogsRule;
+pub struct JournalLogsRule {
+    /// Target size for `journalctl --vacuum-size`
+    vacuum_size: String,
+    /// Optional retention for `journalctl --vacuum-time`
+    vacuum_time: Option<String>,
+}
+
+impl Default for JournalLogsRule {
+    fn default() -> Self {
+        Self::new(&LinuxConfig::default())
+    }
+}
+
+impl JournalLogsRule {
+    /// Create a journal rule using the configured vacuum targets
+    pub fn new(config: &LinuxConfig) -> Self {
+        Self {
+            vacuum_size: config.journal_vacuum_size.clone(),
+            vacuum_time: config.journal_vacuum_time.clone(),
+        }
+    }
+
+    /// Arguments passed to `journalctl`
+    fn vacuum_args(&self) -> Vec<String> {
+        let mut args = vec![format!("--vacuum-size={}", self.vacuum_size)];
+        if let Some(time) = &self.vacuum_time {
+            args.push(format!("--vacuum-time={}", time));
+        }
+        args
+    }
+}
 
 impl CleanRule for JournalLogsRule {
+    fn id(&self) -> &str {
+        "journal_logs"
+    }
+
     fn name(&self) -> &str {
         "Systemd Journal Logs"
     }
@@ -413,7 +538,7 @@ impl CleanRule for JournalLogsRule {
     }
 
     fn description(&self) -> &str {
-        "Systemd journal log files"
+        "Systemd journal log files (vacuumed via journalctl)"
     }
 
     fn is_applicable(&self) -> bool {
@@ -424,41 +549,113 @@ impl CleanRule for JournalLogsRule {
         vec![PathBuf::from("/var/log/journal")]
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
+        let target = parse_size(&self.vacuum_size, true).unwrap_or(0);
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
-                if size > 100 * 1024 * 1024 {
+                let size = ctx.dir_size(&path);
+                let reclaimable = size.saturating_sub(target);
+                if size > ctx.min_size(self, 100) && reclaimable > 0 {
                     // Only show if > 100MB
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "Systemd journal logs (consider using journalctl --vacuum-size)",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                    items.push(
+                        CleanItem::new(
+                            path,
+                            reclaimable,
+                            format!("Systemd journal logs (vacuum to {})", self.vacuum_size),
+                            self.risk_level(),
+                            self.category(),
+                        )
+                        .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, _items: &[CleanItem], _to_trash: bool) -> anyhow::Result<CleanResult> {
-        // For journal logs, recommend using journalctl --vacuum-size instead
-        Ok(CleanResult {
-            cleaned_count: 0,
-            bytes_freed: 0,
-            failed: vec![],
-            cancelled: false,
-        })
+    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+        if items.is_empty() {
+            return Ok(result);
+        }
+
+        // journalctl vacuums every journal directory at once, so one run covers
+        // all of `items`; the scan reports a single item, so the cleaner
+        // (which passes items one at a time) runs it once per clean
+        let path = items[0].path.clone();
+        match Command::new("journalctl").args(self.vacuum_args()).output() {
+            Ok(output) if output.status.success() => {
+                // journalctl reports its progress on stderr
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                result.cleaned_count += 1;
+                result.bytes_freed += parse_vacuum_freed(&stderr);
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let message = if stderr.contains("ermission denied") {
+                    format!(
+                        "journalctl requires root to vacuum system journals: {}",
+                        stderr.trim()
+                    )
+                } else {
+                    format!("journalctl failed: {}", stderr.trim())
+                };
+                result.failed.push((path, message));
+            }
+            Err(e) => {
+                result
+                    .failed
+                    .push((path, format!("journalctl is not available: {}", e)));
+            }
+        }
+
+        Ok(result)
     }
 }
 
+/// Sum the "freed X of archived journals" lines printed by `journalctl --vacuum-*`
+fn parse_vacuum_freed(stderr: &str) -> u64 {
+    stderr
+        .lines()
+        .filter_map(|l| l.split("freed ").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter_map(|size| parse_size(size, true))
+        .sum()
+}
+
+/// `~/.cache` entries cleaned by a dedicated rule, matched by name
+const OWNED_CACHE_DIRS: &[&str] = &["pip", "npm", "yarn", "cargo", "go"];
+
 /// User cache rule (~/.cache)
 pub struct UserCacheRule;
 
+impl UserCacheRule {
+    /// Paths scanned by the dedicated cache rules
+    ///
+    /// A `~/.cache` entry holding one of them belongs to that rule, which
+    /// reports it once and with its own risk level.
+    fn claimed_paths(config: &Config) -> Vec<PathBuf> {
+        super::devtools::get_devtools_rules(config)
+            .into_iter()
+            .chain(super::gamedev::get_gamedev_rules(config))
+            .chain([Box::new(super::browser::FirefoxCacheRule) as Box<dyn CleanRule>])
+            .flat_map(|rule| rule.scan_paths())
+            .collect()
+    }
+
+    /// Whether `entry` is left to a dedicated rule
+    fn is_claimed(entry: &Path, name: &str, claimed: &[PathBuf]) -> bool {
+        OWNED_CACHE_DIRS.iter().any(|p| name.contains(p))
+            || claimed.iter().any(|path| path.starts_with(entry))
+    }
+}
+
 impl CleanRule for UserCacheRule {
+    fn id(&self) -> &str {
+        "user_cache"
+    }
+
     fn name(&self) -> &str {
         "User Cache Directory"
     }
@@ -481,17 +678,17 @@ impl CleanRule for UserCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(cache_dir) = dirs::cache_dir() {
+        if let Some(cache_dir) = crate::paths::cache_dir() {
             paths.push(cache_dir);
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
         // Skip caches that are handled by other rules
-        let skip_patterns = ["pip", "npm", "yarn", "cargo", "go"];
+        let claimed = Self::claimed_paths(&ctx.config);
 
         for path in self.scan_paths() {
             if path.exists() {
@@ -503,13 +700,13 @@ impl CleanRule for UserCacheRule {
                             .map(|n| n.to_string_lossy().to_string())
                             .unwrap_or_default();
 
-                        if skip_patterns.iter().any(|p| name.contains(p)) {
+                        if Self::is_claimed(&entry_path, &name, &claimed) {
                             continue;
                         }
 
                         if entry_path.is_dir() {
-                            let size = dir_size(&entry_path);
-                            if size > 10 * 1024 * 1024 {
+                            let size = ctx.dir_size(&entry_path);
+                            if size > ctx.min_size(self, 10) {
                                 // > 10MB
                                 items.push(CleanItem::new(
                                     entry_path,
@@ -527,7 +724,113 @@ impl CleanRule for UserCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{
+        BazelCacheRule, CabalStackCacheRule, ComposerCacheRule, FirefoxCacheRule, LspCacheRule,
+        MlCacheRule, PoetryCacheRule,
+    };
+    use crate::test_support::with_env;
+
+    /// Ids of the rules reporting anything in `~/.cache/<dir>` once
+    /// `~/.cache/<subpath>` holds some data
+    fn cache_reporters(rules: &[&dyn CleanRule], dir: &str, subpath: &str) -> Vec<String> {
+        let home = tempfile::tempdir().unwrap();
+        let cache = home.path().join(".cache");
+        let data = cache.join(subpath);
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(data.join("blob"), vec![0u8; 64]).unwrap();
+
+        let vars = [
+            ("HOME", Some(home.path())),
+            ("XDG_CACHE_HOME", None),
+            ("HF_HOME", None),
+            ("TORCH_HOME", None),
+            ("POETRY_CACHE_DIR", None),
+            ("COMPOSER_CACHE_DIR", None),
+        ];
+        with_env(&vars, || {
+            let ctx = ScanContext::default().include_small(true);
+            let dir = cache.join(dir);
+            rules
+                .iter()
+                .filter(|rule| {
+                    let items = rule.scan(&ctx).unwrap();
+                    items
+                        .iter()
+                        .any(|item| item.path.starts_with(&dir) || dir.starts_with(&item.path))
+                })
+                .map(|rule| rule.id().to_string())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_user_cache_leaves_ml_caches_to_their_rule() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &MlCacheRule];
+        for dir in ["huggingface", "torch"] {
+            let data = format!("{}/hub", dir);
+            assert_eq!(cache_reporters(&rules, dir, &data), ["ml_caches"]);
+        }
+    }
+
+    #[test]
+    fn test_user_cache_reports_unclaimed_lookalike_names() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &MlCacheRule];
+        let reporters = cache_reporters(&rules, "torchlight", "torchlight/saves");
+        assert_eq!(reporters, ["user_cache"]);
+    }
+
+    #[test]
+    fn test_user_cache_leaves_poetry_cache_to_its_rule() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &PoetryCacheRule];
+        let reporters = cache_reporters(&rules, "pypoetry", "pypoetry/cache");
+        assert_eq!(reporters, ["poetry_cache"]);
+    }
+
+    #[test]
+    fn test_user_cache_leaves_composer_cache_to_its_rule() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &ComposerCacheRule];
+        let reporters = cache_reporters(&rules, "composer", "composer/files");
+        assert_eq!(reporters, ["composer_cache"]);
+    }
+
+    #[test]
+    fn test_user_cache_leaves_bazel_and_cabal_caches_to_their_rules() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &BazelCacheRule];
+        let reporters = cache_reporters(&rules, "bazel", "bazel/_bazel_user/cache/repos");
+        assert_eq!(reporters, ["bazel_cache"]);
+
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &CabalStackCacheRule];
+        let reporters = cache_reporters(&rules, "cabal", "cabal/packages/hackage.haskell.org");
+        assert_eq!(reporters, ["cabal_stack_cache"]);
+    }
+
+    #[test]
+    fn test_user_cache_leaves_lsp_caches_to_their_rule() {
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &LspCacheRule];
+        let caches = [
+            ("rust-analyzer", "rust-analyzer/proc-macros"),
+            ("gopls", "gopls/a1b2c3d4"),
+            ("clangd", "clangd/index"),
+        ];
+        for (dir, data) in caches {
+            assert_eq!(cache_reporters(&rules, dir, data), ["lsp_cache"]);
+        }
+    }
+
+    #[test]
+    fn test_user_cache_leaves_mozilla_to_firefox_rule() {
+        // Without profiles.ini the Firefox rule reports nothing, and the
+        // profile caches must still not show up as one Low-risk item
+        let rules: [&dyn CleanRule; 2] = [&UserCacheRule, &FirefoxCacheRule];
+        let data = "mozilla/firefox/abcd1234.default-release/cache2";
+        assert!(cache_reporters(&rules, "mozilla", data).is_empty());
+    }
+}
@@ -1,8 +1,12 @@
 //! macOS-specific cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
-use std::path::PathBuf;
-use walkdir::WalkDir;
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, ScanCost, remove_path,
+};
+use crate::util::parse_size;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Get all macOS-specific rules
 pub fn get_macos_rules() -> Vec<Box<dyn CleanRule>> {
@@ -15,32 +19,17 @@ pub fn get_macos_rules() -> Vec<Box<dyn CleanRule>> {
         Box::new(SimulatorRule),
         Box::new(MacOSCacheRule),
         Box::new(MacOSLogsRule),
+        Box::new(MacOSSystemCacheRule),
+        Box::new(MobileSyncBackupRule),
     ]
 }
 
-/// Calculate directory size recursively
-fn dir_size(path: &std::path::Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|m| m.len())
-        .sum()
-}
-
 /// Common function to clean items
-fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
     let mut result = CleanResult::default();
 
     for item in items {
-        let clean_result = if to_trash {
-            trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-        } else if item.path.is_dir() {
-            std::fs::remove_dir_all(&item.path)
-        } else {
-            std::fs::remove_file(&item.path)
-        };
+        let clean_result = remove_path(&item.path, to_trash);
 
         match clean_result {
             Ok(_) => {
@@ -59,7 +48,38 @@ fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResul
 /// Homebrew cache rule
 pub struct HomebrewRule;
 
+/// Arguments passed to `brew cleanup`
+const BREW_CLEANUP_ARGS: &[&str] = &["cleanup", "-s", "--prune=all"];
+
+impl HomebrewRule {
+    /// Check whether the `brew` binary is available
+    fn brew_available() -> bool {
+        Command::new("brew")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Estimate reclaimable space with `brew cleanup -n`
+    fn estimate_cleanup() -> Option<u64> {
+        let output = Command::new("brew")
+            .args(BREW_CLEANUP_ARGS)
+            .arg("-n")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_brew_freed(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
 impl CleanRule for HomebrewRule {
+    fn id(&self) -> &str {
+        "homebrew_cache"
+    }
+
     fn name(&self) -> &str {
         "Homebrew Cache"
     }
@@ -82,40 +102,83 @@ impl CleanRule for HomebrewRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Caches/Homebrew"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
+        let brew_available = Self::brew_available();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                // Prefer Homebrew's own estimate, which also covers old formula versions
+                let (size, desc) = match brew_available.then(Self::estimate_cleanup).flatten() {
+                    Some(estimate) => (estimate, "Homebrew cleanup (brew cleanup)"),
+                    None => (ctx.dir_size(&path), "Homebrew download cache"),
+                };
                 if size > 0 {
-                    items.push(CleanItem::new(
-                        path,
-                        size,
-                        "Homebrew download cache",
-                        self.risk_level(),
-                        self.category(),
-                    ));
+                    items.push(
+                        CleanItem::new(path, size, desc, self.risk_level(), self.category())
+                            .with_handler(self.id()),
+                    );
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
-        clean_items(items, to_trash)
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        if items.is_empty() || !Self::brew_available() {
+            return clean_items(items, to_trash);
+        }
+
+        let mut result = CleanResult::default();
+        let estimated: u64 = items.iter().map(|i| i.size).sum();
+        let path = items[0].path.clone();
+
+        match Command::new("brew").args(BREW_CLEANUP_ARGS).output() {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                result.cleaned_count += items.len();
+                result.bytes_freed += parse_brew_freed(&stdout).unwrap_or(estimated);
+            }
+            Ok(output) => {
+                result.failed.push((
+                    path,
+                    format!(
+                        "brew cleanup failed: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ),
+                ));
+            }
+            Err(e) => {
+                result.failed.push((path, e.to_string()));
+            }
+        }
+
+        Ok(result)
     }
 }
 
+/// Parse the "freed approximately 1.2GB of disk space" line printed by `brew cleanup`
+fn parse_brew_freed(stdout: &str) -> Option<u64> {
+    stdout
+        .lines()
+        .filter_map(|l| l.split("approximately ").nth(1))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .find_map(|size| parse_size(size, true))
+}
+
 /// Xcode DerivedData rule
 pub struct XcodeDerivedDataRule;
 
 impl CleanRule for XcodeDerivedDataRule {
+    fn id(&self) -> &str {
+        "xcode_derived_data"
+    }
+
     fn name(&self) -> &str {
         "Xcode DerivedData"
     }
@@ -132,58 +195,90 @@ impl CleanRule for XcodeDerivedDataRule {
         "Xcode build artifacts and intermediate files"
     }
 
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Expensive
+    }
+
     fn is_applicable(&self) -> bool {
         self.scan_paths().iter().any(|p| p.exists())
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Developer/Xcode/DerivedData"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
+        let now = SystemTime::now();
         for path in self.scan_paths() {
-            if path.exists() {
-                // Scan individual project folders
-                if let Ok(entries) = std::fs::read_dir(&path) {
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let entry_path = entry.path();
-                        if entry_path.is_dir() {
-                            let size = dir_size(&entry_path);
-                            if size > 0 {
-                                let name = entry_path
-                                    .file_name()
-                                    .map(|n| n.to_string_lossy().to_string())
-                                    .unwrap_or_default();
-                                items.push(CleanItem::new(
-                                    entry_path,
-                                    size,
-                                    format!("Xcode build data for {}", name),
-                                    self.risk_level(),
-                                    self.category(),
-                                ));
-                            }
-                        }
+            for (entry_path, modified) in
+                derived_data_candidates(&path, ctx.config.xcode.keep_recent)
+            {
+                let size = ctx.dir_size(&entry_path);
+                if size > 0 {
+                    let name = entry_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let days = now
+                        .duration_since(modified)
+                        .map(|d| d.as_secs() / (24 * 60 * 60))
+                        .unwrap_or(0);
+                    let mut item = CleanItem::new(
+                        entry_path,
+                        size,
+                        format!(
+                            "Xcode build data for {} (last built {} days ago)",
+                            name, days
+                        ),
+                        self.risk_level(),
+                        self.category(),
+                    );
+                    if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                        item = item.with_last_modified(since_epoch.as_secs() as i64);
                     }
+                    items.push(item);
                 }
             }
         }
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
 
+/// Project folders in a DerivedData directory, newest first, skipping the
+/// `keep_recent` most recently built ones
+fn derived_data_candidates(dir: &Path, keep_recent: usize) -> Vec<(PathBuf, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<(PathBuf, SystemTime)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((e.path(), modified))
+        })
+        .collect();
+    folders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    folders.into_iter().skip(keep_recent).collect()
+}
+
 /// Xcode Archives rule
 pub struct XcodeArchivesRule;
 
 impl CleanRule for XcodeArchivesRule {
+    fn id(&self) -> &str {
+        "xcode_archives"
+    }
+
     fn name(&self) -> &str {
         "Xcode Archives"
     }
@@ -206,17 +301,17 @@ impl CleanRule for XcodeArchivesRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Developer/Xcode/Archives"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -231,7 +326,7 @@ impl CleanRule for XcodeArchivesRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -240,6 +335,10 @@ impl CleanRule for XcodeArchivesRule {
 pub struct XcodeDeviceSupportRule;
 
 impl CleanRule for XcodeDeviceSupportRule {
+    fn id(&self) -> &str {
+        "xcode_device_support"
+    }
+
     fn name(&self) -> &str {
         "Xcode Device Support"
     }
@@ -262,18 +361,18 @@ impl CleanRule for XcodeDeviceSupportRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Developer/Xcode/iOS DeviceSupport"));
             paths.push(home.join("Library/Developer/Xcode/watchOS DeviceSupport"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -288,7 +387,7 @@ impl CleanRule for XcodeDeviceSupportRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -297,6 +396,10 @@ impl CleanRule for XcodeDeviceSupportRule {
 pub struct CocoaPodsRule;
 
 impl CleanRule for CocoaPodsRule {
+    fn id(&self) -> &str {
+        "cocoapods_cache"
+    }
+
     fn name(&self) -> &str {
         "CocoaPods Cache"
     }
@@ -319,17 +422,17 @@ impl CleanRule for CocoaPodsRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Caches/CocoaPods"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -344,7 +447,7 @@ impl CleanRule for CocoaPodsRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -353,6 +456,10 @@ impl CleanRule for CocoaPodsRule {
 pub struct SimulatorRule;
 
 impl CleanRule for SimulatorRule {
+    fn id(&self) -> &str {
+        "ios_simulators"
+    }
+
     fn name(&self) -> &str {
         "iOS Simulators"
     }
@@ -375,17 +482,17 @@ impl CleanRule for SimulatorRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Developer/CoreSimulator/Devices"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -400,7 +507,7 @@ impl CleanRule for SimulatorRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -409,6 +516,10 @@ impl CleanRule for SimulatorRule {
 pub struct MacOSCacheRule;
 
 impl CleanRule for MacOSCacheRule {
+    fn id(&self) -> &str {
+        "macos_user_caches"
+    }
+
     fn name(&self) -> &str {
         "macOS User Caches"
     }
@@ -431,13 +542,13 @@ impl CleanRule for MacOSCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Caches"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
@@ -458,8 +569,8 @@ impl CleanRule for MacOSCacheRule {
                         }
 
                         if entry_path.is_dir() {
-                            let size = dir_size(&entry_path);
-                            if size > 1024 * 1024 {
+                            let size = ctx.dir_size(&entry_path);
+                            if size > ctx.min_size(self, 1) {
                                 // Only show caches > 1MB
                                 items.push(CleanItem::new(
                                     entry_path,
@@ -477,7 +588,7 @@ impl CleanRule for MacOSCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -486,6 +597,10 @@ impl CleanRule for MacOSCacheRule {
 pub struct MacOSLogsRule;
 
 impl CleanRule for MacOSLogsRule {
+    fn id(&self) -> &str {
+        "macos_user_logs"
+    }
+
     fn name(&self) -> &str {
         "macOS User Logs"
     }
@@ -508,17 +623,17 @@ impl CleanRule for MacOSLogsRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Logs"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
         for path in self.scan_paths() {
             if path.exists() {
-                let size = dir_size(&path);
+                let size = ctx.dir_size(&path);
                 if size > 0 {
                     items.push(CleanItem::new(
                         path,
@@ -533,7 +648,276 @@ impl CleanRule for MacOSLogsRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
+
+/// QuickLook, Finder and saved application state caches
+pub struct MacOSSystemCacheRule;
+
+impl MacOSSystemCacheRule {
+    /// Per-user cache directory reported by `getconf DARWIN_USER_CACHE_DIR`
+    fn darwin_user_cache_dir() -> Option<PathBuf> {
+        let output = Command::new("getconf")
+            .arg("DARWIN_USER_CACHE_DIR")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let dir = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!dir.is_empty()).then(|| PathBuf::from(dir))
+    }
+
+    /// Cache paths with their description and risk
+    fn targets(&self) -> Vec<(PathBuf, &'static str, RiskLevel)> {
+        let mut targets = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            let caches = home.join("Library/Caches");
+            targets.push((
+                caches.join("com.apple.QuickLook.thumbnailcache"),
+                "QuickLook thumbnail cache",
+                RiskLevel::Low,
+            ));
+            targets.push((
+                caches.join("com.apple.finder"),
+                "Finder cache",
+                RiskLevel::Low,
+            ));
+            // Losing saved state only loses window restoration
+            targets.push((
+                home.join("Library/Saved Application State"),
+                "Saved application state",
+                RiskLevel::Medium,
+            ));
+        }
+        if let Some(dir) = Self::darwin_user_cache_dir() {
+            targets.push((
+                dir.join("com.apple.QuickLook.thumbnailcache"),
+                "QuickLook thumbnail cache",
+                RiskLevel::Low,
+            ));
+        }
+        targets
+    }
+}
+
+impl CleanRule for MacOSSystemCacheRule {
+    fn id(&self) -> &str {
+        "macos_quicklook_saved_state"
+    }
+
+    fn name(&self) -> &str {
+        "macOS QuickLook & Saved State"
+    }
+
+    fn category(&self) -> Category {
+        Category::System
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "QuickLook thumbnails, Finder cache and saved application state"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        self.targets()
+            .into_iter()
+            .map(|(path, _, _)| path)
+            .collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let min_size = ctx.min_size(self, 1);
+
+        for (path, description, risk) in self.targets() {
+            if path.exists() {
+                let size = ctx.dir_size(&path);
+                if size > min_size {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        description,
+                        risk,
+                        self.category(),
+                    ));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// iOS device backups made by Finder/iTunes
+pub struct MobileSyncBackupRule;
+
+impl MobileSyncBackupRule {
+    /// Device name recorded in a backup's Info.plist
+    fn device_name(backup: &std::path::Path) -> Option<String> {
+        let info = backup.join("Info.plist");
+        let content = std::fs::read(&info).ok()?;
+
+        if content.starts_with(b"bplist") {
+            // Binary plists are decoded with plutil
+            let output = Command::new("plutil")
+                .args(["-extract", "Device Name", "raw", "-o", "-"])
+                .arg(&info)
+                .output()
+                .ok()?;
+            let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            return (output.status.success() && !name.is_empty()).then_some(name);
+        }
+
+        plist_string_value(&String::from_utf8_lossy(&content), "Device Name")
+    }
+}
+
+impl CleanRule for MobileSyncBackupRule {
+    fn id(&self) -> &str {
+        "ios_backups"
+    }
+
+    fn name(&self) -> &str {
+        "iOS Device Backups"
+    }
+
+    fn category(&self) -> Category {
+        Category::Other("iOS Backups".to_string())
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::High
+    }
+
+    fn description(&self) -> &str {
+        "iPhone and iPad backups in ~/Library/Application Support/MobileSync/Backup"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Some(home) = crate::paths::home_dir() {
+            paths.push(home.join("Library/Application Support/MobileSync/Backup"));
+        }
+        paths
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+
+        for path in self.scan_paths() {
+            let Ok(entries) = std::fs::read_dir(&path) else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let backup = entry.path();
+                if !backup.is_dir() {
+                    continue;
+                }
+
+                let size = ctx.dir_size(&backup);
+                if size == 0 {
+                    continue;
+                }
+
+                let device = Self::device_name(&backup)
+                    .unwrap_or_else(|| entry.file_name().to_string_lossy().to_string());
+                let mut item = CleanItem::new(backup, size, "", self.risk_level(), self.category())
+                    .with_path_mtime();
+                let modified = item
+                    .last_modified
+                    .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                    .map(|dt| dt.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                item.description = format!("Backup of {} (last modified {})", device, modified);
+                items.push(item);
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Value of the `<string>` following `<key>key</key>` in an XML plist
+fn plist_string_value(xml: &str, key: &str) -> Option<String> {
+    let key_tag = format!("<key>{}</key>", key);
+    let rest = &xml[xml.find(&key_tag)? + key_tag.len()..];
+    let rest = rest.trim_start().strip_prefix("<string>")?;
+    let value = &rest[..rest.find("</string>")?];
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&apos;", "'")
+            .replace("&quot;", "\"")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_data_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        for (name, days_ago) in [("Old", 30), ("Newest", 1), ("Middle", 7)] {
+            let folder = dir.path().join(name);
+            std::fs::create_dir(&folder).unwrap();
+            std::fs::File::open(&folder)
+                .unwrap()
+                .set_modified(now - std::time::Duration::from_secs(days_ago * 86400))
+                .unwrap();
+        }
+
+        let names = |keep| {
+            derived_data_candidates(dir.path(), keep)
+                .into_iter()
+                .map(|(path, _)| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(0), ["Newest", "Middle", "Old"]);
+        assert_eq!(names(2), ["Old"]);
+        assert!(names(5).is_empty());
+    }
+
+    #[test]
+    fn test_plist_string_value() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>Build Version</key>
+    <string>21A329</string>
+    <key>Device Name</key>
+    <string>Alex&apos;s iPhone</string>
+</dict>
+</plist>"#;
+        assert_eq!(
+            plist_string_value(xml, "Device Name").as_deref(),
+            Some("Alex's iPhone")
+        );
+        assert_eq!(plist_string_value(xml, "Product Type"), None);
+    }
+}
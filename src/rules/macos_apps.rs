@@ -1,8 +1,10 @@
 //! macOS application-specific cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
+use super::{
+    Category, CleanItem, CleanResult, CleanRule, ELECTRON_APPS, ElectronAppCacheRule, RiskLevel,
+    ScanContext, remove_path,
+};
 use std::path::PathBuf;
-use walkdir::WalkDir;
 
 /// Get all macOS application-specific rules
 pub fn get_macos_app_rules() -> Vec<Box<dyn CleanRule>> {
@@ -11,32 +13,16 @@ pub fn get_macos_app_rules() -> Vec<Box<dyn CleanRule>> {
         Box::new(AppLogsRule),
         Box::new(AppSupportCacheRule),
         Box::new(ContainerCacheRule),
+        Box::new(ElectronAppCacheRule),
     ]
 }
 
-/// Calculate directory size recursively
-fn dir_size(path: &std::path::Path) -> u64 {
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|m| m.len())
-        .sum()
-}
-
 /// Common function to clean items
-fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
     let mut result = CleanResult::default();
 
     for item in items {
-        let clean_result = if to_trash {
-            trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-        } else if item.path.is_dir() {
-            std::fs::remove_dir_all(&item.path)
-        } else {
-            std::fs::remove_file(&item.path)
-        };
+        let clean_result = remove_path(&item.path, to_trash);
 
         match clean_result {
             Ok(_) => {
@@ -56,6 +42,10 @@ fn clean_items(items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResul
 pub struct AppCacheRule;
 
 impl CleanRule for AppCacheRule {
+    fn id(&self) -> &str {
+        "app_caches"
+    }
+
     fn name(&self) -> &str {
         "Application Caches"
     }
@@ -78,13 +68,13 @@ impl CleanRule for AppCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Caches"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
         // Skip system and already-handled caches
@@ -113,9 +103,9 @@ impl CleanRule for AppCacheRule {
                         }
 
                         if entry_path.is_dir() {
-                            let size = dir_size(&entry_path);
+                            let size = ctx.dir_size(&entry_path);
                             // Only show caches > 10MB
-                            if size > 10 * 1024 * 1024 {
+                            if size > ctx.min_size(self, 10) {
                                 items.push(CleanItem::new(
                                     entry_path,
                                     size,
@@ -132,7 +122,7 @@ impl CleanRule for AppCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -141,6 +131,10 @@ impl CleanRule for AppCacheRule {
 pub struct AppLogsRule;
 
 impl CleanRule for AppLogsRule {
+    fn id(&self) -> &str {
+        "app_logs"
+    }
+
     fn name(&self) -> &str {
         "Application Logs"
     }
@@ -163,13 +157,13 @@ impl CleanRule for AppLogsRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Logs"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
         // Skip certain system logs
@@ -190,8 +184,8 @@ impl CleanRule for AppLogsRule {
                         }
 
                         if entry_path.is_dir() {
-                            let size = dir_size(&entry_path);
-                            if size > 1024 * 1024 {
+                            let size = ctx.dir_size(&entry_path);
+                            if size > ctx.min_size(self, 1) {
                                 // > 1MB
                                 items.push(CleanItem::new(
                                     entry_path,
@@ -205,7 +199,7 @@ impl CleanRule for AppLogsRule {
                             // Individual log files
                             if let Ok(metadata) = entry_path.metadata() {
                                 let size = metadata.len();
-                                if size > 1024 * 1024 {
+                                if size > ctx.min_size(self, 1) {
                                     items.push(CleanItem::new(
                                         entry_path,
                                         size,
@@ -223,7 +217,7 @@ impl CleanRule for AppLogsRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -232,6 +226,10 @@ impl CleanRule for AppLogsRule {
 pub struct AppSupportCacheRule;
 
 impl CleanRule for AppSupportCacheRule {
+    fn id(&self) -> &str {
+        "app_support_caches"
+    }
+
     fn name(&self) -> &str {
         "Application Support Caches"
     }
@@ -254,13 +252,13 @@ impl CleanRule for AppSupportCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Application Support"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
         let cache_names = [
@@ -277,13 +275,17 @@ impl CleanRule for AppSupportCacheRule {
                 if let Ok(app_dirs) = std::fs::read_dir(&base_path) {
                     for app_entry in app_dirs.filter_map(|e| e.ok()) {
                         let app_path = app_entry.path();
-                        if app_path.is_dir() {
+                        // Electron apps are covered by ElectronAppCacheRule
+                        let is_electron = ELECTRON_APPS
+                            .iter()
+                            .any(|(_, folder)| app_path.file_name() == Some(folder.as_ref()));
+                        if app_path.is_dir() && !is_electron {
                             // Look for cache directories inside each app folder
                             for cache_name in &cache_names {
                                 let cache_path = app_path.join(cache_name);
                                 if cache_path.exists() && cache_path.is_dir() {
-                                    let size = dir_size(&cache_path);
-                                    if size > 10 * 1024 * 1024 {
+                                    let size = ctx.dir_size(&cache_path);
+                                    if size > ctx.min_size(self, 10) {
                                         // > 10MB
                                         let app_name = app_path
                                             .file_name()
@@ -307,7 +309,7 @@ impl CleanRule for AppSupportCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
@@ -316,6 +318,10 @@ impl CleanRule for AppSupportCacheRule {
 pub struct ContainerCacheRule;
 
 impl CleanRule for ContainerCacheRule {
+    fn id(&self) -> &str {
+        "sandboxed_app_caches"
+    }
+
     fn name(&self) -> &str {
         "Sandboxed App Caches"
     }
@@ -338,13 +344,13 @@ impl CleanRule for ContainerCacheRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home.join("Library/Containers"));
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
         for base_path in self.scan_paths() {
@@ -355,8 +361,8 @@ impl CleanRule for ContainerCacheRule {
                         if container_path.is_dir() {
                             let cache_path = container_path.join("Data/Library/Caches");
                             if cache_path.exists() && cache_path.is_dir() {
-                                let size = dir_size(&cache_path);
-                                if size > 5 * 1024 * 1024 {
+                                let size = ctx.dir_size(&cache_path);
+                                if size > ctx.min_size(self, 5) {
                                     // > 5MB
                                     let container_name = container_path
                                         .file_name()
@@ -379,7 +385,7 @@ impl CleanRule for ContainerCacheRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         clean_items(items, to_trash)
     }
 }
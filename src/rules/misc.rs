@@ -1,6 +1,6 @@
 //! Miscellaneous cleanup rules
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, remove_path};
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
@@ -8,6 +8,10 @@ use walkdir::WalkDir;
 pub struct DsStoreRule;
 
 impl CleanRule for DsStoreRule {
+    fn id(&self) -> &str {
+        "ds_store"
+    }
+
     fn name(&self) -> &str {
         ".DS_Store Files"
     }
@@ -31,16 +35,16 @@ impl CleanRule for DsStoreRule {
 
     fn scan_paths(&self) -> Vec<PathBuf> {
         let mut paths = Vec::new();
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             paths.push(home);
         }
         paths
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
+    fn scan(&self, _ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
 
-        if let Some(home) = dirs::home_dir() {
+        if let Some(home) = crate::paths::home_dir() {
             // We restrict scan to specific areas to avoid scanning the entire disk deeply which is slow
             // Let's check Desktop, Documents, Downloads.
             let target_dirs = vec![
@@ -58,7 +62,11 @@ impl CleanRule for DsStoreRule {
                     continue;
                 }
 
-                for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+                for entry in WalkDir::new(dir)
+                    .follow_links(false)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                {
                     if entry.file_type().is_file() && entry.file_name() == ".DS_Store" {
                         if let Ok(metadata) = entry.metadata() {
                             items.push(CleanItem::new(
@@ -77,15 +85,11 @@ impl CleanRule for DsStoreRule {
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
         for item in items {
-            let res = if to_trash {
-                trash::delete(&item.path).map_err(|e| std::io::Error::other(e.to_string()))
-            } else {
-                std::fs::remove_file(&item.path)
-            };
+            let res = remove_path(&item.path, to_trash);
 
             match res {
                 Ok(_) => {
@@ -3,8 +3,13 @@
 //! This module contains the core trait for cleanup rules and implementations
 //! for various platforms and development tools.
 
+mod browser;
+mod context;
+mod custom;
 mod devtools;
 mod docker;
+mod electron;
+mod gamedev;
 mod heuristic;
 #[cfg(target_os = "linux")]
 mod linux;
@@ -13,10 +18,18 @@ mod macos;
 #[cfg(target_os = "macos")]
 mod macos_apps;
 mod misc;
+mod prune;
 mod trash;
+#[cfg(target_os = "windows")]
+mod windows;
 
+pub use browser::*;
+pub use context::ScanContext;
+pub use custom::*;
 pub use devtools::*;
 pub use docker::*;
+pub use electron::*;
+pub use gamedev::*;
 pub use heuristic::*;
 #[cfg(target_os = "linux")]
 pub use linux::*;
@@ -25,19 +38,26 @@ pub use macos::*;
 #[cfg(target_os = "macos")]
 pub use macos_apps::*;
 pub use misc::*;
+pub use prune::*;
 pub use trash::*;
+#[cfg(target_os = "windows")]
+pub use windows::*;
 
+use crate::config::Config;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Risk level for cleanup operations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RiskLevel {
     /// Low risk: cache files that can be safely deleted
+    #[serde(alias = "low")]
     Low,
     /// Medium risk: may affect application performance temporarily
+    #[serde(alias = "medium")]
     Medium,
     /// High risk: requires explicit user confirmation
+    #[serde(alias = "high")]
     High,
 }
 
@@ -51,6 +71,18 @@ impl std::fmt::Display for RiskLevel {
     }
 }
 
+/// Rough cost of running a rule's scan, used to schedule slow rules early
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ScanCost {
+    /// Checks a few known paths
+    Cheap,
+    /// Sizes one or more cache directories
+    #[default]
+    Moderate,
+    /// Walks large parts of the home directory, e.g. looking for projects
+    Expensive,
+}
+
 /// Category of cleanup rules
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Category {
@@ -80,6 +112,26 @@ pub enum Category {
     MacApps,
     /// Linux package managers
     LinuxPackages,
+    /// IDE and editor caches
+    Ide,
+    /// Mobile development (Flutter, CocoaPods)
+    Mobile,
+    /// Ruby (gems, bundler)
+    Ruby,
+    /// .NET (NuGet)
+    DotNet,
+    /// Bazel
+    Bazel,
+    /// Electron app caches
+    Electron,
+    /// Game engines
+    GameDev,
+    /// Haskell (cabal, stack)
+    Haskell,
+    /// OCaml (opam)
+    OCaml,
+    /// PHP (Composer)
+    Php,
     /// Other
     Other(String),
 }
@@ -100,11 +152,109 @@ impl std::fmt::Display for Category {
             Category::Heuristic => write!(f, "Heuristic"),
             Category::MacApps => write!(f, "macOS Apps"),
             Category::LinuxPackages => write!(f, "Linux Packages"),
+            Category::Ide => write!(f, "IDE"),
+            Category::Mobile => write!(f, "Mobile"),
+            Category::Ruby => write!(f, "Ruby"),
+            Category::DotNet => write!(f, "DotNet"),
+            Category::Bazel => write!(f, "Bazel"),
+            Category::Electron => write!(f, "Electron"),
+            Category::GameDev => write!(f, "GameDev"),
+            Category::Haskell => write!(f, "Haskell"),
+            Category::OCaml => write!(f, "OCaml"),
+            Category::Php => write!(f, "PHP"),
             Category::Other(name) => write!(f, "{}", name),
         }
     }
 }
 
+/// Lowercase alphanumeric form of a category name ("Node.js" -> "nodejs")
+fn category_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+impl Category {
+    /// Every category except `Other`
+    pub const BUILTIN: &'static [Category] = &[
+        Category::System,
+        Category::Brew,
+        Category::Xcode,
+        Category::NodeJs,
+        Category::Python,
+        Category::Rust,
+        Category::Go,
+        Category::Java,
+        Category::Docker,
+        Category::Android,
+        Category::Heuristic,
+        Category::MacApps,
+        Category::LinuxPackages,
+        Category::Ide,
+        Category::Mobile,
+        Category::Ruby,
+        Category::DotNet,
+        Category::Bazel,
+        Category::Electron,
+        Category::GameDev,
+        Category::Haskell,
+        Category::OCaml,
+        Category::Php,
+    ];
+
+    /// Canonical name for `--categories` and config files, e.g. "nodejs"
+    pub fn slug(&self) -> String {
+        let slug = match self {
+            Category::System => "system",
+            Category::Brew => "brew",
+            Category::Xcode => "xcode",
+            Category::NodeJs => "nodejs",
+            Category::Python => "python",
+            Category::Rust => "rust",
+            Category::Go => "go",
+            Category::Java => "java",
+            Category::Docker => "docker",
+            Category::Android => "android",
+            Category::Heuristic => "heuristic",
+            Category::MacApps => "macapps",
+            Category::LinuxPackages => "linuxpackages",
+            Category::Ide => "ide",
+            Category::Mobile => "mobile",
+            Category::Ruby => "ruby",
+            Category::DotNet => "dotnet",
+            Category::Bazel => "bazel",
+            Category::Electron => "electron",
+            Category::GameDev => "gamedev",
+            Category::Haskell => "haskell",
+            Category::OCaml => "ocaml",
+            Category::Php => "php",
+            Category::Other(name) => return category_key(name),
+        };
+        slug.to_string()
+    }
+
+    /// Whether `name` refers to this category, by slug or display name
+    ///
+    /// Case and punctuation are ignored, so "nodejs", "Node.js" and "NODEJS"
+    /// all match `NodeJs`.
+    pub fn matches(&self, name: &str) -> bool {
+        let key = category_key(name);
+        key == self.slug() || key == category_key(&self.to_string())
+    }
+
+    /// Parse a category from its slug or display name, case-insensitively
+    ///
+    /// Unknown names become `Category::Other`.
+    pub fn from_name(name: &str) -> Self {
+        Self::BUILTIN
+            .iter()
+            .find(|category| category.matches(name))
+            .cloned()
+            .unwrap_or_else(|| Category::Other(name.to_string()))
+    }
+}
+
 /// A single item that can be cleaned
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CleanItem {
@@ -120,6 +270,10 @@ pub struct CleanItem {
     pub category: Category,
     /// Last modified time (Unix timestamp)
     pub last_modified: Option<i64>,
+    /// Id of the rule that must clean this item itself (command-driven
+    /// items such as Docker resources) instead of deleting `path`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
 }
 
 impl CleanItem {
@@ -138,6 +292,7 @@ impl CleanItem {
             risk_level,
             category,
             last_modified: None,
+            handler: None,
         }
     }
 
@@ -146,10 +301,40 @@ impl CleanItem {
         self.last_modified = Some(timestamp);
         self
     }
+
+    /// Route cleaning of this item through the `clean` of the rule with this id
+    pub fn with_handler(mut self, rule_id: impl Into<String>) -> Self {
+        self.handler = Some(rule_id.into());
+        self
+    }
+
+    /// Fill in `last_modified` from the file system if it is not set yet
+    ///
+    /// Command-driven items (those with a handler) are left untouched.
+    pub fn with_path_mtime(mut self) -> Self {
+        if self.last_modified.is_none() && self.handler.is_none() {
+            self.last_modified = self
+                .path
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64);
+        }
+        self
+    }
+
+    /// Whether this item was last modified more than `days` days ago
+    ///
+    /// Items without a modification time are never stale.
+    pub fn is_stale(&self, days: u32) -> bool {
+        let cutoff = chrono::Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60;
+        self.last_modified.is_some_and(|ts| ts < cutoff)
+    }
 }
 
 /// Result of a cleanup operation
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct CleanResult {
     /// Number of items successfully cleaned
     pub cleaned_count: usize,
@@ -157,6 +342,16 @@ pub struct CleanResult {
     pub bytes_freed: u64,
     /// Items that failed to clean
     pub failed: Vec<(PathBuf, String)>,
+    /// Items cleaned differently than requested, with the reason
+    pub warnings: Vec<(PathBuf, String)>,
+    /// Items moved to the trash rather than deleted permanently
+    pub trashed: Vec<PathBuf>,
+    /// Items left alone because their risk group was declined or the clean
+    /// was cancelled before reaching them
+    pub skipped: Vec<PathBuf>,
+    /// Items that had already disappeared (e.g. removed by another process)
+    /// by the time they were cleaned
+    pub already_gone: Vec<PathBuf>,
     /// Whether the operation was cancelled
     pub cancelled: bool,
 }
@@ -175,12 +370,44 @@ impl CleanResult {
         self.cleaned_count += other.cleaned_count;
         self.bytes_freed += other.bytes_freed;
         self.failed.extend(other.failed);
+        self.warnings.extend(other.warnings);
+        self.trashed.extend(other.trashed);
+        self.skipped.extend(other.skipped);
+        self.already_gone.extend(other.already_gone);
         self.cancelled = self.cancelled || other.cancelled;
     }
 }
 
+/// Move `path` to the trash or delete it permanently
+///
+/// Failures are mapped to typed errors (`PermissionDenied`, `Delete`, `Trash`).
+/// A dangling symlink is removed itself rather than trashed, since there is
+/// nothing behind it to restore.
+pub fn remove_path(path: &Path, to_trash: bool) -> crate::Result<()> {
+    let dangling = path.is_symlink() && !path.exists();
+    if to_trash && !dangling {
+        ::trash::delete(path).map_err(|e| match path.symlink_metadata() {
+            // Report a vanished path as such rather than as a trash failure
+            Err(missing) if missing.kind() == std::io::ErrorKind::NotFound => {
+                crate::Error::delete(path, missing)
+            }
+            _ => crate::Error::trash(path, e.to_string()),
+        })
+    } else if path.is_dir() && !path.is_symlink() {
+        std::fs::remove_dir_all(path).map_err(|e| crate::Error::from_delete(path, e))
+    } else {
+        std::fs::remove_file(path).map_err(|e| crate::Error::from_delete(path, e))
+    }
+}
+
 /// Trait for cleanup rules
 pub trait CleanRule: Send + Sync {
+    /// Stable machine-readable identifier (e.g. "npm_cache")
+    ///
+    /// Used to refer to the rule from the config (`[rules.<id>]`); unlike the
+    /// display name it never changes.
+    fn id(&self) -> &str;
+
     /// Name of the rule
     fn name(&self) -> &str;
 
@@ -200,14 +427,39 @@ pub trait CleanRule: Send + Sync {
     fn scan_paths(&self) -> Vec<PathBuf>;
 
     /// Scan for cleanable items
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>>;
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>>;
+
+    /// How long `scan` is expected to take
+    ///
+    /// Expensive rules are started first so cheap ones finish, and can be
+    /// shown, while they are still running.
+    fn scan_cost(&self) -> ScanCost {
+        ScanCost::Moderate
+    }
 
     /// Clean the specified items
-    fn clean(&self, items: &[CleanItem], to_trash: bool) -> anyhow::Result<CleanResult>;
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult>;
+
+    /// Whether cleaning this rule's items needs root privileges
+    fn requires_root(&self) -> bool {
+        false
+    }
+
+    /// Clean items with root privileges, e.g. by running the owning tool via `sudo`
+    ///
+    /// Only called for rules whose `requires_root` returns true.
+    fn clean_elevated(&self, items: &[CleanItem]) -> crate::Result<CleanResult> {
+        self.clean(items, false)
+    }
 }
 
-/// Get all available rules for the current platform
+/// Get all available rules for the current platform, using the default configuration
 pub fn get_all_rules() -> Vec<Box<dyn CleanRule>> {
+    get_all_rules_with_config(&Config::load_or_default())
+}
+
+/// Get all available rules for the current platform, built from `config`
+pub fn get_all_rules_with_config(config: &Config) -> Vec<Box<dyn CleanRule>> {
     let mut rules: Vec<Box<dyn CleanRule>> = Vec::new();
 
     // Add macOS-specific rules
@@ -220,14 +472,26 @@ pub fn get_all_rules() -> Vec<Box<dyn CleanRule>> {
     // Add Linux-specific rules
     #[cfg(target_os = "linux")]
     {
-        rules.extend(linux::get_linux_rules());
+        rules.extend(linux::get_linux_rules(config));
+    }
+
+    // Add Windows-specific rules
+    #[cfg(target_os = "windows")]
+    {
+        rules.extend(windows::get_windows_rules());
     }
 
     // Add cross-platform dev tools rules
-    rules.extend(devtools::get_devtools_rules());
+    rules.extend(devtools::get_devtools_rules(config));
+
+    // Add game engine rules
+    rules.extend(gamedev::get_gamedev_rules(config));
+
+    // Add browser rules
+    rules.push(Box::new(browser::FirefoxCacheRule));
 
     // Add Docker rule
-    rules.push(Box::new(docker::DockerRule));
+    rules.push(Box::new(docker::DockerRule::default()));
 
     // Add Trash rule
     rules.push(Box::new(trash::TrashRule));
@@ -236,18 +500,369 @@ pub fn get_all_rules() -> Vec<Box<dyn CleanRule>> {
     rules.push(Box::new(misc::DsStoreRule));
 
     // Add heuristic detector
-    rules.push(Box::new(heuristic::HeuristicRule::default()));
+    if config.heuristic.enabled {
+        rules.push(Box::new(heuristic::HeuristicRule::from_config(
+            &config.heuristic,
+            &config.scan,
+        )));
+    }
+
+    // Add user-defined rules
+    rules.extend(
+        config
+            .custom_rules
+            .iter()
+            .map(|rule| Box::new(CustomRule::new(rule.clone())) as Box<dyn CleanRule>),
+    );
+
+    // Drop rules switched off with `[rules.<id>] enabled = false`
+    rules.retain(|rule| config.rule_enabled(rule.id()));
 
     rules
 }
 
 /// Get rules filtered by category
 pub fn get_rules_by_category(categories: &[String]) -> Vec<Box<dyn CleanRule>> {
-    get_all_rules()
+    get_rules_by_category_with_config(&Config::load_or_default(), categories)
+}
+
+/// Get rules built from `config`, filtered by category
+///
+/// An entry may also name a single rule by id (e.g. `npm_cache`).
+pub fn get_rules_by_category_with_config(
+    config: &Config,
+    categories: &[String],
+) -> Vec<Box<dyn CleanRule>> {
+    get_all_rules_with_config(config)
         .into_iter()
         .filter(|rule| {
-            let cat_str = rule.category().to_string().to_lowercase();
-            categories.iter().any(|c| c.to_lowercase() == cat_str)
+            let category = rule.category();
+            categories
+                .iter()
+                .any(|c| category.matches(c) || rule.id() == c)
+        })
+        .collect()
+}
+
+/// Which rules `scan` and `clean` run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RuleSelection {
+    /// `categories.enabled` from the config (every rule if it is empty),
+    /// without `categories.disabled`
+    #[default]
+    Config,
+    /// Every rule, ignoring the `[categories]` section (`--all`)
+    All,
+    /// Rules in these categories or with these ids (`--categories`)
+    Categories(Vec<String>),
+}
+
+impl RuleSelection {
+    /// Selection for the `--categories` and `--all` flags; with neither, the
+    /// config decides
+    pub fn from_flags(categories: Option<Vec<String>>, all: bool) -> Self {
+        match categories {
+            Some(categories) => RuleSelection::Categories(categories),
+            None if all => RuleSelection::All,
+            None => RuleSelection::Config,
+        }
+    }
+
+    /// The selected rules
+    pub fn rules(&self, config: &Config) -> Vec<Box<dyn CleanRule>> {
+        match self {
+            RuleSelection::Categories(categories) => {
+                get_rules_by_category_with_config(config, categories)
+            }
+            RuleSelection::All => get_all_rules_with_config(config),
+            RuleSelection::Config => {
+                let rules = match config.categories.default_selection() {
+                    Some(enabled) => get_rules_by_category_with_config(config, enabled),
+                    None => get_all_rules_with_config(config),
+                };
+                exclude_categories(rules, &config.categories.disabled)
+            }
+        }
+    }
+}
+
+/// Entries of a category selection that match no category or rule id
+pub fn unknown_selections(config: &Config, entries: &[String]) -> Vec<String> {
+    let rules = get_all_rules_with_config(config);
+    entries
+        .iter()
+        .filter(|entry| {
+            !rules
+                .iter()
+                .any(|rule| rule.category().matches(entry) || rule.id() == entry.as_str())
         })
+        .cloned()
         .collect()
 }
+
+/// Read a category/rule selection from a file
+///
+/// Entries are separated by newlines or commas; blank lines and lines
+/// starting with `#` are ignored.
+pub fn read_selection_file(path: &Path) -> crate::Result<Vec<String>> {
+    let content = std::fs::read_to_string(path).map_err(|e| crate::Error::filesystem(path, e))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Drop rules whose category matches one of `excluded` (see `Category::matches`)
+pub fn exclude_categories(
+    rules: Vec<Box<dyn CleanRule>>,
+    excluded: &[String],
+) -> Vec<Box<dyn CleanRule>> {
+    rules
+        .into_iter()
+        .filter(|rule| {
+            let category = rule.category();
+            !excluded.iter().any(|c| category.matches(c))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    fn item_modified_at(timestamp: Option<i64>) -> CleanItem {
+        let item = CleanItem::new(
+            PathBuf::from("/nonexistent"),
+            1,
+            "test",
+            RiskLevel::Low,
+            Category::System,
+        );
+        match timestamp {
+            Some(ts) => item.with_last_modified(ts),
+            None => item,
+        }
+    }
+
+    #[test]
+    fn test_is_stale_boundary() {
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(item_modified_at(Some(now - 30 * DAY - 60)).is_stale(30));
+        assert!(!item_modified_at(Some(now - 30 * DAY + 60)).is_stale(30));
+        assert!(!item_modified_at(Some(now)).is_stale(0));
+        assert!(item_modified_at(Some(now - 60)).is_stale(0));
+    }
+
+    #[test]
+    fn test_items_without_mtime_are_never_stale() {
+        assert!(!item_modified_at(None).is_stale(0));
+
+        // Command-driven items keep no mtime even if the path exists
+        let command_item = CleanItem::new(
+            std::env::temp_dir(),
+            1,
+            "command",
+            RiskLevel::Low,
+            Category::Docker,
+        )
+        .with_handler("docker")
+        .with_path_mtime();
+        assert!(command_item.last_modified.is_none());
+        assert!(!command_item.is_stale(0));
+    }
+
+    #[test]
+    fn test_remove_path_maps_to_typed_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cache.bin");
+        std::fs::write(&file, b"data").unwrap();
+
+        remove_path(&file, false).unwrap();
+        assert!(!file.exists());
+
+        let err = remove_path(&file, false).unwrap_err();
+        assert!(matches!(err, crate::Error::Delete { ref path, .. } if *path == file));
+
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert!(matches!(
+            crate::Error::from_delete(&file, denied),
+            crate::Error::PermissionDenied { .. }
+        ));
+    }
+
+    #[test]
+    fn test_requires_root_flags_system_package_caches() {
+        let _env = env_read();
+        assert!(!CargoTargetRule.requires_root());
+
+        #[cfg(target_os = "linux")]
+        {
+            assert!(AptCacheRule.requires_root());
+            assert!(DnfCacheRule.requires_root());
+            assert!(PacmanCacheRule.requires_root());
+        }
+    }
+
+    #[test]
+    fn test_exclude_categories() {
+        let _env = env_read();
+        let config = Config::default();
+        let all = get_all_rules_with_config(&config).len();
+        let rules = exclude_categories(get_all_rules_with_config(&config), &["DOCKER".to_string()]);
+
+        assert!(rules.iter().all(|rule| rule.category() != Category::Docker));
+        assert_eq!(rules.len(), all - 1);
+    }
+
+    #[test]
+    fn test_category_slugs_round_trip() {
+        let _env = env_read();
+        for category in Category::BUILTIN {
+            assert_eq!(&Category::from_name(&category.slug()), category);
+            assert_eq!(&Category::from_name(&category.to_string()), category);
+        }
+        assert!(Category::NodeJs.matches("Node.js"));
+        assert!(Category::LinuxPackages.matches("linux-packages"));
+        assert!(Category::Other("iOS Backups".to_string()).matches("iosbackups"));
+
+        // Every built-in rule is selectable by its category's slug
+        let config = Config::default();
+        for rule in get_all_rules_with_config(&config) {
+            let slug = rule.category().slug();
+            assert!(
+                get_rules_by_category_with_config(&config, &[slug.to_uppercase()])
+                    .iter()
+                    .any(|selected| selected.id() == rule.id()),
+                "{} not selected by {}",
+                rule.id(),
+                slug
+            );
+        }
+    }
+
+    #[test]
+    fn test_selection_file_mixes_categories_and_rule_ids() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("categories.txt");
+        std::fs::write(
+            &path,
+            "# CI selection\nrust, go\n\nnpm_cache\nnot_a_thing\n",
+        )
+        .unwrap();
+        let config = Config::default();
+
+        let entries = read_selection_file(&path).unwrap();
+        assert_eq!(entries, ["rust", "go", "npm_cache", "not_a_thing"]);
+        assert_eq!(unknown_selections(&config, &entries), ["not_a_thing"]);
+
+        let selected = get_rules_by_category_with_config(&config, &entries);
+        let expected: Vec<_> = get_all_rules_with_config(&config)
+            .into_iter()
+            .filter(|rule| {
+                matches!(rule.category(), Category::Rust | Category::Go) || rule.id() == "npm_cache"
+            })
+            .map(|rule| rule.id().to_string())
+            .collect();
+        assert!(expected.iter().any(|id| id == "npm_cache"));
+        assert_eq!(
+            selected.iter().map(|rule| rule.id()).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_rule_selection_resolves_flags_and_config() {
+        let _env = env_read();
+        let ids = |rules: Vec<Box<dyn CleanRule>>| -> Vec<String> {
+            rules.iter().map(|rule| rule.id().to_string()).collect()
+        };
+        let mut config = Config::default();
+        let all = ids(get_all_rules_with_config(&config));
+        let rust = ids(get_rules_by_category_with_config(
+            &config,
+            &["rust".to_string()],
+        ));
+
+        // No flags and no `enabled` list, or the legacy default one: everything
+        let selection = RuleSelection::from_flags(None, false);
+        assert_eq!(selection, RuleSelection::Config);
+        assert_eq!(ids(selection.rules(&config)), all);
+        config.categories.enabled = ["system", "brew", "xcode", "npm", "pip", "cargo", "docker"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(ids(selection.rules(&config)), all);
+
+        // No flags: the config's categories, without disabled ones
+        config.categories.enabled = vec!["rust".to_string(), "go".to_string()];
+        config.categories.disabled = vec!["go".to_string()];
+        assert_eq!(ids(selection.rules(&config)), rust);
+
+        // --all ignores the config's categories
+        let selection = RuleSelection::from_flags(None, true);
+        assert_eq!(selection, RuleSelection::All);
+        assert_eq!(ids(selection.rules(&config)), all);
+
+        // --categories selects exactly those, even disabled ones
+        let selection = RuleSelection::from_flags(Some(vec!["GO".to_string()]), false);
+        let go = selection.rules(&config);
+        assert!(!go.is_empty());
+        assert!(go.iter().all(|rule| rule.category() == Category::Go));
+    }
+
+    #[test]
+    fn test_heuristic_rule_respects_enabled() {
+        let _env = env_read();
+        let has_heuristic = |config: &Config| {
+            get_all_rules_with_config(config)
+                .iter()
+                .any(|rule| rule.category() == Category::Heuristic)
+        };
+
+        let mut config = Config::default();
+        assert!(has_heuristic(&config));
+
+        config.heuristic.enabled = false;
+        assert!(!has_heuristic(&config));
+    }
+
+    #[test]
+    fn test_rule_ids_are_unique_and_disable_rules() {
+        let _env = env_read();
+        let mut config: Config = toml::from_str(
+            r#"
+            [[custom_rules]]
+            name = "Old Build Logs"
+            paths = ["/tmp/*.log"]
+
+            [rules.docker]
+            enabled = false
+            "#,
+        )
+        .unwrap();
+        let ids = |config: &Config| -> Vec<String> {
+            get_all_rules_with_config(config)
+                .iter()
+                .map(|rule| rule.id().to_string())
+                .collect()
+        };
+
+        let enabled = ids(&config);
+        let unique: std::collections::HashSet<_> = enabled.iter().collect();
+        assert_eq!(unique.len(), enabled.len());
+        assert!(enabled.contains(&"custom_old_build_logs".to_string()));
+        assert!(!enabled.contains(&"docker".to_string()));
+
+        config.rules.clear();
+        assert!(ids(&config).contains(&"docker".to_string()));
+    }
+}
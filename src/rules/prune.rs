@@ -0,0 +1,273 @@
+//! Partial pruning of package caches
+//!
+//! Deleting a whole package cache means re-downloading everything on the next
+//! build. Rules implementing [`PrunableRule`] can instead remove only the
+//! least recently used entries of a cache when a `[prune.rules.<rule id>]`
+//! policy is configured. The cache is then reported as a single item whose
+//! size is what pruning would free, and cleaning it is delegated back to the
+//! rule. The clean plans again with the policy the rule was built with, so
+//! entries used or added since the scan are judged afresh and the amount
+//! freed may differ from the size reported.
+
+use super::{CleanItem, CleanResult, CleanRule, ScanContext, remove_path};
+use crate::config::PrunePolicy;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// A rule whose caches can be pruned entry by entry
+pub trait PrunableRule: CleanRule {
+    /// Policy from the configuration the rule was built with, if any
+    fn prune_policy(&self) -> Option<&PrunePolicy>;
+
+    /// Independently removable entries of `cache`, e.g. files or package versions
+    ///
+    /// Defaults to every file under `cache`.
+    fn prune_units(&self, cache: &Path) -> Vec<PathBuf> {
+        WalkDir::new(cache)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect()
+    }
+}
+
+/// An entry considered for pruning
+#[derive(Debug, Clone)]
+struct PruneUnit {
+    path: PathBuf,
+    size: u64,
+    /// Newest modification time of the entry or anything inside it
+    modified: SystemTime,
+}
+
+impl PruneUnit {
+    fn new(ctx: &ScanContext, path: PathBuf) -> Self {
+        let (size, modified) = if path.is_dir() {
+            (ctx.dir_size(&path), newest_mtime(&path))
+        } else {
+            let metadata = path.symlink_metadata().ok();
+            (
+                metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                metadata.and_then(|m| m.modified().ok()),
+            )
+        };
+        Self {
+            path,
+            size,
+            modified: modified.unwrap_or(SystemTime::UNIX_EPOCH),
+        }
+    }
+}
+
+/// Entries of `cache` the policy would remove
+fn plan(
+    rule: &dyn PrunableRule,
+    ctx: &ScanContext,
+    cache: &Path,
+    policy: &PrunePolicy,
+) -> Vec<PruneUnit> {
+    let mut units: Vec<PruneUnit> = rule
+        .prune_units(cache)
+        .into_iter()
+        .map(|path| PruneUnit::new(ctx, path))
+        .collect();
+    // Newest first, so the first `keep_newest` entries are kept
+    units.sort_by_key(|u| std::cmp::Reverse(u.modified));
+
+    let cutoff = policy
+        .older_than_days
+        .map(|days| SystemTime::now() - Duration::from_secs(u64::from(days) * 24 * 60 * 60));
+    units
+        .into_iter()
+        .skip(policy.keep_newest.unwrap_or(0))
+        .filter(|unit| cutoff.is_none_or(|cutoff| unit.modified < cutoff))
+        .collect()
+}
+
+/// Item for pruning `cache`, or `None` if the policy would remove nothing
+///
+/// The item's size is what pruning frees; its description also gives the
+/// size of the whole cache.
+pub fn prune_item(
+    rule: &dyn PrunableRule,
+    ctx: &ScanContext,
+    cache: &Path,
+    policy: &PrunePolicy,
+    description: &str,
+) -> Option<CleanItem> {
+    let prunable: u64 = plan(rule, ctx, cache, policy).iter().map(|u| u.size).sum();
+    if prunable == 0 {
+        return None;
+    }
+
+    let full = ctx.dir_size(cache);
+    Some(
+        CleanItem::new(
+            cache.to_path_buf(),
+            prunable,
+            format!(
+                "{} (prune {} of {}, {})",
+                description,
+                bytesize::ByteSize::b(prunable),
+                bytesize::ByteSize::b(full),
+                policy
+            ),
+            rule.risk_level(),
+            rule.category(),
+        )
+        .with_handler(rule.id()),
+    )
+}
+
+/// Clean `items`: pruning items produced by [`prune_item`] and removing the rest
+pub fn clean_prunable(
+    rule: &dyn PrunableRule,
+    items: &[CleanItem],
+    to_trash: bool,
+) -> crate::Result<CleanResult> {
+    let ctx = ScanContext::default();
+    let policy = rule.prune_policy();
+    let mut result = CleanResult::default();
+
+    for item in items {
+        if item.handler.is_none() {
+            match remove_path(&item.path, to_trash) {
+                Ok(_) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += item.size;
+                }
+                Err(e) => result.failed.push((item.path.clone(), e.to_string())),
+            }
+            continue;
+        }
+
+        let Some(policy) = policy else {
+            result.failed.push((
+                item.path.clone(),
+                format!("No prune policy configured for {}", rule.name()),
+            ));
+            continue;
+        };
+
+        let mut failed = false;
+        for unit in plan(rule, &ctx, &item.path, policy) {
+            match remove_path(&unit.path, to_trash) {
+                Ok(_) => result.bytes_freed += unit.size,
+                Err(e) => {
+                    failed = true;
+                    result.failed.push((unit.path, e.to_string()));
+                }
+            }
+        }
+        if !failed {
+            result.cleaned_count += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Newest modification time of anything under `dir`
+fn newest_mtime(dir: &Path) -> Option<SystemTime> {
+    WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::PipCacheRule;
+    use crate::test_support::env_read;
+
+    fn set_age(path: &Path, days: u64) {
+        let time = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_and_recent() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("pip");
+        std::fs::create_dir_all(cache.join("http/a")).unwrap();
+        for (name, days) in [("new", 1), ("recent", 10), ("old", 60), ("ancient", 400)] {
+            let path = cache.join("http/a").join(name);
+            std::fs::write(&path, vec![0u8; 1000]).unwrap();
+            set_age(&path, days);
+        }
+        let ctx = ScanContext::default();
+
+        let keep_two = PrunePolicy {
+            keep_newest: Some(2),
+            older_than_days: None,
+        };
+        let item = prune_item(&PipCacheRule::default(), &ctx, &cache, &keep_two, "pip").unwrap();
+        assert_eq!(item.size, 2000);
+        let full = bytesize::ByteSize::b(ctx.dir_size(&cache));
+        assert!(item.description.contains(&format!("of {}", full)));
+
+        let month = PrunePolicy {
+            keep_newest: None,
+            older_than_days: Some(30),
+        };
+        let removed: Vec<String> = plan(&PipCacheRule::default(), &ctx, &cache, &month)
+            .iter()
+            .map(|u| u.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(removed, ["old", "ancient"]);
+
+        let both = PrunePolicy {
+            keep_newest: Some(3),
+            older_than_days: Some(30),
+        };
+        assert_eq!(plan(&PipCacheRule::default(), &ctx, &cache, &both).len(), 1);
+    }
+
+    #[test]
+    fn test_clean_uses_the_rule_policy() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("pip");
+        std::fs::create_dir_all(&cache).unwrap();
+        for (name, days) in [("new", 1), ("old", 60)] {
+            let path = cache.join(name);
+            std::fs::write(&path, vec![0u8; 1000]).unwrap();
+            set_age(&path, days);
+        }
+
+        let mut config = crate::config::PruneConfig::default();
+        config.rules.insert(
+            "pip_cache".to_string(),
+            PrunePolicy {
+                keep_newest: Some(1),
+                older_than_days: None,
+            },
+        );
+        let rule = PipCacheRule::new(&config);
+        let policy = rule.prune_policy().unwrap();
+        let item = prune_item(&rule, &ScanContext::default(), &cache, policy, "pip").unwrap();
+
+        // A rule built without the policy can't tell what to prune
+        let unconfigured =
+            clean_prunable(&PipCacheRule::default(), std::slice::from_ref(&item), false);
+        assert_eq!(unconfigured.unwrap().failed.len(), 1);
+        assert!(cache.join("old").exists());
+
+        let result = clean_prunable(&rule, &[item], false).unwrap();
+        assert_eq!((result.cleaned_count, result.bytes_freed), (1, 1000));
+        assert!(cache.join("new").exists());
+        assert!(!cache.join("old").exists());
+    }
+}
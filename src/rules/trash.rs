@@ -1,12 +1,200 @@
 //! Trash cleanup rule
 
-use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel};
-use std::path::PathBuf;
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext};
+use crate::scanner::fs_util::dir_size;
+use std::path::{Path, PathBuf};
+
+/// A trash directory on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashLocation {
+    /// Root of the trash (the path reported in `CleanItem`s)
+    pub root: PathBuf,
+    /// Directory holding the trashed files
+    pub files: PathBuf,
+    /// Directory holding FreeDesktop `.trashinfo` metadata, if the trash uses that layout
+    pub info: Option<PathBuf>,
+}
+
+impl TrashLocation {
+    /// Describe the trash rooted at `root`
+    ///
+    /// A root containing a `files` directory is treated as a FreeDesktop trash
+    /// (`files/` + `info/`); anything else is a plain directory of trashed items
+    /// like macOS `~/.Trash`.
+    pub fn from_root(root: &Path) -> Self {
+        let files = root.join("files");
+        if files.is_dir() {
+            Self {
+                root: root.to_path_buf(),
+                files,
+                info: Some(root.join("info")),
+            }
+        } else {
+            Self {
+                root: root.to_path_buf(),
+                files: root.to_path_buf(),
+                info: None,
+            }
+        }
+    }
+
+    /// Trashed entries in this location
+    pub fn entries(&self) -> Vec<PathBuf> {
+        std::fs::read_dir(&self.files)
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Metadata file describing a trashed entry (FreeDesktop layout only)
+    fn info_path(&self, entry: &Path) -> Option<PathBuf> {
+        let info = self.info.as_ref()?;
+        let name = entry.file_name()?.to_string_lossy();
+        Some(info.join(format!("{}.trashinfo", name)))
+    }
+
+    /// Total size of the trashed entries and their metadata
+    pub fn size(&self) -> u64 {
+        dir_size(&self.files) + self.info.as_deref().map(dir_size).unwrap_or(0)
+    }
+
+    /// Permanently remove every entry together with its metadata
+    pub fn empty(&self) -> CleanResult {
+        let mut result = CleanResult::default();
+
+        for entry in self.entries() {
+            // Skip macOS Finder metadata that lives alongside trashed items
+            if entry.file_name().map(|n| n == ".DS_Store").unwrap_or(false) {
+                continue;
+            }
+
+            let size = if entry.is_dir() {
+                dir_size(&entry)
+            } else {
+                entry.symlink_metadata().map(|m| m.len()).unwrap_or(0)
+            };
+
+            let removed = if entry.is_dir() && !entry.is_symlink() {
+                std::fs::remove_dir_all(&entry)
+            } else {
+                std::fs::remove_file(&entry)
+            };
+
+            match removed {
+                Ok(_) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += size;
+                    if let Some(info_path) = self.info_path(&entry) {
+                        if let Ok(m) = info_path.metadata() {
+                            result.bytes_freed += m.len();
+                        }
+                        let _ = std::fs::remove_file(&info_path);
+                    }
+                }
+                Err(e) => {
+                    result.failed.push((entry, e.to_string()));
+                }
+            }
+        }
+
+        // Drop metadata left behind for entries that no longer exist
+        if let Some(info) = &self.info {
+            if let Ok(entries) = std::fs::read_dir(info) {
+                for entry in entries.filter_map(|e| e.ok()) {
+                    let path = entry.path();
+                    let orphaned = path
+                        .file_stem()
+                        .map(|stem| !self.files.join(stem).exists())
+                        .unwrap_or(false);
+                    if orphaned && std::fs::remove_file(&path).is_ok() {
+                        result.bytes_freed += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+/// Current user's id, taken from the owner of the home directory
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn current_uid() -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    crate::paths::home_dir()
+        .and_then(|home| home.metadata().ok())
+        .map(|m| m.uid())
+}
+
+/// Enumerate the trash locations for the current user
+#[cfg(target_os = "macos")]
+pub fn trash_locations() -> Vec<TrashLocation> {
+    let mut locations = Vec::new();
+
+    if let Some(home) = crate::paths::home_dir() {
+        let trash = home.join(".Trash");
+        if trash.is_dir() {
+            locations.push(TrashLocation::from_root(&trash));
+        }
+    }
+
+    // External volumes keep per-user trashes in /Volumes/<name>/.Trashes/<uid>
+    if let (Some(uid), Ok(volumes)) = (current_uid(), std::fs::read_dir("/Volumes")) {
+        for volume in volumes.filter_map(|e| e.ok()) {
+            let trash = volume.path().join(".Trashes").join(uid.to_string());
+            if trash.is_dir() {
+                locations.push(TrashLocation::from_root(&trash));
+            }
+        }
+    }
+
+    locations
+}
+
+/// Enumerate the trash locations for the current user
+#[cfg(target_os = "linux")]
+pub fn trash_locations() -> Vec<TrashLocation> {
+    let mut locations = Vec::new();
+
+    // Home trash: $XDG_DATA_HOME/Trash
+    if let Some(data_home) = crate::paths::data_local_dir() {
+        let trash = data_home.join("Trash");
+        if trash.is_dir() {
+            locations.push(TrashLocation::from_root(&trash));
+        }
+    }
+
+    // Mounted volumes use $topdir/.Trash/$uid or $topdir/.Trash-$uid
+    if let (Some(uid), Ok(mounts)) = (current_uid(), std::fs::read_to_string("/proc/mounts")) {
+        for mount_point in mounts.lines().filter_map(|l| l.split_whitespace().nth(1)) {
+            let top = PathBuf::from(mount_point.replace("\\040", " "));
+            for trash in [
+                top.join(".Trash").join(uid.to_string()),
+                top.join(format!(".Trash-{}", uid)),
+            ] {
+                if trash.join("files").is_dir() && !locations.iter().any(|l| l.root == trash) {
+                    locations.push(TrashLocation::from_root(&trash));
+                }
+            }
+        }
+    }
+
+    locations
+}
+
+/// Enumerate the trash locations for the current user
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn trash_locations() -> Vec<TrashLocation> {
+    Vec::new()
+}
 
 /// Trash cleanup rule
 pub struct TrashRule;
 
 impl CleanRule for TrashRule {
+    fn id(&self) -> &str {
+        "trash"
+    }
+
     fn name(&self) -> &str {
         "Trash"
     }
@@ -28,116 +216,115 @@ impl CleanRule for TrashRule {
     }
 
     fn scan_paths(&self) -> Vec<PathBuf> {
-        Vec::new() // Not path based in the traditional sense
+        trash_locations().into_iter().map(|l| l.root).collect()
     }
 
-    fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
-        // We can't easily list trash items with the `trash` crate in a cross-platform way
-        // that gives us sizes effectively for individual files without some work.
-        // But we can check if it's empty or not, or just represent it as one "Trash" item.
-
-        // For accurate size, we might need platform specific logic.
-        // But `trash` crate doesn't expose list/size easily in version 5?
-        // Let's check imports in Cargo.toml. Yes "trash = 5".
-        // Actually, checking trash size can be complex.
-        // For macOS we can check ~/.Trash.
-
+    fn scan(&self, _ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
         let mut items = Vec::new();
-        let mut total_size = 0;
-        let mut found = false;
-
-        #[cfg(target_os = "macos")]
-        {
-            if let Some(home) = dirs::home_dir() {
-                let trash_path = home.join(".Trash");
-                if trash_path.exists() {
-                    // Simple recursive size check
-                    total_size += dir_size(&trash_path);
-                    if total_size > 0 {
-                        found = true;
-                    }
-                }
-            }
-        }
 
-        // On Linux, trash usually in ~/.local/share/Trash
-        #[cfg(target_os = "linux")]
-        {
-            if let Some(home) = dirs::home_dir() {
-                let trash_path = home.join(".local/share/Trash/files");
-                if trash_path.exists() {
-                    total_size += dir_size(&trash_path);
-                    if total_size > 0 {
-                        found = true;
-                    }
-                }
+        for location in trash_locations() {
+            let count = location.entries().len();
+            let size = location.size();
+            if count == 0 || size == 0 {
+                continue;
             }
-        }
 
-        if found {
-            items.push(CleanItem::new(
-                PathBuf::from("System Trash"),
-                total_size,
-                "All items in the Trash",
-                self.risk_level(),
-                self.category(),
-            ));
+            // The trash itself must never be moved to the trash, so route
+            // cleaning through this rule
+            items.push(
+                CleanItem::new(
+                    location.root.clone(),
+                    size,
+                    format!("Trash ({} items)", count),
+                    self.risk_level(),
+                    self.category(),
+                )
+                .with_handler(self.id()),
+            );
         }
 
         Ok(items)
     }
 
-    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> anyhow::Result<CleanResult> {
+    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
         let mut result = CleanResult::default();
 
-        // trash crate doesn't have "empty trash" function directly?
-        // Checking docs... it usually handles moving TO trash.
-        // To empty trash, we might need to actually delete the files in the trash folders.
-        // Or use platform specific commands.
-        // On macOS: `rm -rf ~/.Trash/*` (risky if not careful)
-
-        // Let's rely on manual deletion of contents for now safely.
-
         for item in items {
-            if item.path.to_string_lossy() == "System Trash" {
-                // Delete contents of trash folders
-                #[cfg(target_os = "macos")]
-                {
-                    if let Some(home) = dirs::home_dir() {
-                        let trash_path = home.join(".Trash");
-                        if let Ok(entries) = std::fs::read_dir(&trash_path) {
-                            for entry in entries.filter_map(|e| e.ok()) {
-                                let path = entry.path();
-                                if path.is_dir() {
-                                    if std::fs::remove_dir_all(&path).is_ok() {
-                                        // Count rough estimate?
-                                    }
-                                } else if std::fs::remove_file(&path).is_ok() {
-                                    // Count
-                                }
-                            }
-                        }
-                    }
-                }
-
-                // Result updates are tricky without exact counts
-                result.cleaned_count += 1; // Count the "Trash" item itself
-                result.bytes_freed += item.size;
-            }
+            result.merge(TrashLocation::from_root(&item.path).empty());
         }
 
         Ok(result)
     }
 }
 
-/// Calculate directory size recursively (copied helper)
-fn dir_size(path: &std::path::Path) -> u64 {
-    use walkdir::WalkDir;
-    WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter_map(|e| e.metadata().ok())
-        .map(|m| m.len())
-        .sum()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(target_os = "linux")]
+    use crate::test_support::with_env;
+
+    /// Build a FreeDesktop trash under a temporary XDG_DATA_HOME
+    fn freedesktop_trash(data_home: &Path) -> TrashLocation {
+        let root = data_home.join("Trash");
+        std::fs::create_dir_all(root.join("files/dir")).unwrap();
+        std::fs::create_dir_all(root.join("info")).unwrap();
+        std::fs::write(root.join("files/a.txt"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("files/dir/b.bin"), vec![0u8; 200]).unwrap();
+        std::fs::write(root.join("info/a.txt.trashinfo"), "[Trash Info]\n").unwrap();
+        std::fs::write(root.join("info/dir.trashinfo"), "[Trash Info]\n").unwrap();
+        std::fs::write(root.join("info/gone.trashinfo"), "[Trash Info]\n").unwrap();
+        TrashLocation::from_root(&root)
+    }
+
+    #[test]
+    fn test_freedesktop_layout_detected() {
+        let xdg = tempfile::tempdir().unwrap();
+        let location = freedesktop_trash(xdg.path());
+
+        assert_eq!(location.files, xdg.path().join("Trash/files"));
+        assert_eq!(location.info, Some(xdg.path().join("Trash/info")));
+        assert_eq!(location.entries().len(), 2);
+        assert!(location.size() >= 300);
+    }
+
+    #[test]
+    fn test_empty_removes_files_and_info() {
+        let xdg = tempfile::tempdir().unwrap();
+        let location = freedesktop_trash(xdg.path());
+
+        let result = location.empty();
+
+        assert_eq!(result.cleaned_count, 2);
+        assert!(result.failed.is_empty());
+        assert!(result.bytes_freed >= 300);
+        assert!(location.entries().is_empty());
+        let remaining_info = std::fs::read_dir(xdg.path().join("Trash/info"))
+            .unwrap()
+            .count();
+        assert_eq!(remaining_info, 0);
+    }
+
+    #[test]
+    fn test_plain_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("old.txt"), b"data").unwrap();
+
+        let location = TrashLocation::from_root(dir.path());
+
+        assert_eq!(location.files, dir.path());
+        assert!(location.info.is_none());
+        assert_eq!(location.empty().cleaned_count, 1);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_home_trash_follows_xdg_data_home() {
+        let xdg = tempfile::tempdir().unwrap();
+        let location = freedesktop_trash(xdg.path());
+
+        with_env(&[("XDG_DATA_HOME", Some(xdg.path()))], || {
+            let roots: Vec<_> = trash_locations().into_iter().map(|l| l.files).collect();
+            assert!(roots.contains(&location.files), "{:?}", roots);
+        });
+    }
 }
@@ -0,0 +1,414 @@
+//! Windows-specific cleanup rules
+
+use super::{Category, CleanItem, CleanResult, CleanRule, RiskLevel, ScanContext, remove_path};
+use crate::scanner::fs_util::dir_size;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Temp entries younger than this may still be in use by a running program
+const TEMP_MIN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Get all Windows-specific rules
+pub fn get_windows_rules() -> Vec<Box<dyn CleanRule>> {
+    vec![
+        Box::new(WindowsTempRule),
+        Box::new(WindowsCrashDumpsRule),
+        Box::new(WindowsNpmCacheRule),
+        Box::new(WindowsNuGetCacheRule),
+        Box::new(RecycleBinRule),
+    ]
+}
+
+/// Common function to clean items
+fn clean_items(items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+    let mut result = CleanResult::default();
+
+    for item in items {
+        match remove_path(&item.path, to_trash) {
+            Ok(_) => {
+                result.cleaned_count += 1;
+                result.bytes_freed += item.size;
+            }
+            Err(e) => {
+                result.failed.push((item.path.clone(), e.to_string()));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// `%LOCALAPPDATA%` joined with `relative`
+fn local_app_data(relative: &str) -> Option<PathBuf> {
+    crate::paths::data_local_dir().map(|dir| dir.join(relative))
+}
+
+/// One item per existing directory in `paths`, each at least `min_size` bytes
+fn dir_items(
+    rule: &dyn CleanRule,
+    ctx: &ScanContext,
+    paths: Vec<PathBuf>,
+    description: &str,
+    min_size: u64,
+) -> Vec<CleanItem> {
+    paths
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let size = ctx.dir_size(&path);
+            (size > min_size).then(|| {
+                CleanItem::new(path, size, description, rule.risk_level(), rule.category())
+            })
+        })
+        .collect()
+}
+
+/// User temp directory rule (`%TEMP%`)
+pub struct WindowsTempRule;
+
+impl CleanRule for WindowsTempRule {
+    fn id(&self) -> &str {
+        "windows_temp"
+    }
+
+    fn name(&self) -> &str {
+        "Windows Temp Files"
+    }
+
+    fn category(&self) -> Category {
+        Category::System
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Files in the user temp directory not touched for a day"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        vec![std::env::temp_dir()]
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let mut items = Vec::new();
+        let cutoff = SystemTime::now() - TEMP_MIN_AGE;
+        let min_size = ctx.min_size(self, 1);
+
+        for temp in self.scan_paths() {
+            let Ok(entries) = std::fs::read_dir(&temp) else {
+                continue;
+            };
+
+            // Each top-level entry is removed on its own; the directory
+            // itself stays
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.modified().is_ok_and(|modified| modified > cutoff) {
+                    continue;
+                }
+
+                let size = if metadata.is_dir() {
+                    ctx.dir_size(&path)
+                } else {
+                    metadata.len()
+                };
+                if size > min_size {
+                    items.push(CleanItem::new(
+                        path,
+                        size,
+                        "Temporary file",
+                        self.risk_level(),
+                        self.category(),
+                    ));
+                }
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Crash dump and error report rule
+pub struct WindowsCrashDumpsRule;
+
+impl CleanRule for WindowsCrashDumpsRule {
+    fn id(&self) -> &str {
+        "windows_crash_dumps"
+    }
+
+    fn name(&self) -> &str {
+        "Windows Crash Dumps"
+    }
+
+    fn category(&self) -> Category {
+        Category::System
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "Application crash dumps and archived Windows Error Reporting reports"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        ["CrashDumps", r"Microsoft\Windows\WER\ReportArchive"]
+            .into_iter()
+            .filter_map(local_app_data)
+            .collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(dir_items(
+            self,
+            ctx,
+            self.scan_paths(),
+            "Crash dumps and error reports",
+            ctx.min_size(self, 0),
+        ))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// npm cache rule (`%LOCALAPPDATA%\npm-cache`)
+pub struct WindowsNpmCacheRule;
+
+impl CleanRule for WindowsNpmCacheRule {
+    fn id(&self) -> &str {
+        "windows_npm_cache"
+    }
+
+    fn name(&self) -> &str {
+        "npm Cache (Windows)"
+    }
+
+    fn category(&self) -> Category {
+        Category::NodeJs
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "npm package cache and logs"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        [r"npm-cache\_cacache", r"npm-cache\_logs"]
+            .into_iter()
+            .filter_map(local_app_data)
+            .collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(dir_items(
+            self,
+            ctx,
+            self.scan_paths(),
+            "npm cache",
+            ctx.min_size(self, 0),
+        ))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// NuGet HTTP and plugin cache rule (`%LOCALAPPDATA%\NuGet`)
+///
+/// The global packages folder (`~\.nuget\packages`) is covered by the
+/// cross-platform NuGet rule.
+pub struct WindowsNuGetCacheRule;
+
+impl CleanRule for WindowsNuGetCacheRule {
+    fn id(&self) -> &str {
+        "windows_nuget_cache"
+    }
+
+    fn name(&self) -> &str {
+        "NuGet Cache (Windows)"
+    }
+
+    fn category(&self) -> Category {
+        Category::DotNet
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Low
+    }
+
+    fn description(&self) -> &str {
+        "NuGet HTTP and plugin caches"
+    }
+
+    fn is_applicable(&self) -> bool {
+        self.scan_paths().iter().any(|p| p.exists())
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        [r"NuGet\v3-cache", r"NuGet\plugins-cache"]
+            .into_iter()
+            .filter_map(local_app_data)
+            .collect()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        Ok(dir_items(
+            self,
+            ctx,
+            self.scan_paths(),
+            "NuGet cache",
+            ctx.min_size(self, 0),
+        ))
+    }
+
+    fn clean(&self, items: &[CleanItem], to_trash: bool) -> crate::Result<CleanResult> {
+        clean_items(items, to_trash)
+    }
+}
+
+/// Recycle Bin rule
+///
+/// The Recycle Bin is listed and emptied through the shell via the `trash`
+/// crate rather than by deleting `$Recycle.Bin` directly.
+pub struct RecycleBinRule;
+
+impl CleanRule for RecycleBinRule {
+    fn id(&self) -> &str {
+        "recycle_bin"
+    }
+
+    fn name(&self) -> &str {
+        "Recycle Bin"
+    }
+
+    fn category(&self) -> Category {
+        Category::System
+    }
+
+    fn risk_level(&self) -> RiskLevel {
+        RiskLevel::Medium
+    }
+
+    fn description(&self) -> &str {
+        "Empty the Windows Recycle Bin"
+    }
+
+    fn is_applicable(&self) -> bool {
+        trash::os_limited::is_empty().is_ok_and(|empty| !empty)
+    }
+
+    fn scan_paths(&self) -> Vec<PathBuf> {
+        let drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+        vec![PathBuf::from(format!(r"{}\$Recycle.Bin", drive))]
+    }
+
+    fn scan(&self, _ctx: &ScanContext) -> crate::Result<Vec<CleanItem>> {
+        let entries = trash::os_limited::list()
+            .map_err(|e| crate::Error::Other(format!("Failed to list Recycle Bin: {}", e)))?;
+        if entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Directories only report their entry count, so measure them on disk
+        let size: u64 = entries
+            .iter()
+            .map(
+                |entry| match trash::os_limited::metadata(entry).map(|m| m.size) {
+                    Ok(trash::TrashItemSize::Bytes(bytes)) => bytes,
+                    _ => dir_size(&PathBuf::from(&entry.id)),
+                },
+            )
+            .sum();
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+
+        Ok(self
+            .scan_paths()
+            .into_iter()
+            .map(|path| {
+                CleanItem::new(
+                    path,
+                    size,
+                    format!("Recycle Bin ({} items)", entries.len()),
+                    self.risk_level(),
+                    self.category(),
+                )
+                .with_handler(self.id())
+            })
+            .collect())
+    }
+
+    fn clean(&self, items: &[CleanItem], _to_trash: bool) -> crate::Result<CleanResult> {
+        let mut result = CleanResult::default();
+
+        let purged = trash::os_limited::list().and_then(trash::os_limited::purge_all);
+        for item in items {
+            match &purged {
+                Ok(()) => {
+                    result.cleaned_count += 1;
+                    result.bytes_freed += item.size;
+                }
+                Err(e) => result.failed.push((item.path.clone(), e.to_string())),
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+
+    #[test]
+    fn test_temp_rule_skips_recent_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let old = temp.path().join("old.tmp");
+        let recent = temp.path().join("recent.tmp");
+        for path in [&old, &recent] {
+            std::fs::write(path, vec![0u8; 2 * 1024 * 1024]).unwrap();
+        }
+        std::fs::File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * TEMP_MIN_AGE)
+            .unwrap();
+
+        with_env(&[("TMP", Some(temp.path()))], || {
+            let items = WindowsTempRule.scan(&ScanContext::default()).unwrap();
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].path, old);
+        });
+    }
+}
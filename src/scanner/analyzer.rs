@@ -1,11 +1,17 @@
 //! Storage analyzer for analyzing disk usage
 
-use std::collections::HashMap;
+use super::duplicates::{DuplicateGroup, find_duplicates};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use walkdir::WalkDir;
 
 /// Storage usage information
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct StorageInfo {
     /// Total size analyzed
     pub total_size: u64,
@@ -17,6 +23,95 @@ pub struct StorageInfo {
     pub by_extension: HashMap<String, u64>,
     /// Largest files
     pub largest_files: Vec<(PathBuf, u64)>,
+    /// Sets of identical files, when duplicate detection is enabled
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<DuplicateGroup>,
+}
+
+impl StorageInfo {
+    /// Sizes rolled up into [`ExtensionGroup`]s, largest first
+    pub fn by_group(&self) -> Vec<(ExtensionGroup, u64)> {
+        self.by_group_with(&BTreeMap::new())
+    }
+
+    /// Like [`Self::by_group`], with `overrides` taking precedence over the
+    /// built-in mapping (keys are lowercase extensions without the dot)
+    pub fn by_group_with(
+        &self,
+        overrides: &BTreeMap<String, ExtensionGroup>,
+    ) -> Vec<(ExtensionGroup, u64)> {
+        let mut groups: BTreeMap<ExtensionGroup, u64> = BTreeMap::new();
+        for (ext, size) in &self.by_extension {
+            let group = overrides
+                .get(ext)
+                .copied()
+                .unwrap_or_else(|| ExtensionGroup::for_extension(ext));
+            *groups.entry(group).or_insert(0) += size;
+        }
+
+        let mut groups: Vec<(ExtensionGroup, u64)> = groups.into_iter().collect();
+        groups.sort_by(|a, b| b.1.cmp(&a.1));
+        groups
+    }
+}
+
+/// Human-friendly file type of an extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtensionGroup {
+    Video,
+    Images,
+    Audio,
+    Archives,
+    /// Disk images and installers
+    DiskImages,
+    Documents,
+    Code,
+    /// Databases, logs and other data files
+    Data,
+    Other,
+}
+
+impl ExtensionGroup {
+    /// Built-in group of a lowercase extension without the dot
+    pub fn for_extension(ext: &str) -> Self {
+        match ext {
+            "mp4" | "mov" | "mkv" | "avi" | "webm" | "m4v" | "wmv" | "flv" => Self::Video,
+            "jpg" | "jpeg" | "png" | "gif" | "heic" | "webp" | "tiff" | "tif" | "bmp" | "raw"
+            | "cr2" | "nef" | "arw" | "dng" | "svg" | "psd" => Self::Images,
+            "mp3" | "m4a" | "wav" | "flac" | "aac" | "ogg" | "aiff" | "opus" => Self::Audio,
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "jar" => {
+                Self::Archives
+            }
+            "dmg" | "iso" | "img" | "pkg" | "deb" | "rpm" | "appimage" | "vmdk" | "qcow2" => {
+                Self::DiskImages
+            }
+            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "pages" | "numbers"
+            | "key" | "txt" | "md" | "rtf" | "odt" | "epub" => Self::Documents,
+            "rs" | "c" | "h" | "cpp" | "hpp" | "cc" | "go" | "py" | "js" | "ts" | "tsx" | "jsx"
+            | "java" | "kt" | "swift" | "m" | "rb" | "php" | "cs" | "sh" | "html" | "css"
+            | "scss" | "vue" | "dart" | "lua" | "toml" | "yaml" | "yml" | "json" => Self::Code,
+            "db" | "sqlite" | "sqlite3" | "log" | "csv" | "parquet" | "bin" | "dat" => Self::Data,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for ExtensionGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Video => "Video",
+            Self::Images => "Images",
+            Self::Audio => "Audio",
+            Self::Archives => "Archives",
+            Self::DiskImages => "Disk Images",
+            Self::Documents => "Documents",
+            Self::Code => "Code",
+            Self::Data => "Data",
+            Self::Other => "Other",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 /// Storage analyzer
@@ -26,6 +121,14 @@ pub struct StorageAnalyzer {
     max_depth: Option<usize>,
     /// Number of largest files to track
     top_n: usize,
+    /// Stay on the file system of the analyzed root, like `du -x`
+    same_filesystem: bool,
+    /// Minimum size of files checked for duplicates, if enabled
+    duplicates_min_size: Option<u64>,
+    /// Only count files with one of these extensions, if set
+    only_extensions: Option<HashSet<String>>,
+    /// Ignore files with these extensions
+    excluded_extensions: HashSet<String>,
 }
 
 impl StorageAnalyzer {
@@ -34,6 +137,10 @@ impl StorageAnalyzer {
         Self {
             max_depth: None,
             top_n: 10,
+            same_filesystem: false,
+            duplicates_min_size: None,
+            only_extensions: None,
+            excluded_extensions: HashSet::new(),
         }
     }
 
@@ -49,10 +156,74 @@ impl StorageAnalyzer {
         self
     }
 
+    /// Skip mounted volumes and pseudo file systems (e.g. `/proc`, network
+    /// drives) that live on a different device than the analyzed root
+    pub fn with_same_filesystem(mut self, same_filesystem: bool) -> Self {
+        self.same_filesystem = same_filesystem;
+        self
+    }
+
+    /// Find duplicate files of at least `min_size` bytes
+    pub fn with_duplicates(mut self, min_size: u64) -> Self {
+        self.duplicates_min_size = Some(min_size);
+        self
+    }
+
+    /// Only count files with one of these extensions (case-insensitive,
+    /// with or without the leading dot)
+    pub fn with_only_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.only_extensions = Some(normalize_extensions(extensions));
+        self
+    }
+
+    /// Ignore files with these extensions (case-insensitive, with or without
+    /// the leading dot)
+    pub fn with_excluded_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.excluded_extensions = normalize_extensions(extensions);
+        self
+    }
+
+    /// Whether a file with this lowercase extension passes the filters
+    fn includes_extension(&self, ext: Option<&str>) -> bool {
+        if let Some(only) = &self.only_extensions
+            && !ext.is_some_and(|ext| only.contains(ext))
+        {
+            return false;
+        }
+        !ext.is_some_and(|ext| self.excluded_extensions.contains(ext))
+    }
+
     /// Analyze a directory
     pub fn analyze(&self, path: &PathBuf) -> anyhow::Result<StorageInfo> {
-        let mut info = StorageInfo::default();
-        let mut largest: Vec<(PathBuf, u64)> = Vec::with_capacity(self.top_n + 1);
+        self.analyze_with_progress(path, |_, _| {})
+    }
+
+    /// Like [`Self::analyze`], calling `on_file` with the running file count
+    /// and total size after each counted file
+    ///
+    /// Files are counted on several threads, so `on_file` may be called
+    /// concurrently and the totals it sees are not strictly increasing.
+    pub fn analyze_with_progress<F>(
+        &self,
+        path: &PathBuf,
+        on_file: F,
+    ) -> anyhow::Result<StorageInfo>
+    where
+        F: Fn(usize, u64) + Sync,
+    {
+        // (device, inode) pairs of hardlinked files already counted
+        let seen_links: Mutex<HashSet<(u64, u64)>> = Mutex::new(HashSet::new());
+        // Running totals for progress, shared across threads
+        let files_seen = AtomicUsize::new(0);
+        let bytes_seen = AtomicU64::new(0);
 
         let walker = if let Some(depth) = self.max_depth {
             WalkDir::new(path).max_depth(depth)
@@ -60,33 +231,75 @@ impl StorageAnalyzer {
             WalkDir::new(path)
         };
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            let entry_path = entry.path();
+        // Directory reads stay sequential; the per-entry stat calls and the
+        // bookkeeping run on the rayon pool, each thread folding into its own
+        // partial result. walkdir compares each directory's device with the root's.
+        let totals = walker
+            .follow_links(false)
+            .same_file_system(self.same_filesystem)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .par_bridge()
+            .fold(
+                || Partial::new(self.top_n),
+                |mut partial, entry| {
+                    // Entry metadata does not traverse symlinks
+                    let Ok(metadata) = entry.metadata() else {
+                        return partial;
+                    };
+                    if metadata.is_dir() {
+                        partial.dir_count += 1;
+                        return partial;
+                    }
+                    if !metadata.is_file() {
+                        return partial;
+                    }
+
+                    let entry_path = entry.path();
+                    let ext = entry_path
+                        .extension()
+                        .map(|ext| ext.to_string_lossy().to_lowercase());
+                    if !self.includes_extension(ext.as_deref())
+                        || !first_link(&metadata, &seen_links)
+                    {
+                        return partial;
+                    }
 
-            if let Ok(metadata) = entry_path.metadata() {
-                if metadata.is_file() {
                     let size = metadata.len();
-                    info.total_size += size;
-                    info.file_count += 1;
+                    partial.total_size += size;
+                    partial.file_count += 1;
+                    on_file(
+                        files_seen.fetch_add(1, Ordering::Relaxed) + 1,
+                        bytes_seen.fetch_add(size, Ordering::Relaxed) + size,
+                    );
 
                     // Track by extension
-                    if let Some(ext) = entry_path.extension() {
-                        let ext_str = ext.to_string_lossy().to_lowercase();
-                        *info.by_extension.entry(ext_str).or_insert(0) += size;
+                    if let Some(ext) = ext {
+                        *partial.by_extension.entry(ext).or_insert(0) += size;
                     }
 
-                    // Track largest files
-                    largest.push((entry_path.to_path_buf(), size));
-                    largest.sort_by(|a, b| b.1.cmp(&a.1));
-                    largest.truncate(self.top_n);
-                } else if metadata.is_dir() {
-                    info.dir_count += 1;
-                }
-            }
-        }
+                    if self.duplicates_min_size.is_some_and(|min| size >= min) {
+                        partial
+                            .by_size
+                            .entry(size)
+                            .or_default()
+                            .push(entry_path.to_path_buf());
+                    }
+
+                    partial.largest.push(entry_path.to_path_buf(), size);
+                    partial
+                },
+            )
+            .reduce(|| Partial::new(self.top_n), Partial::merge);
 
-        info.largest_files = largest;
-        Ok(info)
+        Ok(StorageInfo {
+            total_size: totals.total_size,
+            file_count: totals.file_count,
+            dir_count: totals.dir_count,
+            by_extension: totals.by_extension,
+            largest_files: totals.largest.into_sorted_vec(),
+            duplicates: find_duplicates(totals.by_size),
+        })
     }
 
     /// Analyze multiple directories
@@ -105,6 +318,7 @@ impl StorageAnalyzer {
                 }
 
                 combined.largest_files.extend(info.largest_files);
+                combined.duplicates.extend(info.duplicates);
             }
         }
 
@@ -116,7 +330,323 @@ impl StorageAnalyzer {
     }
 }
 
+/// Lowercase extensions without the leading dot
+fn normalize_extensions<I, S>(extensions: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    extensions
+        .into_iter()
+        .map(|ext| ext.as_ref().trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+/// One thread's share of an analysis, merged into the final [`StorageInfo`]
+struct Partial {
+    total_size: u64,
+    file_count: usize,
+    dir_count: usize,
+    by_extension: HashMap<String, u64>,
+    largest: TopFiles,
+    /// Duplicate candidates keyed by size
+    by_size: HashMap<u64, Vec<PathBuf>>,
+}
+
+impl Partial {
+    fn new(top_n: usize) -> Self {
+        Self {
+            total_size: 0,
+            file_count: 0,
+            dir_count: 0,
+            by_extension: HashMap::new(),
+            largest: TopFiles::new(top_n),
+            by_size: HashMap::new(),
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.total_size += other.total_size;
+        self.file_count += other.file_count;
+        self.dir_count += other.dir_count;
+        for (ext, size) in other.by_extension {
+            *self.by_extension.entry(ext).or_insert(0) += size;
+        }
+        for (path, size) in other
+            .largest
+            .heap
+            .into_iter()
+            .map(|Reverse(file)| file.into_pair())
+        {
+            self.largest.push(path, size);
+        }
+        for (size, paths) in other.by_size {
+            self.by_size.entry(size).or_default().extend(paths);
+        }
+        self
+    }
+}
+
+/// File ordered by size, then by path with earlier paths ranking higher
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RankedFile(u64, Reverse<PathBuf>);
+
+impl RankedFile {
+    fn into_pair(self) -> (PathBuf, u64) {
+        (self.1.0, self.0)
+    }
+}
+
+/// The `capacity` largest files seen so far
+///
+/// A min-heap of the current top files, so each insert is O(log capacity)
+/// and only displaces the smallest entry.
+struct TopFiles {
+    capacity: usize,
+    heap: BinaryHeap<Reverse<RankedFile>>,
+}
+
+impl TopFiles {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity + 1),
+        }
+    }
+
+    fn push(&mut self, path: PathBuf, size: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.heap.len() == self.capacity {
+            // Cheap size check before allocating a comparison key
+            match self.heap.peek() {
+                Some(Reverse(smallest)) if size < smallest.0 => return,
+                _ => {}
+            }
+        }
+        self.heap.push(Reverse(RankedFile(size, Reverse(path))));
+        if self.heap.len() > self.capacity {
+            self.heap.pop();
+        }
+    }
+
+    /// Largest first, ties by path
+    fn into_sorted_vec(self) -> Vec<(PathBuf, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(file)| file.into_pair())
+            .collect()
+    }
+}
+
+/// Whether this is the first time a (possibly hardlinked) file is seen
+#[cfg(unix)]
+fn first_link(metadata: &std::fs::Metadata, seen: &Mutex<HashSet<(u64, u64)>>) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink() <= 1
+        || seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((metadata.dev(), metadata.ino()))
+}
+
+/// Whether this is the first time a (possibly hardlinked) file is seen
+#[cfg(not(unix))]
+fn first_link(_metadata: &std::fs::Metadata, _seen: &Mutex<HashSet<(u64, u64)>>) -> bool {
+    true
+}
+
 /// Format bytes to human-readable string
 pub fn format_bytes(bytes: u64) -> String {
     bytesize::ByteSize::b(bytes).to_string()
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_group_rolls_up_extensions() {
+        let info = StorageInfo {
+            by_extension: HashMap::from([
+                ("mp4".to_string(), 500),
+                ("mov".to_string(), 300),
+                ("png".to_string(), 100),
+                ("blend".to_string(), 50),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            info.by_group(),
+            [
+                (ExtensionGroup::Video, 800),
+                (ExtensionGroup::Images, 100),
+                (ExtensionGroup::Other, 50),
+            ]
+        );
+
+        let overrides = BTreeMap::from([
+            ("blend".to_string(), ExtensionGroup::Images),
+            ("png".to_string(), ExtensionGroup::Documents),
+        ]);
+        assert_eq!(
+            info.by_group_with(&overrides),
+            [
+                (ExtensionGroup::Video, 800),
+                (ExtensionGroup::Documents, 100),
+                (ExtensionGroup::Images, 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_matches_serial_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        for d in 0..8 {
+            let sub = root.join(format!("dir{d}/nested"));
+            std::fs::create_dir_all(&sub).unwrap();
+            for f in 0..25 {
+                let ext = ["bin", "LOG", "txt", "mp4"][f % 4];
+                // Repeated sizes exercise the path tie-break in the top list
+                let size = (d * 25 + f) % 40 * 10;
+                std::fs::write(sub.join(format!("f{f}.{ext}")), vec![0u8; size]).unwrap();
+            }
+        }
+
+        // Serial reference: plain walk, full sort
+        let mut expected = StorageInfo::default();
+        let mut files = Vec::new();
+        for entry in WalkDir::new(&root).into_iter().filter_map(|e| e.ok()) {
+            let metadata = entry.metadata().unwrap();
+            if metadata.is_dir() {
+                expected.dir_count += 1;
+            } else {
+                expected.total_size += metadata.len();
+                expected.file_count += 1;
+                let ext = entry.path().extension().unwrap().to_string_lossy();
+                *expected.by_extension.entry(ext.to_lowercase()).or_insert(0) += metadata.len();
+                files.push((entry.path().to_path_buf(), metadata.len()));
+            }
+        }
+        files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        files.truncate(15);
+
+        let info = StorageAnalyzer::new()
+            .with_top_n(15)
+            .analyze(&root)
+            .unwrap();
+
+        assert_eq!(info.total_size, expected.total_size);
+        assert_eq!(info.file_count, expected.file_count);
+        assert_eq!(info.dir_count, expected.dir_count);
+        assert_eq!(info.by_extension, expected.by_extension);
+        assert_eq!(info.largest_files, files);
+    }
+
+    #[test]
+    fn test_symlink_loop_terminates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/data.bin"), vec![0u8; 300]).unwrap();
+        std::os::unix::fs::symlink(&root, root.join("sub/loop")).unwrap();
+
+        let info = StorageAnalyzer::new().analyze(&root).unwrap();
+
+        assert_eq!(info.total_size, 300);
+        assert_eq!(info.file_count, 1);
+        assert_eq!(crate::scanner::fs_util::dir_size(&root), 300);
+    }
+
+    #[test]
+    fn test_extension_filters_change_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("clip.MP4"), vec![0u8; 500]).unwrap();
+        std::fs::write(root.join("main.o"), vec![0u8; 200]).unwrap();
+        std::fs::write(root.join("mod.pyc"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("README"), vec![0u8; 50]).unwrap();
+
+        let all = StorageAnalyzer::new().analyze(&root).unwrap();
+        assert_eq!((all.total_size, all.file_count), (850, 4));
+
+        let excluded = StorageAnalyzer::new()
+            .with_excluded_extensions([".O", "pyc"])
+            .analyze(&root)
+            .unwrap();
+        assert_eq!((excluded.total_size, excluded.file_count), (550, 2));
+        assert_eq!(
+            excluded.by_extension,
+            HashMap::from([("mp4".to_string(), 500)])
+        );
+
+        let only = StorageAnalyzer::new()
+            .with_only_extensions(["mp4"])
+            .analyze(&root)
+            .unwrap();
+        assert_eq!((only.total_size, only.file_count), (500, 1));
+        assert_eq!(only.largest_files, [(root.join("clip.MP4"), 500)]);
+    }
+
+    #[test]
+    fn test_hardlinks_counted_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("original.bin"), vec![0u8; 400]).unwrap();
+        std::fs::hard_link(root.join("original.bin"), root.join("linked.bin")).unwrap();
+
+        let info = StorageAnalyzer::new().analyze(&root).unwrap();
+
+        assert_eq!(info.total_size, 400);
+        assert_eq!(info.file_count, 1);
+    }
+
+    #[test]
+    fn test_progress_reports_each_counted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        std::fs::write(root.join("a.bin"), vec![0u8; 100]).unwrap();
+        std::fs::write(root.join("b.bin"), vec![0u8; 200]).unwrap();
+        std::fs::write(root.join("c.log"), vec![0u8; 300]).unwrap();
+
+        let calls = std::sync::Mutex::new(Vec::new());
+        let info = StorageAnalyzer::new()
+            .with_excluded_extensions(["log"])
+            .analyze_with_progress(&root, |files, bytes| {
+                calls.lock().unwrap().push((files, bytes))
+            })
+            .unwrap();
+
+        let calls = calls.into_inner().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls.iter().map(|c| c.0).max(), Some(info.file_count));
+        assert_eq!(calls.iter().map(|c| c.1).max(), Some(info.total_size));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_same_filesystem_skips_proc() {
+        // /proc is a separate pseudo file system whose kcore reports a huge size
+        if !std::path::Path::new("/proc/self").exists() {
+            return;
+        }
+
+        let info = StorageAnalyzer::new()
+            .with_max_depth(2)
+            .with_top_n(1000)
+            .with_same_filesystem(true)
+            .analyze(&PathBuf::from("/"))
+            .unwrap();
+
+        assert!(
+            info.largest_files
+                .iter()
+                .all(|(path, _)| !path.starts_with("/proc"))
+        );
+    }
+}
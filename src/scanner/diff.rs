@@ -0,0 +1,207 @@
+//! Size comparison between two scan summaries
+
+use super::ScanSummary;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// How a path changed between two scans
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathChangeKind {
+    /// Only present in the later scan
+    Added,
+    /// Only present in the earlier scan
+    Removed,
+    /// Present in both and larger now
+    Grew,
+    /// Present in both and smaller now
+    Shrank,
+}
+
+impl std::fmt::Display for PathChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathChangeKind::Added => write!(f, "added"),
+            PathChangeKind::Removed => write!(f, "removed"),
+            PathChangeKind::Grew => write!(f, "grew"),
+            PathChangeKind::Shrank => write!(f, "shrank"),
+        }
+    }
+}
+
+/// Size change of one category
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryDelta {
+    /// Category name
+    pub category: String,
+    /// Bytes in the earlier scan
+    pub before: u64,
+    /// Bytes in the later scan
+    pub after: u64,
+}
+
+impl CategoryDelta {
+    /// Signed change in bytes
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+/// Size change of one path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathDelta {
+    /// Path of the item
+    pub path: PathBuf,
+    /// Category the item was reported under
+    pub category: String,
+    /// What happened to the path
+    pub change: PathChangeKind,
+    /// Bytes in the earlier scan (0 if added)
+    pub before: u64,
+    /// Bytes in the later scan (0 if removed)
+    pub after: u64,
+}
+
+impl PathDelta {
+    /// Signed change in bytes
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+/// Per-category and per-path differences between two scans
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanDiff {
+    /// Total bytes in the earlier scan
+    pub before_size: u64,
+    /// Total bytes in the later scan
+    pub after_size: u64,
+    /// Every category seen in either scan, largest change first
+    pub categories: Vec<CategoryDelta>,
+    /// Paths that appeared, disappeared or changed size, largest change first
+    pub paths: Vec<PathDelta>,
+}
+
+impl ScanDiff {
+    /// Compare an earlier scan with a later one
+    pub fn between(before: &ScanSummary, after: &ScanSummary) -> Self {
+        let mut categories: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+        let mut paths: BTreeMap<&PathBuf, (&str, Option<u64>, Option<u64>)> = BTreeMap::new();
+
+        for (category, items) in &before.by_category {
+            let entry = categories.entry(category).or_default();
+            for item in items {
+                entry.0 += item.size;
+                paths.entry(&item.path).or_insert((category, None, None)).1 = Some(item.size);
+            }
+        }
+        for (category, items) in &after.by_category {
+            let entry = categories.entry(category).or_default();
+            for item in items {
+                entry.1 += item.size;
+                let path = paths.entry(&item.path).or_insert((category, None, None));
+                path.0 = category;
+                path.2 = Some(item.size);
+            }
+        }
+
+        let mut categories: Vec<CategoryDelta> = categories
+            .into_iter()
+            .map(|(category, (before, after))| CategoryDelta {
+                category: category.to_string(),
+                before,
+                after,
+            })
+            .collect();
+        categories.sort_by_key(|c| Reverse(c.delta().unsigned_abs()));
+
+        let mut paths: Vec<PathDelta> = paths
+            .into_iter()
+            .filter_map(|(path, (category, before, after))| {
+                let change = match (before, after) {
+                    (None, Some(_)) => PathChangeKind::Added,
+                    (Some(_), None) => PathChangeKind::Removed,
+                    (Some(b), Some(a)) if a > b => PathChangeKind::Grew,
+                    (Some(b), Some(a)) if a < b => PathChangeKind::Shrank,
+                    _ => return None,
+                };
+                Some(PathDelta {
+                    path: path.clone(),
+                    category: category.to_string(),
+                    change,
+                    before: before.unwrap_or(0),
+                    after: after.unwrap_or(0),
+                })
+            })
+            .collect();
+        paths.sort_by_key(|p| Reverse(p.delta().unsigned_abs()));
+
+        Self {
+            before_size: categories.iter().map(|c| c.before).sum(),
+            after_size: categories.iter().map(|c| c.after).sum(),
+            categories,
+            paths,
+        }
+    }
+
+    /// Signed change of the total size in bytes
+    pub fn delta(&self) -> i64 {
+        self.after_size as i64 - self.before_size as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Category, CleanItem, RiskLevel};
+
+    fn item(path: &str, size: u64, category: Category) -> CleanItem {
+        CleanItem::new(PathBuf::from(path), size, "test", RiskLevel::Low, category)
+    }
+
+    #[test]
+    fn test_diff_labels_added_removed_and_resized_paths() {
+        let before = ScanSummary::from_items(vec![
+            item("/a/node_modules", 100, Category::NodeJs),
+            item("/b/node_modules", 50, Category::NodeJs),
+            item("/c/target", 400, Category::Rust),
+            item("/d/target", 10, Category::Rust),
+        ]);
+        let after = ScanSummary::from_items(vec![
+            item("/a/node_modules", 300, Category::NodeJs),
+            item("/c/target", 100, Category::Rust),
+            item("/d/target", 10, Category::Rust),
+            item("/e/.venv", 20, Category::Python),
+        ]);
+
+        let diff = ScanDiff::between(&before, &after);
+        assert_eq!(diff.delta(), 430 - 560);
+
+        let changes: Vec<_> = diff
+            .paths
+            .iter()
+            .map(|p| (p.path.to_str().unwrap(), p.change, p.delta()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("/c/target", PathChangeKind::Shrank, -300),
+                ("/a/node_modules", PathChangeKind::Grew, 200),
+                ("/b/node_modules", PathChangeKind::Removed, -50),
+                ("/e/.venv", PathChangeKind::Added, 20),
+            ]
+        );
+
+        let categories: Vec<_> = diff
+            .categories
+            .iter()
+            .map(|c| (c.category.as_str(), c.delta()))
+            .collect();
+        assert_eq!(
+            categories,
+            vec![("Rust", -300), ("Node.js", 150), ("Python", 20)]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Free space on the volume being cleaned, to put reclaimed sizes in context
+
+use std::path::Path;
+
+/// Capacity and free space of one volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    /// Size of the volume in bytes
+    pub total: u64,
+    /// Bytes available to the current user
+    pub available: u64,
+}
+
+impl DiskSpace {
+    /// Space on the volume holding `path`, or `None` if it cannot be determined
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        volume_for(
+            &path,
+            disks.list().iter().map(|disk| {
+                (
+                    disk.mount_point(),
+                    disk.total_space(),
+                    disk.available_space(),
+                )
+            }),
+        )
+    }
+
+    /// Space on the volume holding the home directory
+    pub fn home() -> Option<Self> {
+        Self::for_path(&crate::paths::home_dir()?)
+    }
+
+    /// Percentage of the volume in use
+    pub fn used_percent(&self) -> u8 {
+        if self.total == 0 {
+            return 0;
+        }
+        let used = self.total.saturating_sub(self.available);
+        (used as f64 / self.total as f64 * 100.0).round() as u8
+    }
+}
+
+impl std::fmt::Display for DiskSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} free of {} ({}% used)",
+            bytesize::ByteSize::b(self.available),
+            bytesize::ByteSize::b(self.total),
+            self.used_percent()
+        )
+    }
+}
+
+/// The volume with the longest mount point containing `path`
+fn volume_for<'a>(
+    path: &Path,
+    volumes: impl IntoIterator<Item = (&'a Path, u64, u64)>,
+) -> Option<DiskSpace> {
+    volumes
+        .into_iter()
+        .filter(|(mount, total, _)| *total > 0 && path.starts_with(mount))
+        .max_by_key(|(mount, _, _)| mount.components().count())
+        .map(|(_, total, available)| DiskSpace { total, available })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+
+    #[test]
+    fn test_volume_for_picks_innermost_mount() {
+        let _env = env_read();
+        let volumes = [
+            (Path::new("/"), 500_000_000_000, 100_000_000_000),
+            (Path::new("/home"), 1_000_000_000_000, 250_000_000_000),
+            (Path::new("/home/backup"), 0, 0),
+        ];
+        let space = volume_for(Path::new("/home/me/projects"), volumes).unwrap();
+        assert_eq!(space.available, 250_000_000_000);
+        assert_eq!(space.used_percent(), 75);
+        assert_eq!(
+            volume_for(Path::new("/tmp"), volumes).map(|s| s.total),
+            Some(500_000_000_000)
+        );
+        assert_eq!(volume_for(Path::new("/tmp"), []), None);
+        assert!(space.to_string().ends_with("(75% used)"));
+    }
+}
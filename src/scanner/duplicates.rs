@@ -0,0 +1,107 @@
+//! Duplicate file detection for `analyze --duplicates`
+//!
+//! [`StorageAnalyzer`](super::StorageAnalyzer) collects files by size during
+//! its walk; only sizes shared by several files are hashed with BLAKE3, which
+//! is fast enough to read large files at disk speed and makes a collision
+//! between different contents practically impossible, so files with equal
+//! hashes are reported as duplicates without comparing them byte for byte.
+
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Files with identical contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Size of each file
+    pub size: u64,
+    /// Paths of the identical files, sorted
+    pub paths: Vec<PathBuf>,
+}
+
+impl DuplicateGroup {
+    /// Bytes freed by keeping one copy and removing the rest
+    pub fn reclaimable(&self) -> u64 {
+        self.size * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Group files of equal size by content, largest reclaimable groups first
+///
+/// `by_size` maps a file size to the files of that size; unreadable files
+/// are left out.
+pub fn find_duplicates(by_size: HashMap<u64, Vec<PathBuf>>) -> Vec<DuplicateGroup> {
+    let mut groups: Vec<DuplicateGroup> = by_size
+        .into_par_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map_iter(|(size, paths)| {
+            let mut by_hash: HashMap<blake3::Hash, Vec<PathBuf>> = HashMap::new();
+            for path in paths {
+                if let Ok(hash) = hash_file(&path) {
+                    by_hash.entry(hash).or_default().push(path);
+                }
+            }
+            by_hash
+                .into_values()
+                .filter(|paths| paths.len() > 1)
+                .map(move |mut paths| {
+                    paths.sort();
+                    DuplicateGroup { size, paths }
+                })
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.reclaimable()
+            .cmp(&a.reclaimable())
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+    groups
+}
+
+/// Bytes reclaimable across all groups
+pub fn total_reclaimable(groups: &[DuplicateGroup]) -> u64 {
+    groups.iter().map(DuplicateGroup::reclaimable).sum()
+}
+
+/// BLAKE3 hash of a file's contents
+fn hash_file(path: &Path) -> std::io::Result<blake3::Hash> {
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicates_grouped_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, contents: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        };
+        let a = write("a.bin", &[1u8; 4096]);
+        let b = write("b.bin", &[1u8; 4096]);
+        let c = write("c.bin", &[2u8; 4096]);
+        let d = write("d.bin", &[1u8; 4096]);
+
+        let groups = find_duplicates(HashMap::from([(
+            4096,
+            vec![d.clone(), c, a.clone(), b.clone()],
+        )]));
+
+        assert_eq!(
+            groups,
+            [DuplicateGroup {
+                size: 4096,
+                paths: vec![a, b, d],
+            }]
+        );
+        assert_eq!(total_reclaimable(&groups), 8192);
+    }
+}
@@ -1,70 +1,135 @@
 //! Parallel file scanner using rayon
 
-use crate::rules::{CleanItem, CleanRule};
-use indicatif::{ProgressBar, ProgressStyle};
-use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use super::progress::{NoProgress, ScanProgress};
+use crate::rules::{CleanItem, CleanRule, RiskLevel, ScanContext};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long one rule took to scan and what it found
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleStat {
+    /// Rule name
+    pub rule: String,
+    /// Time spent in the rule's scan
+    #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+    /// Number of items found
+    pub items: usize,
+    /// Total size of the items found
+    pub bytes: u64,
+}
+
+fn serialize_millis<S: Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(elapsed.as_millis())
+}
 
 /// File scanner for scanning cleanable items
 pub struct FileScanner {
     /// Rules to use for scanning
     rules: Vec<Box<dyn CleanRule>>,
+    /// Context passed to every rule's scan
+    ctx: ScanContext,
+    /// Observer notified of scan progress
+    progress: Box<dyn ScanProgress>,
 }
 
 impl FileScanner {
     /// Create a new file scanner with the given rules
     pub fn new(rules: Vec<Box<dyn CleanRule>>) -> Self {
-        Self { rules }
+        Self {
+            rules,
+            ctx: ScanContext::default(),
+            progress: Box::new(NoProgress),
+        }
     }
 
-    /// Scan all rules and return cleanable items
-    pub fn scan(&self) -> anyhow::Result<Vec<CleanItem>> {
-        let items: Arc<Mutex<Vec<CleanItem>>> = Arc::new(Mutex::new(Vec::new()));
+    /// The rules, e.g. to hand to a [`crate::cleaner::Cleaner`] after scanning
+    pub fn into_rules(self) -> Vec<Box<dyn CleanRule>> {
+        self.rules
+    }
 
-        let pb = ProgressBar::new(self.rules.len() as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template(
-                    "{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}",
-                )
-                .unwrap_or_else(|_| ProgressStyle::default_bar()),
-        );
+    /// Set the context (configuration) passed to rules
+    pub fn with_context(mut self, ctx: ScanContext) -> Self {
+        self.ctx = ctx;
+        self
+    }
 
-        // Scan rules in parallel
-        self.rules.par_iter().for_each(|rule| {
-            if rule.is_applicable() {
-                pb.set_message(format!("Scanning: {}", rule.name()));
-                match rule.scan() {
-                    Ok(found_items) => {
-                        let mut items_guard = items.lock().unwrap();
-                        items_guard.extend(found_items);
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to scan {}: {}", rule.name(), e);
-                    }
-                }
-            }
-            pb.inc(1);
-        });
+    /// Set the observer notified as each rule starts and finishes
+    pub fn with_progress(mut self, progress: impl ScanProgress + 'static) -> Self {
+        self.progress = Box::new(progress);
+        self
+    }
 
-        pb.finish_with_message("Scan complete");
+    /// Paths the last scan could not read because permission was denied
+    pub fn skipped_paths(&self) -> Vec<std::path::PathBuf> {
+        self.ctx.skipped_paths()
+    }
 
-        let result = Arc::try_unwrap(items)
-            .map_err(|_| anyhow::anyhow!("Failed to unwrap Arc"))?
-            .into_inner()
-            .map_err(|e| anyhow::anyhow!("Mutex poisoned: {}", e))?;
+    /// Scan all rules in parallel and return cleanable items
+    ///
+    /// Progress is reported to the observer set with [`Self::with_progress`];
+    /// nothing is written to the terminal by the scanner itself.
+    pub fn scan(&self) -> crate::Result<Vec<CleanItem>> {
+        self.scan_with_stats().map(|(items, _)| items)
+    }
+
+    /// [`Self::scan`], also returning how long each applicable rule took,
+    /// slowest first
+    pub fn scan_with_stats(&self) -> crate::Result<(Vec<CleanItem>, Vec<RuleStat>)> {
+        self.scan_parallel(self.progress.as_ref(), &AtomicBool::new(false), |_, _| {})
+    }
+
+    /// Scan all rules in parallel until `cancel` is set
+    ///
+    /// Once it is set no further rules are started; rules already running
+    /// finish, and the items found so far are returned.
+    pub fn scan_with_cancel(&self, cancel: &AtomicBool) -> crate::Result<Vec<CleanItem>> {
+        self.scan_parallel(self.progress.as_ref(), cancel, |_, _| {})
+            .map(|(items, _)| items)
+    }
+
+    /// Scan rules in parallel, calling `on_rule` with each rule's name and
+    /// items as soon as that rule finishes
+    ///
+    /// Calls to `on_rule` are serialized, so it may freely mutate captured state.
+    pub fn scan_streaming<F>(&self, on_rule: F) -> crate::Result<Vec<CleanItem>>
+    where
+        F: FnMut(&str, &[CleanItem]) + Send,
+    {
+        self.scan_parallel(self.progress.as_ref(), &AtomicBool::new(false), on_rule)
+            .map(|(items, _)| items)
+    }
 
-        Ok(result)
+    /// [`Self::scan_streaming`] that stops starting rules once `cancel` is set,
+    /// like [`Self::scan_with_cancel`]
+    pub fn scan_streaming_with_cancel<F>(
+        &self,
+        cancel: &AtomicBool,
+        on_rule: F,
+    ) -> crate::Result<Vec<CleanItem>>
+    where
+        F: FnMut(&str, &[CleanItem]) + Send,
+    {
+        self.scan_parallel(self.progress.as_ref(), cancel, on_rule)
+            .map(|(items, _)| items)
     }
 
-    /// Scan rules without progress bar (for non-interactive use)
-    pub fn scan_quiet(&self) -> anyhow::Result<Vec<CleanItem>> {
+    /// Scan rules sequentially, ignoring the progress observer
+    pub fn scan_quiet(&self) -> crate::Result<Vec<CleanItem>> {
         let mut all_items = Vec::new();
+        self.ctx.clear_skipped();
+        self.ctx.clear_applicability();
 
         for rule in &self.rules {
-            if rule.is_applicable() {
-                match rule.scan() {
-                    Ok(items) => all_items.extend(items),
+            if self.ctx.is_applicable(rule.as_ref()) {
+                match rule.scan(&self.ctx) {
+                    Ok(items) => all_items.extend(self.ctx.filter_items(items)),
                     Err(e) => {
                         tracing::warn!("Failed to scan {}: {}", rule.name(), e);
                     }
@@ -75,14 +140,72 @@ impl FileScanner {
         Ok(all_items)
     }
 
-    /// Scan rules in parallel without progress bar
-    pub fn scan_parallel_quiet(&self) -> anyhow::Result<Vec<CleanItem>> {
-        let items: Arc<Mutex<Vec<CleanItem>>> = Arc::new(Mutex::new(Vec::new()));
+    /// Scan rules in parallel, ignoring the progress observer
+    pub fn scan_parallel_quiet(&self) -> crate::Result<Vec<CleanItem>> {
+        self.scan_parallel(&NoProgress, &AtomicBool::new(false), |_, _| {})
+            .map(|(items, _)| items)
+    }
+
+    /// Rules ordered most expensive first, otherwise in registration order
+    fn scheduled_rules(&self) -> Vec<&dyn CleanRule> {
+        let mut rules: Vec<&dyn CleanRule> = self.rules.iter().map(|r| r.as_ref()).collect();
+        rules.sort_by_key(|rule| Reverse(rule.scan_cost()));
+        rules
+    }
 
-        self.rules.par_iter().for_each(|rule| {
-            if rule.is_applicable() {
-                match rule.scan() {
+    fn scan_parallel<F>(
+        &self,
+        progress: &dyn ScanProgress,
+        cancel: &AtomicBool,
+        on_rule: F,
+    ) -> crate::Result<(Vec<CleanItem>, Vec<RuleStat>)>
+    where
+        F: FnMut(&str, &[CleanItem]) + Send,
+    {
+        let items: Mutex<Vec<CleanItem>> = Mutex::new(Vec::new());
+        let stats: Mutex<Vec<RuleStat>> = Mutex::new(Vec::new());
+        let on_rule = Mutex::new(on_rule);
+        let completed = AtomicUsize::new(0);
+        let total = self.rules.len();
+
+        self.ctx.clear_skipped();
+        self.ctx.clear_applicability();
+        progress.on_scan_start(total);
+
+        let scan_rule = |rule: &dyn CleanRule| {
+            // Rules are queued up front, so cancelling skips those not yet started
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            if self.ctx.is_applicable(rule) {
+                progress.on_rule_start(rule.name());
+                let started = Instant::now();
+                let scanned = rule
+                    .scan(&self.ctx)
+                    .map(|items| self.ctx.filter_items(items));
+                let stat = RuleStat {
+                    rule: rule.name().to_string(),
+                    elapsed: started.elapsed(),
+                    items: scanned.as_ref().map_or(0, |items| items.len()),
+                    bytes: scanned
+                        .as_ref()
+                        .map_or(0, |items| items.iter().map(|i| i.size).sum()),
+                };
+                tracing::debug!(
+                    rule = %stat.rule,
+                    elapsed_ms = stat.elapsed.as_millis() as u64,
+                    items = stat.items,
+                    bytes = stat.bytes,
+                    "Rule scanned"
+                );
+                if let Ok(mut stats) = stats.lock() {
+                    stats.push(stat);
+                }
+                match scanned {
                     Ok(found_items) => {
+                        if let Ok(mut callback) = on_rule.lock() {
+                            callback(rule.name(), &found_items);
+                        }
                         let mut items_guard = items.lock().unwrap();
                         items_guard.extend(found_items);
                     }
@@ -91,48 +214,506 @@ impl FileScanner {
                     }
                 }
             }
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            progress.on_rule_finish(done, total, rule.name());
+        };
+
+        // FIFO spawning starts rules in scheduled order on every free thread
+        rayon::scope_fifo(|scope| {
+            for rule in self.scheduled_rules() {
+                let scan_rule = &scan_rule;
+                scope.spawn_fifo(move |_| scan_rule(rule));
+            }
         });
 
-        let result = Arc::try_unwrap(items)
-            .map_err(|_| anyhow::anyhow!("Failed to unwrap Arc"))?
-            .into_inner()
-            .map_err(|e| anyhow::anyhow!("Mutex poisoned: {}", e))?;
+        progress.on_scan_finish();
 
-        Ok(result)
+        let items = items
+            .into_inner()
+            .map_err(|e| crate::Error::Other(format!("Mutex poisoned: {}", e)))?;
+        let mut stats = stats
+            .into_inner()
+            .map_err(|e| crate::Error::Other(format!("Mutex poisoned: {}", e)))?;
+        stats.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.rule.cmp(&b.rule)));
+        Ok((items, stats))
     }
 }
 
+/// Order of the items within each category of a [`ScanSummary`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ItemSort {
+    /// Largest first
+    #[default]
+    Size,
+    /// By path
+    Name,
+    /// Least recently modified first; items without a modification time last
+    Age,
+    /// Highest risk first, then largest
+    Risk,
+}
+
+/// Order of the categories of a [`ScanSummary`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum CategorySort {
+    /// Largest total first
+    #[default]
+    Size,
+    /// By name
+    Name,
+}
+
 /// Summary of scan results
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ScanSummary {
+    /// When the scan ran, recorded when the summary is saved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanned_at: Option<DateTime<Utc>>,
     /// Total number of items found
     pub total_items: usize,
     /// Total size in bytes
     pub total_size: u64,
-    /// Items grouped by category
-    pub by_category: std::collections::HashMap<String, Vec<CleanItem>>,
+    /// Items grouped by category, largest category first and largest item
+    /// first within each category
+    pub by_category: Vec<(String, Vec<CleanItem>)>,
+    /// Item count and size per risk level; levels without items are absent
+    #[serde(default)]
+    pub by_risk: BTreeMap<RiskLevel, RiskTotal>,
+}
+
+/// Number and total size of the items of one risk level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskTotal {
+    /// Number of items
+    pub count: usize,
+    /// Total size in bytes
+    pub bytes: u64,
 }
 
 impl ScanSummary {
     /// Create a summary from a list of items
+    ///
+    /// The ordering is deterministic: categories by total size descending,
+    /// then by name; items by size descending, then by path.
     pub fn from_items(items: Vec<CleanItem>) -> Self {
-        use std::collections::HashMap;
-
-        let mut by_category: HashMap<String, Vec<CleanItem>> = HashMap::new();
+        let mut grouped: BTreeMap<String, Vec<CleanItem>> = BTreeMap::new();
+        let mut by_risk: BTreeMap<RiskLevel, RiskTotal> = BTreeMap::new();
         let mut total_size = 0u64;
 
         for item in items {
             total_size += item.size;
+            let risk = by_risk.entry(item.risk_level).or_default();
+            risk.count += 1;
+            risk.bytes += item.size;
             let category_name = item.category.to_string();
-            by_category.entry(category_name).or_default().push(item);
+            grouped.entry(category_name).or_default().push(item);
+        }
+
+        let mut by_category: Vec<(String, Vec<CleanItem>)> = grouped.into_iter().collect();
+        for (_, items) in &mut by_category {
+            items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
         }
+        // Stable sort keeps the BTreeMap's name order for equal sizes
+        by_category.sort_by_key(|(_, items)| Reverse(items.iter().map(|i| i.size).sum::<u64>()));
 
-        let total_items = by_category.values().map(|v| v.len()).sum();
+        let total_items = by_category.iter().map(|(_, v)| v.len()).sum();
 
         Self {
+            scanned_at: None,
             total_items,
             total_size,
             by_category,
+            by_risk,
+        }
+    }
+
+    /// Reorder the categories and the items within each
+    ///
+    /// Ties keep falling back to size (largest first) and then path, so the
+    /// order stays deterministic. Sorting by age reads modification times
+    /// that the rules did not record.
+    pub fn sort(&mut self, items: ItemSort, categories: CategorySort) {
+        for (_, category_items) in &mut self.by_category {
+            if items == ItemSort::Age {
+                *category_items = std::mem::take(category_items)
+                    .into_iter()
+                    .map(CleanItem::with_path_mtime)
+                    .collect();
+            }
+            category_items.sort_by(|a, b| {
+                let primary = match items {
+                    ItemSort::Size => std::cmp::Ordering::Equal,
+                    ItemSort::Name => a.path.cmp(&b.path),
+                    ItemSort::Age => match (a.last_modified, b.last_modified) {
+                        (Some(a), Some(b)) => a.cmp(&b),
+                        (a, b) => a.is_none().cmp(&b.is_none()),
+                    },
+                    ItemSort::Risk => b.risk_level.cmp(&a.risk_level),
+                };
+                primary
+                    .then_with(|| b.size.cmp(&a.size))
+                    .then_with(|| a.path.cmp(&b.path))
+            });
         }
+
+        match categories {
+            CategorySort::Size => self.by_category.sort_by(|(a_name, a), (b_name, b)| {
+                let size = |items: &[CleanItem]| items.iter().map(|i| i.size).sum::<u64>();
+                size(b).cmp(&size(a)).then_with(|| a_name.cmp(b_name))
+            }),
+            CategorySort::Name => self
+                .by_category
+                .sort_by_key(|(name, _)| name.to_lowercase()),
+        }
+    }
+
+    /// Save the summary as JSON, stamping it with the current time
+    pub fn save(&self, path: &Path) -> crate::Result<()> {
+        let summary = Self {
+            scanned_at: Some(Utc::now()),
+            ..self.clone()
+        };
+        let content = serde_json::to_string_pretty(&summary)
+            .map_err(|e| crate::Error::Other(e.to_string()))?;
+        std::fs::write(path, content).map_err(|e| crate::Error::filesystem(path, e))
+    }
+
+    /// Load a summary previously written by [`ScanSummary::save`]
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| crate::Error::filesystem(path, e))?;
+        serde_json::from_str(&content).map_err(|e| {
+            crate::Error::Other(format!("{} is not a saved scan: {}", path.display(), e))
+        })
+    }
+
+    /// Items of the named category
+    pub fn category(&self, name: &str) -> Option<&[CleanItem]> {
+        self.by_category
+            .iter()
+            .find(|(category, _)| category == name)
+            .map(|(_, items)| items.as_slice())
+    }
+
+    /// Serialize the categories as a JSON object, keeping the summary's order
+    pub fn by_category_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&OrderedCategories(&self.by_category))
+    }
+
+    /// Serialize the totals, the risk breakdown and the categories (as by
+    /// [`Self::by_category_json`]) as one JSON object, for `scan --format json`
+    pub fn report_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&JsonReport {
+            total_items: self.total_items,
+            total_size: self.total_size,
+            by_risk: &self.by_risk,
+            categories: OrderedCategories(&self.by_category),
+        })
+    }
+}
+
+/// Serialize an item as a single line of JSON, for newline-delimited output
+///
+/// The category and risk are written by name, e.g. `"Rust"` and `"Low"`.
+pub fn item_json_line(item: &CleanItem) -> serde_json::Result<String> {
+    serde_json::to_string(&JsonLine {
+        path: &item.path,
+        size: item.size,
+        category: item.category.to_string(),
+        risk: item.risk_level,
+        description: &item.description,
+    })
+}
+
+/// Layout written by [`ScanSummary::report_json`]
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    total_items: usize,
+    total_size: u64,
+    by_risk: &'a BTreeMap<RiskLevel, RiskTotal>,
+    categories: OrderedCategories<'a>,
+}
+
+/// Fields of an item written by [`item_json_line`]
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    path: &'a std::path::Path,
+    size: u64,
+    category: String,
+    risk: RiskLevel,
+    description: &'a str,
+}
+
+/// Serializes category pairs as a map without re-sorting the keys
+struct OrderedCategories<'a>(&'a [(String, Vec<CleanItem>)]);
+
+impl Serialize for OrderedCategories<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, items)| (name, items)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CustomRuleConfig;
+    use crate::rules::{Category, CustomRule};
+    use crate::test_support::{env_read, log_rule};
+    use std::path::PathBuf;
+
+    fn item(path: &str, size: u64, category: Category) -> CleanItem {
+        CleanItem::new(PathBuf::from(path), size, "test", RiskLevel::Low, category)
+    }
+
+    #[test]
+    fn test_summary_sorts_items_by_size() {
+        let summary = ScanSummary::from_items(vec![
+            item("/small", 10, Category::System),
+            item("/large", 300, Category::System),
+            item("/medium", 200, Category::System),
+        ]);
+
+        let sizes: Vec<u64> = summary
+            .category("System")
+            .unwrap()
+            .iter()
+            .map(|i| i.size)
+            .collect();
+        assert_eq!(sizes, [300, 200, 10]);
+    }
+
+    #[test]
+    fn test_summary_sort_options() {
+        let mut old = item("/b/old", 10, Category::Rust);
+        old.last_modified = Some(1_000);
+        let mut risky = item("/a/risky", 20, Category::Rust);
+        risky.risk_level = RiskLevel::High;
+        risky.last_modified = Some(2_000);
+        let mut summary = ScanSummary::from_items(vec![
+            old,
+            risky,
+            item("/c/large", 300, Category::Rust),
+            item("/z/docker", 100, Category::Docker),
+            item("/y/system", 50, Category::System),
+        ]);
+        let order = |summary: &ScanSummary| -> (Vec<String>, Vec<u64>) {
+            (
+                summary.by_category.iter().map(|(c, _)| c.clone()).collect(),
+                summary
+                    .category("Rust")
+                    .unwrap()
+                    .iter()
+                    .map(|i| i.size)
+                    .collect(),
+            )
+        };
+
+        // The defaults are largest first for categories and items alike
+        summary.sort(ItemSort::default(), CategorySort::default());
+        let (categories, sizes) = order(&summary);
+        assert_eq!(categories, ["Rust", "Docker", "System"]);
+        assert_eq!(sizes, [300, 20, 10]);
+
+        summary.sort(ItemSort::Name, CategorySort::Name);
+        assert_eq!(
+            order(&summary),
+            (
+                vec!["Docker".into(), "Rust".into(), "System".into()],
+                vec![20, 10, 300]
+            )
+        );
+
+        summary.sort(ItemSort::Age, CategorySort::Size);
+        assert_eq!(order(&summary).1, [10, 20, 300]);
+
+        summary.sort(ItemSort::Risk, CategorySort::Size);
+        assert_eq!(order(&summary).1, [20, 300, 10]);
+    }
+
+    #[test]
+    fn test_summary_totals_by_risk() {
+        let mut risky = item("/docs", 500, Category::System);
+        risky.risk_level = RiskLevel::High;
+        let summary = ScanSummary::from_items(vec![
+            item("/cache/a", 100, Category::System),
+            item("/cache/b", 50, Category::Rust),
+            risky,
+        ]);
+
+        assert_eq!(
+            summary.by_risk,
+            BTreeMap::from([
+                (
+                    RiskLevel::Low,
+                    RiskTotal {
+                        count: 2,
+                        bytes: 150
+                    }
+                ),
+                (
+                    RiskLevel::High,
+                    RiskTotal {
+                        count: 1,
+                        bytes: 500
+                    }
+                ),
+            ])
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&summary.report_json().unwrap()).unwrap();
+        assert_eq!(json["by_risk"]["Low"]["bytes"], 150);
+        assert_eq!(json["by_risk"]["High"]["count"], 1);
+        assert_eq!(json["categories"]["Rust"][0]["path"], "/cache/b");
+    }
+
+    #[test]
+    fn test_summary_ordering_is_deterministic() {
+        let items = vec![
+            item("/go", 50, Category::Go),
+            item("/rust/b", 100, Category::Rust),
+            item("/docker", 50, Category::Docker),
+            item("/rust/a", 100, Category::Rust),
+            item("/system", 500, Category::System),
+        ];
+        let mut reversed = items.clone();
+        reversed.reverse();
+
+        let first = ScanSummary::from_items(items);
+        let second = ScanSummary::from_items(reversed);
+
+        let order = |summary: &ScanSummary| -> Vec<String> {
+            summary
+                .by_category
+                .iter()
+                .flat_map(|(name, items)| {
+                    std::iter::once(name.clone())
+                        .chain(items.iter().map(|i| i.path.display().to_string()))
+                })
+                .collect()
+        };
+        assert_eq!(
+            order(&first),
+            [
+                "System", "/system", "Rust", "/rust/a", "/rust/b", "Docker", "/docker", "Go", "/go"
+            ]
+        );
+        assert_eq!(order(&first), order(&second));
+        assert_eq!(
+            first.by_category_json().unwrap(),
+            second.by_category_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_item_json_line() {
+        let item = item("/tmp/cache", 42, Category::Bazel);
+        let line = item_json_line(&item).unwrap();
+        assert!(!line.contains('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "path": "/tmp/cache",
+                "size": 42,
+                "category": "Bazel",
+                "risk": "Low",
+                "description": "test",
+            })
+        );
+    }
+
+    #[test]
+    fn test_expensive_rules_scheduled_first() {
+        let _env = env_read();
+        let custom = |name: &str| -> Box<dyn CleanRule> {
+            Box::new(CustomRule::new(CustomRuleConfig {
+                id: None,
+                name: name.to_string(),
+                ..Default::default()
+            }))
+        };
+        let scanner = FileScanner::new(vec![
+            custom("first"),
+            Box::new(crate::rules::HeuristicRule::default()),
+            custom("second"),
+        ]);
+
+        let order: Vec<&str> = scanner.scheduled_rules().iter().map(|r| r.name()).collect();
+        assert_eq!(order, ["Heuristic Detection", "first", "second"]);
+    }
+
+    #[test]
+    fn test_cancelled_scan_starts_no_rules() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.log"), b"log").unwrap();
+        let rules: Vec<Box<dyn CleanRule>> = (0..3)
+            .map(|i| log_rule(&format!("rule {}", i), dir.path()))
+            .collect();
+        let scanner = FileScanner::new(rules);
+
+        assert_eq!(
+            scanner
+                .scan_with_cancel(&AtomicBool::new(false))
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(
+            scanner
+                .scan_with_cancel(&AtomicBool::new(true))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_scan_with_stats_records_each_applicable_rule() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.log"), b"log").unwrap();
+        let scanner = FileScanner::new(vec![
+            log_rule("logs", dir.path()),
+            log_rule("missing", &dir.path().join("missing")),
+        ]);
+
+        let (items, stats) = scanner.scan_with_stats().unwrap();
+
+        assert_eq!(items.len(), 1);
+        let logs = stats.iter().find(|s| s.rule == "logs").unwrap();
+        assert_eq!((logs.items, logs.bytes), (1, 3));
+        assert!(stats.windows(2).all(|w| w[0].elapsed >= w[1].elapsed));
+    }
+
+    #[test]
+    fn test_scan_reports_progress_per_rule() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.log"), b"log").unwrap();
+        let rules: Vec<Box<dyn CleanRule>> = (0..4)
+            .map(|i| log_rule(&format!("rule {}", i), dir.path()))
+            .collect();
+        let rule_count = rules.len();
+        let calls = std::sync::Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = calls.clone();
+        let scanner = FileScanner::new(rules).with_progress(
+            move |completed: usize, total: usize, name: &str| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((completed, total, name.to_string()));
+            },
+        );
+        assert_eq!(scanner.scan().unwrap().len(), rule_count);
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), rule_count);
+        assert!(calls.iter().all(|(_, total, _)| *total == rule_count));
+        let mut completed: Vec<usize> = calls.iter().map(|(done, _, _)| *done).collect();
+        completed.sort_unstable();
+        assert_eq!(completed, (1..=rule_count).collect::<Vec<_>>());
     }
 }
@@ -0,0 +1,103 @@
+//! File system helpers shared by rules and analyzers
+
+use rayon::prelude::*;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Calculate the total size of regular files under `path`
+///
+/// The walk is fanned out over rayon so metadata calls run in parallel.
+/// Symlinks are never followed, which avoids cycles and counting a linked
+/// tree twice.
+pub fn dir_size(path: &Path) -> u64 {
+    dir_size_reporting(path, |_| {})
+}
+
+/// Like [`dir_size`], calling `on_denied` for every path that could not be
+/// read because of missing permissions
+pub fn dir_size_reporting(path: &Path, on_denied: impl Fn(&Path) + Sync) -> u64 {
+    WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .par_bridge()
+        .filter_map(|e| match e {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                if is_permission_denied(&err) {
+                    on_denied(err.path().unwrap_or(path));
+                }
+                None
+            }
+        })
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Whether a walk error was caused by missing permissions
+pub fn is_permission_denied(err: &walkdir::Error) -> bool {
+    err.io_error()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dir_size_known_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("a/b")).unwrap();
+        std::fs::write(root.join("top.bin"), vec![0u8; 1000]).unwrap();
+        std::fs::write(root.join("a/mid.bin"), vec![0u8; 200]).unwrap();
+        std::fs::write(root.join("a/b/deep.bin"), vec![0u8; 34]).unwrap();
+
+        assert_eq!(dir_size(root), 1234);
+        assert_eq!(dir_size(&root.join("a")), 234);
+    }
+
+    #[test]
+    fn test_dir_size_missing_path() {
+        assert_eq!(dir_size(Path::new("/nonexistent/cleanmymac-rs")), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_ignores_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("real")).unwrap();
+        std::fs::write(root.join("real/data.bin"), vec![0u8; 500]).unwrap();
+        std::os::unix::fs::symlink(root.join("real"), root.join("linked")).unwrap();
+        std::os::unix::fs::symlink(root.join("real/data.bin"), root.join("file-link")).unwrap();
+
+        assert_eq!(dir_size(root), 500);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_size_reports_denied_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::write(locked.join("secret.bin"), vec![0u8; 10]).unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+
+        // Root bypasses permission checks, so nothing is denied there
+        if std::fs::read_dir(&locked).is_ok() {
+            std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+
+        let denied = std::sync::Mutex::new(Vec::new());
+        let size = dir_size_reporting(dir.path(), |p| denied.lock().unwrap().push(p.to_path_buf()));
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(size, 0);
+        assert_eq!(denied.into_inner().unwrap(), [locked]);
+    }
+}
@@ -1,9 +1,20 @@
 //! Scanner module for file system scanning and analysis
 
 mod analyzer;
+mod diff;
+mod disk;
+mod duplicates;
 mod file_scanner;
+pub mod fs_util;
+mod progress;
 pub mod treemap;
+mod watcher;
 
 pub use analyzer::*;
+pub use diff::*;
+pub use disk::*;
+pub use duplicates::*;
 pub use file_scanner::*;
+pub use progress::*;
 pub use treemap::*;
+pub use watcher::*;
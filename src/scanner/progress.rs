@@ -0,0 +1,88 @@
+//! Scan progress reporting
+//!
+//! `FileScanner` never writes to the terminal itself; it reports progress to a
+//! [`ScanProgress`] observer. The CLI uses [`ProgressBarReporter`], while GUIs
+//! and other embedders can supply their own implementation or a closure.
+
+use crate::ui::glyphs::glyphs;
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Observer notified as a scan runs
+///
+/// Rules are scanned in parallel, so callbacks may arrive from several threads
+/// and rule start/finish notifications for different rules may interleave.
+pub trait ScanProgress: Send + Sync {
+    /// Called once before any rule is scanned
+    fn on_scan_start(&self, _total_rules: usize) {}
+
+    /// Called when a rule starts scanning
+    fn on_rule_start(&self, _rule_name: &str) {}
+
+    /// Called when a rule finishes (or is skipped as not applicable)
+    fn on_rule_finish(&self, _completed: usize, _total_rules: usize, _rule_name: &str) {}
+
+    /// Called once after every rule has finished
+    fn on_scan_finish(&self) {}
+}
+
+/// Closures receive `(completed, total_rules, rule_name)` as each rule finishes
+impl<F> ScanProgress for F
+where
+    F: Fn(usize, usize, &str) + Send + Sync,
+{
+    fn on_rule_finish(&self, completed: usize, total_rules: usize, rule_name: &str) {
+        self(completed, total_rules, rule_name)
+    }
+}
+
+/// Observer that ignores all progress
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoProgress;
+
+impl ScanProgress for NoProgress {}
+
+/// Terminal progress bar backed by indicatif
+pub struct ProgressBarReporter {
+    bar: ProgressBar,
+}
+
+impl ProgressBarReporter {
+    /// Create a progress bar; its length is set when the scan starts
+    pub fn new() -> Self {
+        let bar = ProgressBar::new(0);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}",
+                )
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars(glyphs().progress_chars)
+                .tick_chars(glyphs().tick_chars),
+        );
+        Self { bar }
+    }
+}
+
+impl Default for ProgressBarReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanProgress for ProgressBarReporter {
+    fn on_scan_start(&self, total_rules: usize) {
+        self.bar.set_length(total_rules as u64);
+    }
+
+    fn on_rule_start(&self, rule_name: &str) {
+        self.bar.set_message(format!("Scanning: {}", rule_name));
+    }
+
+    fn on_rule_finish(&self, _completed: usize, _total_rules: usize, _rule_name: &str) {
+        self.bar.inc(1);
+    }
+
+    fn on_scan_finish(&self) {
+        self.bar.finish_with_message("Scan complete");
+    }
+}
@@ -5,12 +5,15 @@
 //! for optimal visual aspect ratios.
 
 use rayon::prelude::*;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
 
 /// A node in the directory tree
-#[derive(Debug, Clone)]
+///
+/// Serializes as a nested JSON hierarchy; its depth is bounded by the
+/// builder's `max_depth`, deeper directories being collapsed into leaves.
+#[derive(Debug, Clone, Serialize)]
 pub struct TreeNode {
     /// Path to this node
     pub path: PathBuf,
@@ -19,6 +22,7 @@ pub struct TreeNode {
     /// Size in bytes
     pub size: u64,
     /// Children nodes (for directories)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<TreeNode>,
     /// Whether this is a directory
     pub is_dir: bool,
@@ -138,8 +142,9 @@ impl TreemapBuilder {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_else(|| path.to_string_lossy().to_string());
 
-        if !path.is_dir() {
-            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+        // Symlinks are leaves: following them could loop or count a tree twice
+        if !path.is_dir() || path.is_symlink() {
+            let size = path.symlink_metadata().map(|m| m.len()).unwrap_or(0);
             return Ok(TreeNode::new(path.to_path_buf(), name, size, false, depth));
         }
 
@@ -154,7 +159,7 @@ impl TreemapBuilder {
                     let child_path = entry.path();
                     if depth < self.max_depth {
                         self.build_tree_recursive(&child_path, depth + 1).ok()
-                    } else if child_path.is_dir() {
+                    } else if child_path.is_dir() && !child_path.is_symlink() {
                         // For deep directories, just calculate total size
                         let size = self.calculate_dir_size(&child_path);
                         let name = child_path
@@ -163,7 +168,7 @@ impl TreemapBuilder {
                             .unwrap_or_default();
                         Some(TreeNode::new(child_path, name, size, true, depth + 1))
                     } else {
-                        let size = child_path.metadata().map(|m| m.len()).unwrap_or(0);
+                        let size = child_path.symlink_metadata().map(|m| m.len()).unwrap_or(0);
                         let name = child_path
                             .file_name()
                             .map(|n| n.to_string_lossy().to_string())
@@ -180,7 +185,7 @@ impl TreemapBuilder {
                     let child_path = entry.path();
                     if depth < self.max_depth {
                         self.build_tree_recursive(&child_path, depth + 1).ok()
-                    } else if child_path.is_dir() {
+                    } else if child_path.is_dir() && !child_path.is_symlink() {
                         let size = self.calculate_dir_size(&child_path);
                         let name = child_path
                             .file_name()
@@ -188,7 +193,7 @@ impl TreemapBuilder {
                             .unwrap_or_default();
                         Some(TreeNode::new(child_path, name, size, true, depth + 1))
                     } else {
-                        let size = child_path.metadata().map(|m| m.len()).unwrap_or(0);
+                        let size = child_path.symlink_metadata().map(|m| m.len()).unwrap_or(0);
                         let name = child_path
                             .file_name()
                             .map(|n| n.to_string_lossy().to_string())
@@ -213,14 +218,7 @@ impl TreemapBuilder {
 
     /// Calculate directory size using parallel walk
     fn calculate_dir_size(&self, path: &Path) -> u64 {
-        WalkDir::new(path)
-            .into_iter()
-            .par_bridge()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .filter_map(|e| e.metadata().ok())
-            .map(|m| m.len())
-            .sum()
+        super::fs_util::dir_size(path)
     }
 }
 
@@ -476,6 +474,28 @@ mod tests {
         assert_eq!(rect.shorter_side(), 50.0);
     }
 
+    #[test]
+    fn test_tree_json_respects_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let deep = dir.path().join("a/b/c/d");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(deep.join("file.bin"), vec![0u8; 64]).unwrap();
+
+        let tree = TreemapBuilder::new()
+            .max_depth(2)
+            .min_size(0)
+            .build_tree(dir.path())
+            .unwrap();
+        let json = serde_json::to_value(&tree).unwrap();
+
+        // Directories below max_depth are collapsed into sized leaves
+        let c = &json["children"][0]["children"][0]["children"][0];
+        assert_eq!(c["name"], "c");
+        assert_eq!(c["depth"], 3);
+        assert_eq!(c["size"], 64);
+        assert!(c.get("children").is_none());
+    }
+
     #[test]
     fn test_squarify_single() {
         let sizes = vec![100.0];
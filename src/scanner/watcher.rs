@@ -0,0 +1,284 @@
+//! Watch mode: re-scan rules whose directories change
+//!
+//! The watcher subscribes to OS file events through `notify` (inotify,
+//! FSEvents or ReadDirectoryChangesW) on each rule's top scan directories.
+//! Directories are watched recursively, except the home directory and its
+//! ancestors, which project rules scan and which would take a watch per
+//! directory of the whole home; files and missing paths are covered by
+//! watching their parent. Events are debounced: rules are re-scanned only
+//! once their directories have been quiet for the debounce interval.
+
+use crate::rules::{CleanItem, CleanRule, ScanContext};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::time::{Duration, Instant};
+
+/// How often [`ScanWatcher::run`] checks whether it was asked to stop
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Keeps scan results up to date by re-scanning rules whose paths change
+pub struct ScanWatcher {
+    /// Rules being watched
+    rules: Vec<Box<dyn CleanRule>>,
+    /// Context passed to every rule's scan
+    ctx: ScanContext,
+    /// How long a rule's paths must stay unchanged before it is re-scanned
+    debounce: Duration,
+    /// OS watcher delivering events to `events`; dropping it stops watching
+    watcher: Option<RecommendedWatcher>,
+    /// Sending half handed to the OS watcher
+    sender: Sender<notify::Result<Event>>,
+    /// File events from the OS watcher
+    events: Receiver<notify::Result<Event>>,
+    /// Watched directories of each rule
+    roots: Vec<Vec<PathBuf>>,
+    /// Latest items of each rule
+    items: Vec<Vec<CleanItem>>,
+}
+
+impl ScanWatcher {
+    /// Create a watcher for the given rules
+    pub fn new(rules: Vec<Box<dyn CleanRule>>) -> Self {
+        let count = rules.len();
+        let (sender, events) = channel();
+        Self {
+            rules,
+            ctx: ScanContext::default(),
+            debounce: Duration::from_secs(2),
+            watcher: None,
+            sender,
+            events,
+            roots: vec![Vec::new(); count],
+            items: vec![Vec::new(); count],
+        }
+    }
+
+    /// Set the context (configuration) passed to rules
+    pub fn with_context(mut self, ctx: ScanContext) -> Self {
+        self.ctx = ctx;
+        self
+    }
+
+    /// Set how long changes must settle before rules are re-scanned
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Start watching every rule's paths and scan them all
+    pub fn scan_all(&mut self) -> Vec<CleanItem> {
+        self.watch();
+        let all: Vec<usize> = (0..self.rules.len()).collect();
+        self.rescan(&all);
+        self.items()
+    }
+
+    /// Latest items of all rules
+    pub fn items(&self) -> Vec<CleanItem> {
+        self.items.iter().flatten().cloned().collect()
+    }
+
+    /// Subscribe to events under each rule's scan paths
+    ///
+    /// A directory shared by several rules is watched once, recursively if
+    /// any of them needs that.
+    fn watch(&mut self) {
+        self.roots = self.rules.iter().map(|r| watch_roots(r.as_ref())).collect();
+        let mut modes: BTreeMap<&Path, RecursiveMode> = BTreeMap::new();
+        for (root, mode) in self
+            .roots
+            .iter()
+            .flatten()
+            .map(|root| (root, mode_for(root)))
+        {
+            let entry = modes.entry(root.as_path()).or_insert(mode);
+            if mode == RecursiveMode::Recursive {
+                *entry = mode;
+            }
+        }
+
+        let mut watcher = match notify::recommended_watcher(self.sender.clone()) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                tracing::warn!("Cannot watch for file changes: {}", e);
+                return;
+            }
+        };
+        for (root, mode) in modes {
+            if let Err(e) = watcher.watch(root, mode) {
+                tracing::warn!("Cannot watch {}: {}", root.display(), e);
+            }
+        }
+        self.watcher = Some(watcher);
+    }
+
+    /// Indices of rules with file events since the previous check
+    pub fn changed_rules(&mut self) -> Vec<usize> {
+        let mut changed = BTreeSet::new();
+        while let Ok(event) = self.events.try_recv() {
+            changed.extend(self.affected_rules(event));
+        }
+        changed.into_iter().collect()
+    }
+
+    /// Rules watching a path the event is about
+    fn affected_rules(&self, event: notify::Result<Event>) -> Vec<usize> {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("File watch error: {}", e);
+                return Vec::new();
+            }
+        };
+        // Reads, including the re-scans themselves, change nothing
+        if event.kind.is_access() {
+            return Vec::new();
+        }
+        self.roots
+            .iter()
+            .enumerate()
+            .filter(|(_, roots)| {
+                event
+                    .paths
+                    .iter()
+                    .any(|path| roots.iter().any(|root| path.starts_with(root)))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Re-scan the rules at the given indices in parallel
+    pub fn rescan(&mut self, indices: &[usize]) {
+        let rules = &self.rules;
+        let ctx = &self.ctx;
+        let results: Vec<(usize, Vec<CleanItem>)> = indices
+            .par_iter()
+            .map(|&index| {
+                let rule = &rules[index];
+                if !rule.is_applicable() {
+                    return (index, Vec::new());
+                }
+                match rule.scan(ctx) {
+                    Ok(items) => (index, ctx.filter_items(items)),
+                    Err(e) => {
+                        tracing::warn!("Failed to scan {}: {}", rule.name(), e);
+                        (index, Vec::new())
+                    }
+                }
+            })
+            .collect();
+
+        for (index, items) in results {
+            self.items[index] = items;
+        }
+    }
+
+    /// Wait for file events until `stop` is set, calling `on_update` with all
+    /// items after each debounced re-scan
+    pub fn run<F>(&mut self, stop: &AtomicBool, mut on_update: F)
+    where
+        F: FnMut(Vec<CleanItem>),
+    {
+        let mut pending = BTreeSet::new();
+        let mut last_change = Instant::now();
+
+        while !stop.load(Ordering::Relaxed) {
+            match self.events.recv_timeout(STOP_CHECK_INTERVAL) {
+                Ok(event) => {
+                    let affected = self.affected_rules(event);
+                    if !affected.is_empty() {
+                        pending.extend(affected);
+                        last_change = Instant::now();
+                    }
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if !pending.is_empty() && last_change.elapsed() >= self.debounce {
+                let indices: Vec<usize> = std::mem::take(&mut pending).into_iter().collect();
+                self.rescan(&indices);
+                on_update(self.items());
+            }
+        }
+    }
+}
+
+/// Directories to watch for a rule: its scan paths that are directories,
+/// and the parent of those that are files or don't exist yet
+fn watch_roots(rule: &dyn CleanRule) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = rule
+        .scan_paths()
+        .into_iter()
+        .filter_map(|path| {
+            if path.is_dir() {
+                Some(path)
+            } else {
+                path.parent()
+                    .filter(|parent| parent.is_dir())
+                    .map(Path::to_path_buf)
+            }
+        })
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+/// Watch `root` recursively unless it holds the whole home directory
+fn mode_for(root: &Path) -> RecursiveMode {
+    match crate::paths::home_dir() {
+        Some(home) if home.starts_with(root) => RecursiveMode::NonRecursive,
+        _ => RecursiveMode::Recursive,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{env_read, log_rule};
+
+    #[test]
+    fn test_new_file_triggers_rescan() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.log"), vec![0u8; 1024]).unwrap();
+
+        let mut watcher = ScanWatcher::new(vec![log_rule("Logs", dir.path())]);
+
+        assert_eq!(watcher.scan_all().len(), 1);
+        assert!(watcher.changed_rules().is_empty());
+
+        std::fs::write(dir.path().join("b.log"), vec![0u8; 1024]).unwrap();
+        // Events arrive asynchronously
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut changed = watcher.changed_rules();
+        while changed.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            changed = watcher.changed_rules();
+        }
+        assert_eq!(changed, vec![0]);
+
+        watcher.rescan(&changed);
+        assert_eq!(watcher.items().len(), 2);
+    }
+
+    #[test]
+    fn test_rescan_drops_ignored_items() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.log"), vec![0u8; 1024]).unwrap();
+
+        let mut config = crate::config::Config::default();
+        config.ignore.paths = vec![dir.path().join("a.log")];
+        let mut watcher = ScanWatcher::new(vec![log_rule("Logs", dir.path())])
+            .with_context(ScanContext::new(config));
+
+        assert!(watcher.scan_all().is_empty());
+    }
+}
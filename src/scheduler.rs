@@ -0,0 +1,366 @@
+//! Scheduled cleanups via launchd (macOS) or systemd user timers (Linux)
+//!
+//! `Schedule` renders the unit files that periodically invoke
+//! `cleanmymac-rs clean --yes-low --categories <...>` and installs or removes them
+//! with the platform's service manager.
+
+use crate::config::Config;
+use crate::rules::{RiskLevel, get_all_rules_with_config};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// launchd label and systemd unit name
+pub const SCHEDULE_NAME: &str = "cleanmymac-rs";
+
+/// How often the scheduled clean runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Cadence {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Cadence {
+    /// Interval in seconds (used by launchd's `StartInterval`)
+    pub fn seconds(self) -> u64 {
+        match self {
+            Cadence::Hourly => 60 * 60,
+            Cadence::Daily => 24 * 60 * 60,
+            Cadence::Weekly => 7 * 24 * 60 * 60,
+            Cadence::Monthly => 30 * 24 * 60 * 60,
+        }
+    }
+
+    /// systemd `OnCalendar=` shorthand
+    pub fn on_calendar(self) -> &'static str {
+        match self {
+            Cadence::Hourly => "hourly",
+            Cadence::Daily => "daily",
+            Cadence::Weekly => "weekly",
+            Cadence::Monthly => "monthly",
+        }
+    }
+}
+
+/// A file written when installing the schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitFile {
+    /// Where the file is installed
+    pub path: PathBuf,
+    /// File contents
+    pub contents: String,
+}
+
+/// Periodic clean invocation
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    /// How often to run
+    pub cadence: Cadence,
+    /// Categories passed to `clean --categories`
+    pub categories: Vec<String>,
+    /// Binary to invoke
+    pub binary: PathBuf,
+}
+
+impl Schedule {
+    /// Create a schedule invoking the currently running binary
+    pub fn new(cadence: Cadence, categories: Vec<String>) -> crate::Result<Self> {
+        Ok(Self {
+            cadence,
+            categories,
+            binary: std::env::current_exe()?,
+        })
+    }
+
+    /// Arguments passed to the binary on each run
+    pub fn clean_args(&self) -> Vec<String> {
+        vec![
+            "clean".to_string(),
+            // Riskier items a low-risk rule still emits are never approved
+            // unattended: their prompt fails without a terminal
+            "--yes-low".to_string(),
+            "--categories".to_string(),
+            self.categories.join(","),
+        ]
+    }
+
+    /// Unit files for the current platform
+    pub fn unit_files(&self) -> crate::Result<Vec<UnitFile>> {
+        if cfg!(target_os = "macos") {
+            Ok(vec![UnitFile {
+                path: launchd_plist_path()?,
+                contents: self.launchd_plist(),
+            }])
+        } else if cfg!(target_os = "linux") {
+            let dir = systemd_user_dir()?;
+            Ok(vec![
+                UnitFile {
+                    path: dir.join(format!("{}.service", SCHEDULE_NAME)),
+                    contents: self.systemd_service(),
+                },
+                UnitFile {
+                    path: dir.join(format!("{}.timer", SCHEDULE_NAME)),
+                    contents: self.systemd_timer(),
+                },
+            ])
+        } else {
+            Err(unsupported())
+        }
+    }
+
+    /// Render a launchd agent plist
+    pub fn launchd_plist(&self) -> String {
+        let mut arguments = format!(
+            "        <string>{}</string>\n",
+            xml_escape(&self.binary.to_string_lossy())
+        );
+        for arg in self.clean_args() {
+            arguments.push_str(&format!("        <string>{}</string>\n", xml_escape(&arg)));
+        }
+
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>StartInterval</key>
+    <integer>{interval}</integer>
+    <key>RunAtLoad</key>
+    <false/>
+</dict>
+</plist>
+"#,
+            label = SCHEDULE_NAME,
+            arguments = arguments,
+            interval = self.cadence.seconds(),
+        )
+    }
+
+    /// Render the systemd service unit
+    pub fn systemd_service(&self) -> String {
+        let mut exec = systemd_quote(&self.binary.to_string_lossy());
+        for arg in self.clean_args() {
+            exec.push(' ');
+            exec.push_str(&systemd_quote(&arg));
+        }
+
+        format!(
+            "[Unit]\n\
+             Description=Scheduled cleanup by {name}\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStart={exec}\n",
+            name = SCHEDULE_NAME,
+            exec = exec,
+        )
+    }
+
+    /// Render the systemd timer unit
+    pub fn systemd_timer(&self) -> String {
+        format!(
+            "[Unit]\n\
+             Description=Run {name} {cadence}\n\
+             \n\
+             [Timer]\n\
+             OnCalendar={cadence}\n\
+             Persistent=true\n\
+             \n\
+             [Install]\n\
+             WantedBy=timers.target\n",
+            name = SCHEDULE_NAME,
+            cadence = self.cadence.on_calendar(),
+        )
+    }
+
+    /// Write the unit files and activate them
+    pub fn install(&self) -> crate::Result<Vec<PathBuf>> {
+        let files = self.unit_files()?;
+        for file in &files {
+            if let Some(parent) = file.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file.path, &file.contents)?;
+        }
+
+        if cfg!(target_os = "macos") {
+            let plist = files[0].path.to_string_lossy().to_string();
+            // Reload in case an older schedule is already loaded
+            let _ = run("launchctl", &["unload", &plist]);
+            run("launchctl", &["load", "-w", &plist])?;
+        } else {
+            run("systemctl", &["--user", "daemon-reload"])?;
+            run(
+                "systemctl",
+                &[
+                    "--user",
+                    "enable",
+                    "--now",
+                    &format!("{}.timer", SCHEDULE_NAME),
+                ],
+            )?;
+        }
+
+        Ok(files.into_iter().map(|f| f.path).collect())
+    }
+}
+
+/// Deactivate and delete an installed schedule, returning the removed files
+pub fn remove() -> crate::Result<Vec<PathBuf>> {
+    let paths = if cfg!(target_os = "macos") {
+        let plist = launchd_plist_path()?;
+        if plist.exists() {
+            let _ = run("launchctl", &["unload", "-w", &plist.to_string_lossy()]);
+        }
+        vec![plist]
+    } else if cfg!(target_os = "linux") {
+        let dir = systemd_user_dir()?;
+        let timer = dir.join(format!("{}.timer", SCHEDULE_NAME));
+        if timer.exists() {
+            let _ = run(
+                "systemctl",
+                &[
+                    "--user",
+                    "disable",
+                    "--now",
+                    &format!("{}.timer", SCHEDULE_NAME),
+                ],
+            );
+        }
+        vec![timer, dir.join(format!("{}.service", SCHEDULE_NAME))]
+    } else {
+        return Err(unsupported());
+    };
+
+    let mut removed = Vec::new();
+    for path in paths {
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| crate::Error::from_delete(&path, e))?;
+            removed.push(path);
+        }
+    }
+
+    if cfg!(target_os = "linux") && !removed.is_empty() {
+        let _ = run("systemctl", &["--user", "daemon-reload"]);
+    }
+
+    Ok(removed)
+}
+
+/// Categories whose rules are all low risk, the safe default for unattended runs
+pub fn low_risk_categories(config: &Config) -> Vec<String> {
+    let mut all = BTreeSet::new();
+    let mut risky = BTreeSet::new();
+    for rule in get_all_rules_with_config(config) {
+        let category = rule.category().slug();
+        if rule.risk_level() != RiskLevel::Low {
+            risky.insert(category.clone());
+        }
+        all.insert(category);
+    }
+    all.difference(&risky).cloned().collect()
+}
+
+fn launchd_plist_path() -> crate::Result<PathBuf> {
+    crate::paths::home_dir()
+        .map(|home| {
+            home.join("Library/LaunchAgents")
+                .join(format!("{}.plist", SCHEDULE_NAME))
+        })
+        .ok_or_else(|| crate::Error::Other("Could not determine home directory".to_string()))
+}
+
+fn systemd_user_dir() -> crate::Result<PathBuf> {
+    crate::paths::config_dir()
+        .map(|dir| dir.join("systemd/user"))
+        .ok_or_else(|| crate::Error::Other("Could not determine config directory".to_string()))
+}
+
+fn unsupported() -> crate::Error {
+    crate::Error::Other("Scheduling is only supported on macOS and Linux".to_string())
+}
+
+/// Run a service manager command, failing on a non-zero exit
+fn run(program: &str, args: &[&str]) -> crate::Result<()> {
+    let output = Command::new(program).args(args).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(crate::Error::Other(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Quote an argument for a systemd `ExecStart=` line
+fn systemd_quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+
+    fn schedule() -> Schedule {
+        Schedule {
+            cadence: Cadence::Weekly,
+            categories: vec!["homebrew".to_string(), "linux packages".to_string()],
+            binary: PathBuf::from("/usr/local/bin/cleanmymac-rs"),
+        }
+    }
+
+    #[test]
+    fn test_systemd_units() {
+        let _env = env_read();
+        let schedule = schedule();
+        let service = schedule.systemd_service();
+        assert!(service.contains(
+            "ExecStart=/usr/local/bin/cleanmymac-rs clean --yes-low --categories \"homebrew,linux packages\""
+        ));
+        assert!(service.contains("Type=oneshot"));
+        assert!(schedule.systemd_timer().contains("OnCalendar=weekly"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let _env = env_read();
+        let plist = schedule().launchd_plist();
+        assert!(plist.contains("<string>cleanmymac-rs</string>"));
+        assert!(plist.contains("<string>--yes-low</string>"));
+        assert!(plist.contains("<string>homebrew,linux packages</string>"));
+        assert!(plist.contains("<integer>604800</integer>"));
+    }
+
+    #[test]
+    fn test_low_risk_categories_exclude_risky_rules() {
+        let _env = env_read();
+        let categories = low_risk_categories(&Config::default());
+        // Docker prunes images and volumes, which is not low risk
+        assert!(!categories.contains(&"docker".to_string()));
+        // Low-risk caches next to medium-risk vendor dirs and output bases
+        assert!(!categories.contains(&"php".to_string()));
+        assert!(!categories.contains(&"bazel".to_string()));
+    }
+}
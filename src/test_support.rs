@@ -0,0 +1,78 @@
+//! Helpers shared by unit tests
+
+use crate::config::CustomRuleConfig;
+use crate::rules::{CleanRule, CustomRule, RiskLevel};
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::{RwLock, RwLockReadGuard};
+
+/// Guards the process-wide environment and working directory: tests that
+/// change them hold it exclusively, tests that read them hold it shared
+static ENV_LOCK: RwLock<()> = RwLock::new(());
+
+/// Hold for the duration of a test that reads the environment, directly or
+/// through home, cache and config directory lookups, so no `with_env` call
+/// changes it underneath
+pub fn env_read() -> RwLockReadGuard<'static, ()> {
+    ENV_LOCK.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Run `f` with the given environment variables set (`Some`) or removed (`None`),
+/// restoring the previous values afterwards
+pub fn with_env<T>(vars: &[(&str, Option<&Path>)], f: impl FnOnce() -> T) -> T {
+    let _guard = ENV_LOCK.write().unwrap_or_else(|e| e.into_inner());
+
+    let saved: Vec<(&str, Option<OsString>)> = vars
+        .iter()
+        .map(|(key, _)| (*key, std::env::var_os(key)))
+        .collect();
+
+    // SAFETY: we hold ENV_LOCK exclusively, and every test that reads the
+    // environment holds it shared through `env_read`
+    unsafe {
+        for (key, value) in vars {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+
+    // SAFETY: as above
+    unsafe {
+        for (key, value) in saved {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+
+    result.unwrap_or_else(|e| std::panic::resume_unwind(e))
+}
+
+/// Run `f` with the process working directory set to `dir`, restoring it afterwards
+pub fn with_current_dir<T>(dir: &Path, f: impl FnOnce() -> T) -> T {
+    let _guard = ENV_LOCK.write().unwrap_or_else(|e| e.into_inner());
+
+    let saved = std::env::current_dir().unwrap();
+    std::env::set_current_dir(dir).unwrap();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+    std::env::set_current_dir(saved).unwrap();
+
+    result.unwrap_or_else(|e| std::panic::resume_unwind(e))
+}
+
+/// Low-risk custom rule named `name` reporting the `*.log` files in `dir`
+pub fn log_rule(name: &str, dir: &Path) -> Box<dyn CleanRule> {
+    Box::new(CustomRule::new(CustomRuleConfig {
+        id: None,
+        name: name.to_string(),
+        paths: vec![format!("{}/*.log", dir.display())],
+        category: "logs".to_string(),
+        risk: RiskLevel::Low,
+        min_size_mb: 0,
+    }))
+}
@@ -1,5 +1,7 @@
 //! CLI interface using clap
 
+use crate::scanner::{CategorySort, ItemSort};
+use crate::scheduler::Cadence;
 use clap::{Parser, Subcommand, ValueEnum};
 
 /// CleanMyMac-rs - A cross-platform system cleaner
@@ -25,9 +27,26 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub config: Option<String>,
 
+    /// Configuration profile to apply (from `[profiles.<NAME>]`)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
     /// Disable colored output
     #[arg(long, global = true)]
     pub no_color: bool,
+
+    /// Use ASCII instead of emoji and box-drawing characters (also set by CMMRS_ASCII=1)
+    #[arg(long, global = true)]
+    pub ascii: bool,
+
+    /// Skip heuristic cache detection, overriding `heuristic.enabled`
+    #[arg(long, global = true)]
+    pub no_heuristic: bool,
+
+    /// Run at low CPU and I/O priority with half the threads, for background
+    /// runs (also set by `general.io_nice`)
+    #[arg(long, global = true)]
+    pub low_priority: bool,
 }
 
 /// Available commands
@@ -41,10 +60,38 @@ pub enum Commands {
     Scan {
         /// Categories to scan (comma-separated)
         ///
-        /// Available categories: system, brew, xcode, nodejs, python, rust, go, java, docker, android, heuristic, macapps, linuxpackages
+        /// Available categories: system, brew, xcode, nodejs, python, rust, go, java, docker,
+        /// android, heuristic, macapps, linuxpackages, ide, mobile, ruby, dotnet, bazel, electron,
+        /// gamedev, haskell, ocaml, php (see `list` for custom categories)
         #[arg(short = 'C', long, value_delimiter = ',')]
         categories: Option<Vec<String>>,
 
+        /// Read more categories or rule ids from FILE (newline- or
+        /// comma-separated, `#` comments), merged with --categories
+        #[arg(long, value_name = "FILE")]
+        categories_file: Option<std::path::PathBuf>,
+
+        /// Use every rule, ignoring `categories.enabled` and
+        /// `categories.disabled` in the config
+        #[arg(long, conflicts_with_all = ["categories", "categories_file", "config_categories"])]
+        all: bool,
+
+        /// Use the categories in `categories.enabled` (every category if it is
+        /// empty), minus `categories.disabled`; the default without
+        /// --categories or --all
+        #[arg(long, conflicts_with_all = ["categories", "categories_file"])]
+        config_categories: bool,
+
+        /// Categories to leave out, applied after --categories (comma-separated
+        /// or repeated)
+        #[arg(
+            long,
+            alias = "category-exclude",
+            value_name = "CATEGORY",
+            value_delimiter = ','
+        )]
+        exclude_category: Vec<String>,
+
         /// Output format
         #[arg(short, long, default_value = "table")]
         format: OutputFormat,
@@ -52,6 +99,53 @@ pub enum Commands {
         /// Minimum size threshold (e.g., "100MB", "1GB")
         #[arg(long)]
         min_size: Option<String>,
+
+        /// Report caches of any size, ignoring every rule's minimum size
+        #[arg(long)]
+        include_small: bool,
+
+        /// Only report heuristically detected caches with at least this
+        /// confidence (0.0 - 1.0)
+        #[arg(long, value_name = "SCORE", value_parser = parse_confidence)]
+        min_confidence: Option<f64>,
+
+        /// Print running subtotals per category as each rule finishes
+        #[arg(long)]
+        stream: bool,
+
+        /// List paths that were skipped because permission was denied
+        #[arg(long)]
+        show_errors: bool,
+
+        /// Number of items to display per category in the table (all are still cleanable)
+        #[arg(long, value_name = "N", default_value = "5")]
+        top: usize,
+
+        /// Order of the items within each category
+        #[arg(long, value_enum, default_value_t)]
+        sort: ItemSort,
+
+        /// Order of the categories
+        #[arg(long, value_enum, default_value_t)]
+        sort_categories: CategorySort,
+
+        /// Only report items located under DIR (command-driven rules such as
+        /// Docker and Nix are excluded)
+        #[arg(long, value_name = "DIR")]
+        path: Option<std::path::PathBuf>,
+
+        /// Keep running and refresh the results when scanned directories change
+        /// (stop with Ctrl-C)
+        #[arg(long, conflicts_with = "stream")]
+        watch: bool,
+
+        /// Print how long each rule took and what it found, slowest first
+        #[arg(long, conflicts_with_all = ["stream", "watch"])]
+        timings: bool,
+
+        /// Save the results as JSON to FILE, for a later `diff`
+        #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+        save: Option<std::path::PathBuf>,
     },
 
     /// Clean scanned files
@@ -64,25 +158,107 @@ pub enum Commands {
         #[arg(short = 'C', long, value_delimiter = ',')]
         categories: Option<Vec<String>>,
 
+        /// Read more categories or rule ids from FILE (newline- or
+        /// comma-separated, `#` comments), merged with --categories
+        #[arg(long, value_name = "FILE")]
+        categories_file: Option<std::path::PathBuf>,
+
+        /// Use every rule, ignoring `categories.enabled` and
+        /// `categories.disabled` in the config
+        #[arg(long, conflicts_with_all = ["categories", "categories_file", "config_categories"])]
+        all: bool,
+
+        /// Use the categories in `categories.enabled` (every category if it is
+        /// empty), minus `categories.disabled`; the default without
+        /// --categories or --all
+        #[arg(long, conflicts_with_all = ["categories", "categories_file"])]
+        config_categories: bool,
+
+        /// Categories to leave out, applied after --categories (comma-separated
+        /// or repeated)
+        #[arg(
+            long,
+            alias = "category-exclude",
+            value_name = "CATEGORY",
+            value_delimiter = ','
+        )]
+        exclude_category: Vec<String>,
+
         /// Perform a dry run (show what would be deleted)
         #[arg(short = 'n', long)]
         dry_run: bool,
 
+        /// Clean caches of any size, ignoring every rule's minimum size
+        #[arg(long)]
+        include_small: bool,
+
+        /// Only clean heuristically detected caches with at least this
+        /// confidence (0.0 - 1.0)
+        #[arg(long, value_name = "SCORE", value_parser = parse_confidence)]
+        min_confidence: Option<f64>,
+
+        /// Output format (json prints only the dry-run manifest or the clean result)
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+
         /// Skip confirmation prompts
         #[arg(short = 'y', long)]
         yes: bool,
 
+        /// Clean low-risk items without asking, but still prompt for medium-
+        /// and high-risk items (--yes takes precedence)
+        #[arg(long)]
+        yes_low: bool,
+
         /// Permanently delete instead of moving to trash
         #[arg(long)]
         permanent: bool,
 
+        /// Move items into a timestamped batch under DIR instead of the trash,
+        /// to inspect and later remove with `purge-staging`
+        #[arg(long, value_name = "DIR", conflicts_with = "permanent")]
+        stage: Option<std::path::PathBuf>,
+
+        /// Write a per-item report of the clean to FILE, as JSON if it ends
+        /// in `.json` and as Markdown otherwise
+        #[arg(long, value_name = "FILE")]
+        report: Option<std::path::PathBuf>,
+
         /// Interactive mode (select items to clean)
         #[arg(short = 'i', long)]
         interactive: bool,
 
-        /// Don't show progress bar
+        /// Only clean items older than `heuristic.stale_days` from the config
         #[arg(long)]
+        only_stale: bool,
+
+        /// Only clean items older than DAYS days (implies --only-stale)
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+
+        /// Only clean the largest items (low risk first) that together stay
+        /// within SIZE, e.g. "10GB"
+        #[arg(long, value_name = "SIZE")]
+        max_total: Option<String>,
+
+        /// Clean system package caches (APT, DNF, Pacman) via their package
+        /// manager under sudo
+        #[arg(long)]
+        sudo: bool,
+
+        /// Only clean items located under DIR (command-driven rules such as
+        /// Docker and Nix are excluded)
+        #[arg(long, value_name = "DIR")]
+        path: Option<std::path::PathBuf>,
+
+        /// Suppress progress bars and the preview, printing only a one-line summary
+        #[arg(short = 'q', long)]
         quiet: bool,
+
+        /// Take over the lock of another clean if it is older than an hour
+        /// (left behind by a crashed run whose pid is in use again)
+        #[arg(long)]
+        force: bool,
     },
 
     /// Analyze storage usage
@@ -102,6 +278,45 @@ pub enum Commands {
         /// Number of largest files to show
         #[arg(short, long, default_value = "10")]
         top: usize,
+
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+
+        /// Write the directory tree (bounded by --depth) to FILE as JSON
+        #[arg(long, value_name = "FILE")]
+        export_treemap: Option<std::path::PathBuf>,
+
+        /// Stay on the file system of the analyzed directory, skipping mounted
+        /// volumes and pseudo file systems like /proc (like `du -x`)
+        #[arg(short = 'x', long)]
+        one_file_system: bool,
+
+        /// Find sets of identical files and how much removing the extra
+        /// copies would free
+        #[arg(long)]
+        duplicates: bool,
+
+        /// Only check files of at least this size for duplicates (e.g. "1MB")
+        #[arg(
+            long,
+            value_name = "SIZE",
+            default_value = "1MB",
+            requires = "duplicates"
+        )]
+        min_size: String,
+
+        /// Only count files with these extensions (comma-separated, e.g. "mp4,mov")
+        #[arg(long, value_name = "EXTS", value_delimiter = ',')]
+        only_ext: Vec<String>,
+
+        /// Ignore files with these extensions (comma-separated, e.g. "o,pyc")
+        #[arg(long, value_name = "EXTS", value_delimiter = ',')]
+        exclude_ext: Vec<String>,
+
+        /// Don't show the files-scanned spinner
+        #[arg(short = 'q', long)]
+        quiet: bool,
     },
 
     /// List available cleanup rules
@@ -116,6 +331,116 @@ pub enum Commands {
         /// Show detailed information
         #[arg(short, long)]
         detailed: bool,
+
+        /// Show each rule's id, as used in `[rules.<id>]` config sections
+        #[arg(long)]
+        id: bool,
+
+        /// Only list rules that apply to this system (all rules by default)
+        #[arg(long)]
+        applicable_only: bool,
+
+        /// Output format (json includes ids, paths and applicability)
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Permanently remove batches staged by `clean --stage`
+    ///
+    /// Only timestamped batch directories created by `clean --stage` are
+    /// removed; anything else in DIR is left alone.
+    PurgeStaging {
+        /// Staging directory passed to `clean --stage`
+        dir: std::path::PathBuf,
+
+        /// Only purge batches staged more than DAYS days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+
+        /// Show what would be purged without removing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Show how much space has been reclaimed over time
+    ///
+    /// Reads the journal of past clean operations and summarizes bytes freed,
+    /// operation count, and a per-category and per-month breakdown.
+    History {
+        /// Only include operations on or after this date (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Check which previously cleaned paths have grown back
+    ///
+    /// Reads the most recent clean from the journal and reports, for every
+    /// path it removed, whether the path exists again and its current size.
+    Verify {
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Compare two saved scans and show what grew or shrank
+    ///
+    /// BEFORE and AFTER are files written by `scan --save`. Without AFTER,
+    /// the current state is scanned and compared against BEFORE.
+    Diff {
+        /// Earlier saved scan
+        before: std::path::PathBuf,
+
+        /// Later saved scan (defaults to a fresh scan)
+        after: Option<std::path::PathBuf>,
+
+        /// Number of changed paths to display in the table
+        #[arg(long, value_name = "N", default_value = "20")]
+        top: usize,
+
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Diagnose which rules apply to this system and why
+    ///
+    /// For every rule, shows whether it is applicable, the paths it checks,
+    /// and which of those paths exist.
+    Doctor {
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Run low-risk cleanups automatically
+    ///
+    /// Installs a launchd agent (macOS) or systemd user timer (Linux) that
+    /// runs `clean --yes` for the given categories. Only categories whose
+    /// rules are all low risk are cleaned by default.
+    Schedule {
+        /// How often to run the cleanup
+        #[arg(long, value_enum, default_value = "daily")]
+        every: Cadence,
+
+        /// Categories to clean (comma-separated, defaults to low-risk categories)
+        #[arg(short = 'C', long, value_delimiter = ',')]
+        categories: Option<Vec<String>>,
+
+        /// Remove the installed schedule
+        #[arg(long)]
+        remove: bool,
+
+        /// Print the generated unit files without installing them
+        #[arg(short = 'n', long)]
+        dry_run: bool,
     },
 
     /// Initialize or show configuration
@@ -130,6 +455,15 @@ pub enum Commands {
         #[arg(long)]
         show: bool,
 
+        /// Check the configuration file for syntax errors and unknown keys
+        #[arg(long)]
+        validate: bool,
+
+        /// Open the configuration file in $VISUAL or $EDITOR (creating it if
+        /// missing) and check it when the editor exits
+        #[arg(long)]
+        edit: bool,
+
         /// Path for configuration file
         #[arg(long)]
         path: Option<String>,
@@ -152,6 +486,18 @@ pub enum OutputFormat {
     Json,
     /// Simple list format
     List,
+    /// One JSON object per item and line, printed as items are found (scan only)
+    Ndjson,
+}
+
+/// Parse a confidence score between 0.0 and 1.0
+fn parse_confidence(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", value))
+    }
 }
 
 impl Cli {
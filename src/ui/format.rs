@@ -0,0 +1,145 @@
+//! Path formatting for terminal output
+//!
+//! Item paths mostly live under the home directory and can be far wider than
+//! the terminal, so output shows them with the home directory collapsed to `~`
+//! and, where space is limited, with the middle left out.
+
+use super::glyphs::glyphs;
+use std::io::IsTerminal;
+use std::path::Path;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Format a path for display
+///
+/// The home directory is collapsed to `~`. With `max_width`, paths longer
+/// than that many characters keep their start and end around an ellipsis,
+/// favouring the end since it names the item.
+pub fn display_path(path: &Path, max_width: Option<usize>) -> String {
+    let text = collapse_home(path);
+    match max_width {
+        Some(width) => truncate_middle(&text, width),
+        None => text,
+    }
+}
+
+/// Characters left for a path on a terminal line after `reserved` columns
+///
+/// `None` when stdout is not a terminal, so piped output keeps full paths.
+pub fn available_width(reserved: usize) -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let (columns, _) = crossterm::terminal::size().ok()?;
+    Some((columns as usize).saturating_sub(reserved).max(20))
+}
+
+/// Replace a leading home directory with `~`
+fn collapse_home(path: &Path) -> String {
+    let home = crate::paths::home_dir().filter(|home| home.parent().is_some());
+    match home
+        .as_deref()
+        .and_then(|home| path.strip_prefix(home).ok())
+    {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Shorten `text` to at most `width` terminal columns by leaving out the middle
+///
+/// Cuts between characters and counts wide (CJK, emoji) characters as two
+/// columns, so multibyte names are never split and columns stay aligned.
+fn truncate_middle(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+
+    let ellipsis = glyphs().ellipsis;
+    let Some(keep) = width.checked_sub(ellipsis.width()).filter(|&keep| keep > 1) else {
+        return take_columns(text.chars().rev(), width)
+            .chars()
+            .rev()
+            .collect();
+    };
+    let head = take_columns(text.chars(), keep / 3);
+    let tail: String = take_columns(text.chars().rev(), keep - head.width())
+        .chars()
+        .rev()
+        .collect();
+    format!("{}{}{}", head, ellipsis, tail)
+}
+
+/// Shorten `text` to `width` columns, ending in an ellipsis if cut, and pad it
+/// with spaces to exactly `width`
+pub fn fit_width(text: &str, width: usize) -> String {
+    let mut out = if text.width() <= width {
+        text.to_string()
+    } else {
+        match width.checked_sub(glyphs().ellipsis.width()) {
+            Some(keep) => format!("{}{}", take_columns(text.chars(), keep), glyphs().ellipsis),
+            None => take_columns(text.chars(), width),
+        }
+    };
+    let used = out.width();
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Collect characters until the next one would exceed `columns`
+fn take_columns(chars: impl Iterator<Item = char>, columns: usize) -> String {
+    let mut used = 0;
+    chars
+        .take_while(|c| {
+            used += c.width().unwrap_or(0);
+            used <= columns
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{env_read, with_env};
+
+    #[test]
+    fn test_display_path_collapses_home_and_truncates() {
+        with_env(&[("HOME", Some(Path::new("/Users/me")))], || {
+            let path = Path::new("/Users/me/Library/Caches/com.example");
+            assert_eq!(display_path(path, None), "~/Library/Caches/com.example");
+            assert_eq!(display_path(Path::new("/Users/me"), None), "~");
+            assert_eq!(
+                display_path(Path::new("/Users/meg/x"), None),
+                "/Users/meg/x"
+            );
+
+            let short = display_path(path, Some(20));
+            assert_eq!(short.width(), 20);
+            assert!(short.starts_with("~/Li"));
+            assert!(short.ends_with("com.example"));
+        });
+    }
+
+    #[test]
+    fn test_truncation_handles_wide_and_multibyte_characters() {
+        let _env = env_read();
+        let text = "/Users/José/写真/🎉 パーティー/größe.jpg";
+        for width in 0..text.width() + 2 {
+            let short = truncate_middle(text, width);
+            assert!(short.width() <= width, "{}: {}", width, short);
+
+            let fitted = fit_width(text, width);
+            assert_eq!(fitted.width(), width, "{}: {:?}", width, fitted);
+        }
+
+        let short = truncate_middle(text, 24);
+        assert!(short.starts_with("/Users"), "{}", short);
+        assert!(short.ends_with("größe.jpg"), "{}", short);
+        assert!(
+            fit_width("写真写真写真", 7)
+                .trim_end()
+                .ends_with(glyphs().ellipsis)
+        );
+        assert_eq!(fit_width("日本", 6), "日本  ");
+    }
+}
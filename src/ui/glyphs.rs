@@ -0,0 +1,260 @@
+//! Glyphs used in terminal output
+//!
+//! Emoji and box-drawing characters show up as mojibake on some terminals and
+//! in log files, so output looks them up here instead of hardcoding them.
+//! `--ascii` (or the `CMMRS_ASCII` environment variable) selects plain ASCII
+//! equivalents for the rest of the process.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Environment variable that enables ASCII output when set to a non-empty value other than `0`
+pub const ASCII_ENV: &str = "CMMRS_ASCII";
+
+/// Whether the ASCII set is selected
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Available glyph sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlyphSet {
+    /// Emoji and box-drawing characters
+    #[default]
+    Unicode,
+    /// Plain ASCII
+    Ascii,
+}
+
+impl GlyphSet {
+    /// Pick the set from the `--ascii` flag and the `CMMRS_ASCII` environment variable
+    pub fn detect(ascii_flag: bool) -> Self {
+        let from_env = std::env::var_os(ASCII_ENV).is_some_and(|v| !v.is_empty() && v != "0");
+        if ascii_flag || from_env {
+            GlyphSet::Ascii
+        } else {
+            GlyphSet::Unicode
+        }
+    }
+
+    /// Use this set for all subsequent output
+    pub fn select(self) {
+        ASCII.store(self == GlyphSet::Ascii, Ordering::Relaxed);
+    }
+
+    /// The currently selected set
+    pub fn current() -> Self {
+        if ASCII.load(Ordering::Relaxed) {
+            GlyphSet::Ascii
+        } else {
+            GlyphSet::Unicode
+        }
+    }
+
+    /// Glyphs of this set
+    pub fn glyphs(self) -> &'static Glyphs {
+        match self {
+            GlyphSet::Unicode => &UNICODE,
+            GlyphSet::Ascii => &ASCII_GLYPHS,
+        }
+    }
+}
+
+/// Glyphs of the currently selected set
+pub fn glyphs() -> &'static Glyphs {
+    GlyphSet::current().glyphs()
+}
+
+/// One glyph per purpose
+#[derive(Debug)]
+pub struct Glyphs {
+    /// Scanning heading
+    pub search: &'static str,
+    /// Cleaning heading
+    pub broom: &'static str,
+    /// Nothing to do
+    pub sparkles: &'static str,
+    /// Statistics heading
+    pub stats: &'static str,
+    /// Listing heading
+    pub clipboard: &'static str,
+    /// Empty history
+    pub empty: &'static str,
+    /// History heading
+    pub trend: &'static str,
+    /// Removal
+    pub trash: &'static str,
+    /// Scheduling
+    pub clock: &'static str,
+    /// Diagnostics heading
+    pub doctor: &'static str,
+    /// Treemap export
+    pub map: &'static str,
+    /// Prompt pointer
+    pub point: &'static str,
+    /// Folder
+    pub folder: &'static str,
+    /// Open folder
+    pub open_folder: &'static str,
+    /// Disk
+    pub disk: &'static str,
+    /// Settings
+    pub settings: &'static str,
+    /// Help
+    pub help: &'static str,
+    /// Success
+    pub success: &'static str,
+    /// Failure
+    pub failure: &'static str,
+    /// Warning
+    pub warning: &'static str,
+    /// Information
+    pub info: &'static str,
+    /// Check mark
+    pub check: &'static str,
+    /// Cross mark
+    pub cross: &'static str,
+    /// Risk indicator
+    pub dot: &'static str,
+    /// List bullet
+    pub bullet: &'static str,
+    /// Marks text left out of a shortened path
+    pub ellipsis: &'static str,
+    /// Category marker
+    pub arrow: &'static str,
+    /// Selection cursor
+    pub pointer: &'static str,
+    /// Scroll up
+    pub up: &'static str,
+    /// Scroll down
+    pub down: &'static str,
+    /// Heavy horizontal rule segment
+    pub double_rule: &'static str,
+    /// Light horizontal rule segment
+    pub rule: &'static str,
+    /// Vertical divider
+    pub divider: &'static str,
+    /// Selected checkbox
+    pub checked: &'static str,
+    /// Unselected checkbox
+    pub unchecked: &'static str,
+    /// Bar segments from full to nearly empty
+    pub shades: [&'static str; 4],
+    /// Unused bar segment
+    pub bar_empty: &'static str,
+    /// Spinner animation frames
+    pub spinner: &'static [&'static str],
+    /// indicatif progress bar characters (filled, current, empty)
+    pub progress_chars: &'static str,
+    /// indicatif spinner characters, the last one shown when finished
+    pub tick_chars: &'static str,
+}
+
+impl Glyphs {
+    /// A horizontal rule `width` segments long
+    pub fn double_line(&self, width: usize) -> String {
+        self.double_rule.repeat(width)
+    }
+
+    /// A light horizontal rule `width` segments long
+    pub fn line(&self, width: usize) -> String {
+        self.rule.repeat(width)
+    }
+}
+
+const UNICODE: Glyphs = Glyphs {
+    search: "🔍",
+    broom: "🧹",
+    sparkles: "✨",
+    stats: "📊",
+    clipboard: "📋",
+    empty: "📭",
+    trend: "📈",
+    trash: "🗑️",
+    clock: "⏰",
+    doctor: "🩺",
+    map: "🗺️",
+    point: "👉",
+    folder: "📁",
+    open_folder: "📂",
+    disk: "💾",
+    settings: "⚙️",
+    help: "❓",
+    success: "✅",
+    failure: "❌",
+    warning: "⚠️",
+    info: "ℹ",
+    check: "✓",
+    cross: "✗",
+    dot: "●",
+    bullet: "•",
+    ellipsis: "…",
+    arrow: "▸",
+    pointer: "▶",
+    up: "↑",
+    down: "↓",
+    double_rule: "═",
+    rule: "─",
+    divider: "│",
+    checked: "☑",
+    unchecked: "☐",
+    shades: ["█", "▓", "▒", "░"],
+    bar_empty: "·",
+    spinner: &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+    progress_chars: "█░",
+    tick_chars: "⠁⠂⠄⡀⢀⠠⠐⠈ ",
+};
+
+const ASCII_GLYPHS: Glyphs = Glyphs {
+    search: ">>",
+    broom: ">>",
+    sparkles: "**",
+    stats: "##",
+    clipboard: "##",
+    empty: "--",
+    trend: "##",
+    trash: "--",
+    clock: ">>",
+    doctor: ">>",
+    map: "##",
+    point: "->",
+    folder: "[]",
+    open_folder: "[]",
+    disk: "[]",
+    settings: "::",
+    help: "?",
+    success: "[ok]",
+    failure: "[x]",
+    warning: "[!]",
+    info: "i",
+    check: "+",
+    cross: "x",
+    dot: "*",
+    bullet: "-",
+    ellipsis: "...",
+    arrow: ">",
+    pointer: ">",
+    up: "^",
+    down: "v",
+    double_rule: "=",
+    rule: "-",
+    divider: "|",
+    checked: "[x]",
+    unchecked: "[ ]",
+    shades: ["#", "#", "+", "-"],
+    bar_empty: ".",
+    spinner: &["|", "/", "-", "\\"],
+    progress_chars: "#>-",
+    tick_chars: "|/-\\ ",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::env_read;
+
+    #[test]
+    fn test_ascii_set_is_ascii() {
+        let _env = env_read();
+        // Debug output keeps printable non-ASCII characters unescaped
+        assert!(format!("{:?}", GlyphSet::Ascii.glyphs()).is_ascii());
+        assert!(!format!("{:?}", GlyphSet::Unicode.glyphs()).is_ascii());
+    }
+}
@@ -1,6 +1,9 @@
 //! UI module
 
 mod cli;
+pub mod format;
+pub mod glyphs;
+pub mod notify;
 pub mod tui;
 
 pub use cli::*;
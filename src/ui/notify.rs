@@ -0,0 +1,107 @@
+//! Desktop notifications
+//!
+//! Notifications are sent over D-Bus with `notify-rust` on Linux and through
+//! `osascript` on macOS. They are skipped silently when no display is
+//! available or the `notifications` feature is disabled.
+
+use crate::rules::CleanResult;
+
+/// Title shown on every notification
+const TITLE: &str = "CleanMyMac-rs";
+
+/// Notification body summarizing a clean
+pub fn clean_summary(result: &CleanResult) -> String {
+    format!(
+        "Freed {}, cleaned {} items",
+        bytesize::ByteSize::b(result.bytes_freed),
+        result.cleaned_count
+    )
+}
+
+/// Show a notification summarizing a completed clean
+pub fn notify_clean(result: &CleanResult) {
+    send(TITLE, &clean_summary(result));
+}
+
+/// Show a desktop notification, ignoring failures
+#[cfg(feature = "notifications")]
+pub fn send(title: &str, body: &str) {
+    if !has_display() {
+        tracing::debug!("No display available, skipping notification");
+        return;
+    }
+
+    if let Err(e) = show(title, body) {
+        tracing::debug!("Notification failed: {}", e);
+    }
+}
+
+/// Send a notification through the desktop's notification server
+#[cfg(all(feature = "notifications", target_os = "linux"))]
+fn show(title: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname(TITLE)
+        .summary(title)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Send a notification through AppleScript
+#[cfg(all(feature = "notifications", not(target_os = "linux")))]
+fn show(title: &str, body: &str) -> Result<(), String> {
+    if !cfg!(target_os = "macos") {
+        return Err("not supported on this platform".to_string());
+    }
+
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        applescript_escape(body),
+        applescript_escape(title)
+    );
+    let output = std::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Show a desktop notification (disabled at compile time)
+#[cfg(not(feature = "notifications"))]
+pub fn send(_title: &str, _body: &str) {}
+
+/// Whether a graphical session is available to show notifications
+#[cfg(feature = "notifications")]
+fn has_display() -> bool {
+    if cfg!(target_os = "macos") {
+        // launchd agents run inside the GUI session; SSH sessions don't
+        std::env::var_os("SSH_CONNECTION").is_none()
+    } else {
+        std::env::var_os("DISPLAY").is_some() || std::env::var_os("WAYLAND_DISPLAY").is_some()
+    }
+}
+
+#[cfg(all(feature = "notifications", not(target_os = "linux")))]
+fn applescript_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_summary() {
+        let result = CleanResult {
+            cleaned_count: 3,
+            bytes_freed: 1536 * 1024 * 1024,
+            ..Default::default()
+        };
+        assert_eq!(clean_summary(&result), "Freed 1.5 GiB, cleaned 3 items");
+    }
+}
@@ -1,10 +1,18 @@
 //! Modern TUI interface using ratatui
 
 use crate::cleaner::Cleaner;
-use crate::rules::{CleanItem, RiskLevel, get_all_rules};
-use crate::scanner::FileScanner;
+use crate::config::Config;
+use crate::rules::{
+    Category, CleanItem, RiskLevel, RuleSelection, ScanContext, get_all_rules_with_config,
+};
+use crate::scanner::{DiskSpace, FileScanner};
+use crate::ui::format::{display_path, fit_width};
+use crate::ui::glyphs::{GlyphSet, glyphs};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -15,21 +23,36 @@ use ratatui::{
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph,
+        Block, BorderType, Borders, Clear, Gauge, List, ListItem, ListState, Padding, Paragraph,
         Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs, Wrap,
     },
 };
+use std::collections::BTreeSet;
 use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// Smallest terminal width the full layout is drawn at
+const MIN_WIDTH: u16 = 80;
+
+/// Smallest terminal height the full layout is drawn at
+const MIN_HEIGHT: u16 = 20;
+
+/// Number of general settings listed before the category toggles
+const GENERAL_SETTINGS: usize = 3;
+
 /// Messages for communication between scanner thread and UI
 enum ScanMessage {
     /// Found a batch of items
     FoundItems(Vec<CleanItem>),
     /// Scan completed
     Finished,
+    /// Scan stopped early; the items sent so far are all there is
+    Cancelled,
     /// Scan failed with error
     Error(String),
 }
@@ -42,6 +65,8 @@ pub struct App {
     items: Vec<CleanItem>,
     /// Selected items for cleaning
     selected: Vec<bool>,
+    /// Category the item list and stats are limited to, if any
+    category_filter: Option<Category>,
     /// List state for navigation
     list_state: ListState,
     /// Should quit the app
@@ -56,29 +81,59 @@ pub struct App {
     selected_size: u64,
     /// Scrollbar state
     scrollbar_state: ScrollbarState,
+    /// Where the tabs were last rendered, for mapping clicks
+    tabs_area: Rect,
+    /// Where the item list was last rendered, for mapping clicks
+    list_area: Rect,
+    /// Item where visual (range) selection started, if it is active
+    visual_anchor: Option<usize>,
+    /// Selection before visual mode started; the range is added on top of it
+    visual_base: Vec<bool>,
     /// Show help popup
     show_help: bool,
+    /// Risk groups of the selection (level, items, bytes) awaiting
+    /// confirmation before cleaning
+    confirm_clean: Option<Vec<(RiskLevel, usize, u64)>>,
     /// Animation frame
     animation_frame: usize,
     /// Last tick time
     last_tick: Instant,
     /// Channel receiver for scan results
     scan_rx: Option<Receiver<ScanMessage>>,
+    /// Set to ask the running scan to stop
+    scan_cancel: Arc<AtomicBool>,
+    /// Free space on the home volume, refreshed after each clean
+    disk_space: Option<DiskSpace>,
 
     // Settings state
+    /// Configuration used for scans and cleans, edited by the settings tab
+    config: Config,
+    /// File settings changes are saved to
+    config_path: Option<PathBuf>,
     settings_index: usize,
-    setting_use_trash: bool,
-    setting_confirm: bool,
-    setting_scan_hidden: bool,
-    setting_heuristic: bool,
+    /// Categories that can be switched on and off in the settings tab
+    setting_categories: Vec<String>,
 }
 
 impl Default for App {
     fn default() -> Self {
+        Self::with_config(Config::load_or_default(), Config::default_path().ok())
+    }
+}
+
+impl App {
+    /// Create a new App
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an App using `config`, saving settings changes to `config_path`
+    pub fn with_config(config: Config, config_path: Option<PathBuf>) -> Self {
         Self {
             current_tab: 0,
             items: Vec::new(),
             selected: Vec::new(),
+            category_filter: None,
             list_state: ListState::default(),
             should_quit: false,
             is_scanning: false,
@@ -86,27 +141,28 @@ impl Default for App {
             status_message: String::from("Press 's' to scan, 'q' to quit"),
             selected_size: 0,
             scrollbar_state: ScrollbarState::default(),
+            tabs_area: Rect::default(),
+            list_area: Rect::default(),
+            visual_anchor: None,
+            visual_base: Vec::new(),
             show_help: false,
+            confirm_clean: None,
             animation_frame: 0,
             last_tick: Instant::now(),
             scan_rx: None,
+            scan_cancel: Arc::new(AtomicBool::new(false)),
+            disk_space: None,
+            setting_categories: category_names(&config),
+            config,
+            config_path,
             settings_index: 0,
-            setting_use_trash: true,
-            setting_confirm: true,
-            setting_scan_hidden: true,
-            setting_heuristic: true,
         }
     }
-}
-
-impl App {
-    /// Create a new App
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     /// Run the main TUI loop
     pub fn run(&mut self) -> anyhow::Result<()> {
+        self.disk_space = DiskSpace::home();
+
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
@@ -122,35 +178,30 @@ impl App {
 
             // Check for scan results
             let mut scan_finished = false;
-            if let Some(rx) = &self.scan_rx {
-                while let Ok(msg) = rx.try_recv() {
-                    match msg {
-                        ScanMessage::FoundItems(items) => {
-                            self.items = items;
-                        }
-                        ScanMessage::Finished => {
-                            self.is_scanning = false;
-                            scan_finished = true;
-
-                            // Post-scan updates
-                            self.selected = vec![false; self.items.len()];
-                            self.scrollbar_state =
-                                ScrollbarState::default().content_length(self.items.len());
-                            if !self.items.is_empty() {
-                                self.list_state.select(Some(0));
-                            }
-                            let total_size = self.items.iter().map(|i| i.size).sum::<u64>();
-                            self.status_message = format!(
-                                "✅ Found {} items ({}). Press Space to select, 'c' to clean",
-                                self.items.len(),
-                                format_bytes(total_size)
-                            );
-                        }
-                        ScanMessage::Error(e) => {
-                            self.is_scanning = false;
-                            scan_finished = true;
-                            self.status_message = format!("❌ Scan failed: {}", e);
-                        }
+            let messages: Vec<ScanMessage> = self
+                .scan_rx
+                .as_ref()
+                .map(|rx| rx.try_iter().collect())
+                .unwrap_or_default();
+            for msg in messages {
+                match msg {
+                    ScanMessage::FoundItems(items) => {
+                        self.items.extend(items);
+                        self.selected.resize(self.items.len(), false);
+                    }
+                    ScanMessage::Finished => {
+                        scan_finished = true;
+                        self.finish_scan(false);
+                    }
+                    ScanMessage::Cancelled => {
+                        scan_finished = true;
+                        self.finish_scan(true);
+                    }
+                    ScanMessage::Error(e) => {
+                        self.is_scanning = false;
+                        self.selected.resize(self.items.len(), false);
+                        scan_finished = true;
+                        self.status_message = format!("{} Scan failed: {}", glyphs().failure, e);
                     }
                 }
             }
@@ -161,8 +212,10 @@ impl App {
 
             // Handle events with timeout
             if event::poll(tick_rate)? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key(key.code, key.modifiers);
+                match event::read()? {
+                    Event::Key(key) => self.handle_key(key.code, key.modifiers),
+                    Event::Mouse(mouse) => self.handle_mouse(mouse),
+                    _ => {}
                 }
             }
 
@@ -173,10 +226,15 @@ impl App {
 
                 // Update status message if scanning
                 if self.is_scanning {
-                    let spinner = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+                    let spinner = glyphs().spinner;
                     self.status_message = format!(
-                        "{} Scanning...",
-                        spinner[self.animation_frame % spinner.len()]
+                        "{} {}",
+                        spinner[self.animation_frame % spinner.len()],
+                        if self.scan_cancel.load(Ordering::Relaxed) {
+                            "Cancelling, waiting for running rules..."
+                        } else {
+                            "Scanning... (Esc/x to cancel)"
+                        }
                     );
                 }
             }
@@ -205,7 +263,25 @@ impl App {
             return;
         }
 
+        if self.confirm_clean.take().is_some() {
+            if matches!(
+                key,
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter
+            ) {
+                self.clean();
+            } else {
+                self.status_message = format!("{} Clean cancelled", glyphs().warning);
+            }
+            return;
+        }
+
         match key {
+            KeyCode::Esc | KeyCode::Char('x') if self.is_scanning => {
+                self.scan_cancel.store(true, Ordering::Relaxed);
+            }
+            KeyCode::Esc if self.visual_anchor.is_some() => {
+                self.end_visual();
+            }
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.should_quit = true;
             }
@@ -216,14 +292,52 @@ impl App {
                 self.scan();
             }
             KeyCode::Char('c') if !self.is_cleaning && !self.items.is_empty() => {
-                self.clean();
+                self.request_clean();
             }
             KeyCode::Char('a') if !self.items.is_empty() => {
-                // Select all
-                let all_selected = self.selected.iter().all(|&s| s);
-                self.selected.iter_mut().for_each(|s| *s = !all_selected);
+                // Select all visible items
+                self.end_visual();
+                let visible = self.visible_indices();
+                let all_selected = visible
+                    .iter()
+                    .all(|&i| self.selected.get(i).copied().unwrap_or(false));
+                for i in visible {
+                    if let Some(selected) = self.selected.get_mut(i) {
+                        *selected = !all_selected;
+                    }
+                }
                 self.update_selected_size();
             }
+            KeyCode::Char('f') if !self.is_scanning && !self.items.is_empty() => {
+                self.cycle_category_filter();
+            }
+            KeyCode::Char('F') if self.category_filter.is_some() => {
+                self.set_category_filter(None);
+            }
+            KeyCode::Char('A') if self.current_tab == 0 => {
+                self.toggle_category_selection();
+            }
+            KeyCode::Char('V') | KeyCode::Char('v') if self.current_tab == 0 => {
+                if self.visual_anchor.is_some() {
+                    self.end_visual();
+                } else {
+                    self.start_visual();
+                }
+            }
+            KeyCode::Up | KeyCode::Down
+                if self.current_tab == 0 && modifiers.contains(KeyModifiers::SHIFT) =>
+            {
+                // Shift+arrows extend a range without pressing V first
+                if self.visual_anchor.is_none() {
+                    self.start_visual();
+                }
+                if key == KeyCode::Up {
+                    self.previous_item();
+                } else {
+                    self.next_item();
+                }
+                self.update_visual_range();
+            }
             KeyCode::Tab => {
                 self.current_tab = (self.current_tab + 1) % 3;
             }
@@ -235,7 +349,10 @@ impl App {
                 };
             }
             KeyCode::Up | KeyCode::Char('k') => match self.current_tab {
-                0 => self.previous_item(),
+                0 => {
+                    self.previous_item();
+                    self.update_visual_range();
+                }
                 2 => {
                     if self.settings_index > 0 {
                         self.settings_index -= 1;
@@ -244,15 +361,20 @@ impl App {
                 _ => {}
             },
             KeyCode::Down | KeyCode::Char('j') => match self.current_tab {
-                0 => self.next_item(),
+                0 => {
+                    self.next_item();
+                    self.update_visual_range();
+                }
                 2 => {
-                    if self.settings_index < 3 {
+                    if self.settings_index + 1 < GENERAL_SETTINGS + self.setting_categories.len() {
                         self.settings_index += 1;
                     }
                 }
                 _ => {}
             },
             KeyCode::Char(' ') | KeyCode::Enter => match self.current_tab {
+                // Space ends visual mode, keeping the range selected
+                0 if self.visual_anchor.is_some() => self.end_visual(),
                 0 => self.toggle_selection(),
                 2 => self.toggle_setting(),
                 _ => {}
@@ -264,15 +386,191 @@ impl App {
         }
     }
 
+    /// Handle mouse events
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if self.show_help => {
+                self.show_help = false;
+            }
+            // The confirmation dialog is answered with the keyboard
+            _ if self.confirm_clean.is_some() => {}
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(tab) = self.tab_at(mouse.column, mouse.row) {
+                    self.current_tab = tab;
+                } else if let Some((position, on_checkbox)) =
+                    self.list_row_at(mouse.column, mouse.row)
+                {
+                    self.list_state.select(Some(position));
+                    self.scrollbar_state = self.scrollbar_state.position(position);
+                    if self.visual_anchor.is_some() {
+                        self.update_visual_range();
+                    } else if on_checkbox {
+                        self.toggle_selection();
+                    }
+                }
+            }
+            // The wheel moves the cursor without wrapping around
+            MouseEventKind::ScrollDown => match self.current_tab {
+                0 if self.list_state.selected().unwrap_or(0) + 1 < self.visible_count() => {
+                    self.next_item();
+                    self.update_visual_range();
+                }
+                2 if self.settings_index + 1 < GENERAL_SETTINGS + self.setting_categories.len() => {
+                    self.settings_index += 1;
+                }
+                _ => {}
+            },
+            MouseEventKind::ScrollUp => match self.current_tab {
+                0 if self.list_state.selected().unwrap_or(0) > 0 => {
+                    self.previous_item();
+                    self.update_visual_range();
+                }
+                2 if self.settings_index > 0 => {
+                    self.settings_index -= 1;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    /// Tab whose title is at the given screen position
+    fn tab_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.tabs_area;
+        if row <= area.y || row + 1 >= area.bottom() {
+            return None;
+        }
+
+        // Titles are padded by a space on each side and separated by the divider
+        let divider = Line::from(glyphs().divider).width() as u16;
+        let mut x = area.x + 1;
+        for (i, title) in tab_titles().iter().enumerate() {
+            let width = Line::from(title.as_str()).width() as u16 + 2;
+            if (x..x + width).contains(&column) {
+                return Some(i);
+            }
+            x += width + divider;
+        }
+        None
+    }
+
+    /// Visible list position at the given screen position, and whether the
+    /// position is on the row's checkbox
+    fn list_row_at(&self, column: u16, row: u16) -> Option<(usize, bool)> {
+        let area = self.list_area;
+        if self.current_tab != 0
+            || !(area.x + 1..area.right().saturating_sub(1)).contains(&column)
+            || !(area.y + 1..area.bottom().saturating_sub(1)).contains(&row)
+        {
+            return None;
+        }
+
+        let position = self.list_state.offset() + usize::from(row - area.y - 1);
+        if position >= self.visible_count() {
+            return None;
+        }
+
+        // Border and padding, then the highlight symbol and the checkbox
+        let content_start = area.x + 2;
+        let checkbox_end = content_start
+            + Line::from(format!("{} {} ", glyphs().pointer, glyphs().unchecked)).width() as u16;
+        Some((position, column < checkbox_end))
+    }
+
+    /// Indices into `items` of the items shown under the current filter
+    fn visible_indices(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                self.category_filter
+                    .as_ref()
+                    .is_none_or(|category| item.category == *category)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Number of items shown under the current filter
+    fn visible_count(&self) -> usize {
+        match &self.category_filter {
+            Some(category) => self
+                .items
+                .iter()
+                .filter(|item| item.category == *category)
+                .count(),
+            None => self.items.len(),
+        }
+    }
+
+    /// Index into `items` of the item under the cursor
+    fn current_index(&self) -> Option<usize> {
+        let position = self.list_state.selected()?;
+        self.visible_indices().get(position).copied()
+    }
+
+    /// Show only items of `category`, or all items
+    ///
+    /// Items hidden by the filter are deselected so a clean only ever
+    /// removes what is on screen; visible items keep their selection.
+    fn set_category_filter(&mut self, category: Option<Category>) {
+        self.end_visual();
+        let current = self.current_index();
+        self.category_filter = category;
+
+        let visible = self.visible_indices();
+        for (i, selected) in self.selected.iter_mut().enumerate() {
+            *selected &= visible.contains(&i);
+        }
+        self.update_selected_size();
+
+        // Stay on the same item if it is still shown
+        let position = current
+            .and_then(|current| visible.iter().position(|&i| i == current))
+            .or((!visible.is_empty()).then_some(0));
+        self.list_state.select(position);
+        self.scrollbar_state = ScrollbarState::default()
+            .content_length(visible.len())
+            .position(position.unwrap_or(0));
+
+        self.status_message = match &self.category_filter {
+            Some(category) => format!(
+                "{} Showing {} items ({}). 'f' for next category, 'F' to show all",
+                glyphs().info,
+                category,
+                visible.len()
+            ),
+            None => format!("{} Showing all items", glyphs().info),
+        };
+    }
+
+    /// Filter by the next category found in the scan results, then by none
+    fn cycle_category_filter(&mut self) {
+        let mut categories: Vec<&Category> = self.items.iter().map(|i| &i.category).collect();
+        categories.sort_by_key(|c| c.to_string());
+        categories.dedup();
+
+        let next = match &self.category_filter {
+            None => categories.first(),
+            Some(current) => categories
+                .iter()
+                .position(|c| *c == current)
+                .and_then(|i| categories.get(i + 1)),
+        }
+        .map(|c| (*c).clone());
+        self.set_category_filter(next);
+    }
+
     /// Move to previous item
     fn previous_item(&mut self) {
-        if self.items.is_empty() {
+        let count = self.visible_count();
+        if count == 0 {
             return;
         }
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    count - 1
                 } else {
                     i - 1
                 }
@@ -285,12 +583,13 @@ impl App {
 
     /// Move to next item
     fn next_item(&mut self) {
-        if self.items.is_empty() {
+        let count = self.visible_count();
+        if count == 0 {
             return;
         }
         let i = match self.list_state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= count - 1 {
                     0
                 } else {
                     i + 1
@@ -304,7 +603,7 @@ impl App {
 
     /// Toggle selection of current item
     fn toggle_selection(&mut self) {
-        if let Some(i) = self.list_state.selected() {
+        if let Some(i) = self.current_index() {
             if i < self.selected.len() {
                 self.selected[i] = !self.selected[i];
                 self.update_selected_size();
@@ -312,6 +611,84 @@ impl App {
         }
     }
 
+    /// Start selecting the range between the current item and wherever the cursor moves
+    fn start_visual(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            self.visual_anchor = Some(i);
+            self.visual_base = self.selected.clone();
+            self.update_visual_range();
+        }
+    }
+
+    /// Leave visual mode, keeping the selected range
+    fn end_visual(&mut self) {
+        self.visual_anchor = None;
+        self.visual_base.clear();
+    }
+
+    /// Select the range between the visual anchor and the cursor
+    ///
+    /// Both are positions in the visible list, so the range never includes
+    /// items hidden by the category filter.
+    fn update_visual_range(&mut self) {
+        let (Some(anchor), Some(cursor)) = (self.visual_anchor, self.list_state.selected()) else {
+            return;
+        };
+        let visible = self.visible_indices();
+        let len = self.selected.len();
+        self.selected.clone_from(&self.visual_base);
+        self.selected.resize(len, false);
+        let range = anchor.min(cursor)..=anchor.max(cursor);
+        for &i in visible.get(range).unwrap_or_default() {
+            if let Some(selected) = self.selected.get_mut(i) {
+                *selected = true;
+            }
+        }
+        self.update_selected_size();
+    }
+
+    /// Select every item in the current item's category, or deselect them
+    /// if they are all selected already
+    fn toggle_category_selection(&mut self) {
+        let Some(category) = self
+            .current_index()
+            .and_then(|i| self.items.get(i))
+            .map(|item| item.category.clone())
+        else {
+            return;
+        };
+        self.end_visual();
+
+        let indices: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.category == category)
+            .map(|(i, _)| i)
+            .collect();
+        let all_selected = indices
+            .iter()
+            .all(|&i| self.selected.get(i).copied().unwrap_or(false));
+        for &i in &indices {
+            if let Some(selected) = self.selected.get_mut(i) {
+                *selected = !all_selected;
+            }
+        }
+        self.update_selected_size();
+
+        self.status_message = format!(
+            "{} {} {} {} items",
+            glyphs().info,
+            if all_selected {
+                "Deselected"
+            } else {
+                "Selected"
+            },
+            indices.len(),
+            category
+        );
+    }
+
     /// Update total selected size
     fn update_selected_size(&mut self) {
         self.selected_size = self
@@ -329,20 +706,34 @@ impl App {
             return;
         }
 
+        self.end_visual();
+        self.category_filter = None;
         self.is_scanning = true;
-        self.status_message = String::from("🔍 Scanning...");
+        self.status_message = format!("{} Scanning...", glyphs().search);
         self.items.clear();
         self.selected.clear();
 
         let (tx, rx) = mpsc::channel();
         self.scan_rx = Some(rx);
+        // A fresh flag, so a late cancel of the previous scan can't stop this one
+        self.scan_cancel = Arc::new(AtomicBool::new(false));
+        let cancel = self.scan_cancel.clone();
 
+        let config = self.config.clone();
         thread::spawn(move || {
-            let rules = get_all_rules();
-            let scanner = FileScanner::new(rules);
-            match scanner.scan_quiet() {
-                Ok(items) => {
-                    let _ = tx.send(ScanMessage::FoundItems(items));
+            let rules = RuleSelection::Config.rules(&config);
+            let scanner = FileScanner::new(rules).with_context(ScanContext::new(config));
+            // Send each rule's items as it finishes so early results render
+            let batches = tx.clone();
+            match scanner.scan_streaming_with_cancel(&cancel, move |_, items| {
+                if !items.is_empty() {
+                    let _ = batches.send(ScanMessage::FoundItems(items.to_vec()));
+                }
+            }) {
+                Ok(_) if cancel.load(Ordering::Relaxed) => {
+                    let _ = tx.send(ScanMessage::Cancelled);
+                }
+                Ok(_) => {
                     let _ = tx.send(ScanMessage::Finished);
                 }
                 Err(e) => {
@@ -352,36 +743,110 @@ impl App {
         });
     }
 
-    /// Clean selected items
-    fn clean(&mut self) {
-        let selected_items: Vec<_> = self
-            .items
+    /// Prepare the received items for selection once the scan has ended
+    fn finish_scan(&mut self, cancelled: bool) {
+        self.is_scanning = false;
+        self.selected = vec![false; self.items.len()];
+        self.scrollbar_state = ScrollbarState::default().content_length(self.visible_count());
+        if self.visible_count() > 0 {
+            self.list_state.select(Some(0));
+        }
+
+        let total_size = self.items.iter().map(|i| i.size).sum::<u64>();
+        self.status_message = if cancelled {
+            format!(
+                "{} Cancelled (partial results): {} items ({})",
+                glyphs().warning,
+                self.items.len(),
+                format_bytes(total_size)
+            )
+        } else {
+            format!(
+                "{} Found {} items ({}). Press Space to select, 'c' to clean",
+                glyphs().success,
+                self.items.len(),
+                format_bytes(total_size)
+            )
+        };
+    }
+
+    /// Selected items, in list order
+    fn selected_items(&self) -> Vec<CleanItem> {
+        self.items
             .iter()
             .zip(self.selected.iter())
             .filter(|(_, s)| **s)
             .map(|(item, _)| item.clone())
-            .collect();
+            .collect()
+    }
 
+    /// Clean the selected items, first asking to confirm the risk levels
+    /// the configuration wants confirmed
+    fn request_clean(&mut self) {
+        self.end_visual();
+        let selected_items = self.selected_items();
+        if selected_items.is_empty() {
+            self.status_message = format!(
+                "{} No items selected. Press Space to select items.",
+                glyphs().warning
+            );
+            return;
+        }
+
+        let groups: Vec<_> = [
+            (RiskLevel::High, self.config.general.confirm_high_risk),
+            (RiskLevel::Medium, self.config.risk.confirm_medium_risk),
+        ]
+        .into_iter()
+        .filter(|(_, confirm)| *confirm)
+        .filter_map(|(level, _)| {
+            let group: Vec<_> = selected_items
+                .iter()
+                .filter(|item| item.risk_level == level)
+                .collect();
+            let bytes = group.iter().map(|item| item.size).sum();
+            (!group.is_empty()).then_some((level, group.len(), bytes))
+        })
+        .collect();
+
+        if groups.is_empty() {
+            self.clean();
+        } else {
+            self.confirm_clean = Some(groups);
+        }
+    }
+
+    /// Clean selected items
+    ///
+    /// Risk confirmation happens in [`App::request_clean`]; the cleaner must
+    /// not prompt or print while the terminal is in raw mode.
+    fn clean(&mut self) {
+        self.end_visual();
+        let selected_items = self.selected_items();
         if selected_items.is_empty() {
-            self.status_message =
-                String::from("⚠️ No items selected. Press Space to select items.");
             return;
         }
 
         self.is_cleaning = true;
-        self.status_message = String::from("🧹 Cleaning...");
+        self.status_message = format!("{} Cleaning...", glyphs().broom);
 
         let cleaner = Cleaner::new()
-            .use_trash(self.setting_use_trash)
-            .confirm_high_risk(self.setting_confirm);
+            .use_trash(self.config.general.use_trash)
+            .trash_by_risk(self.config.general.trash_by_risk)
+            .confirm_high_risk(false)
+            .quiet(true)
+            .protected_paths(self.config.safety.resolved_protected_paths())
+            .rules(RuleSelection::Config.rules(&self.config));
 
         match cleaner.clean(&selected_items) {
             Ok(result) => {
                 self.status_message = format!(
-                    "✅ Cleaned {} items, freed {}",
+                    "{} Cleaned {} items, freed {}",
+                    glyphs().success,
                     result.cleaned_count,
                     format_bytes(result.bytes_freed)
                 );
+                self.disk_space = DiskSpace::home().or(self.disk_space);
                 // Remove cleaned items
                 let mut new_items = Vec::new();
                 let mut new_selected = Vec::new();
@@ -393,16 +858,17 @@ impl App {
                 }
                 self.items = new_items;
                 self.selected = new_selected;
-                self.scrollbar_state = ScrollbarState::default().content_length(self.items.len());
+                self.scrollbar_state =
+                    ScrollbarState::default().content_length(self.visible_count());
                 self.selected_size = 0;
-                if !self.items.is_empty() {
+                if self.visible_count() > 0 {
                     self.list_state.select(Some(0));
                 } else {
                     self.list_state.select(None);
                 }
             }
             Err(e) => {
-                self.status_message = format!("❌ Clean failed: {}", e);
+                self.status_message = format!("{} Clean failed: {}", glyphs().failure, e);
             }
         }
 
@@ -413,8 +879,15 @@ impl App {
     fn ui(&mut self, frame: &mut Frame) {
         let size = frame.area();
 
+        // The layout below needs room for its fixed rows; draw nothing but a
+        // notice rather than squeezing it into a smaller terminal
+        if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+            self.render_too_small(frame, size);
+            return;
+        }
+
         // Create main layout
-        let chunks = Layout::default()
+        let [title, tabs, content, status] = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints([
@@ -423,40 +896,82 @@ impl App {
                 Constraint::Min(10),   // Main content
                 Constraint::Length(3), // Status bar
             ])
-            .split(size);
+            .areas(size);
 
         // Render title bar with gradient effect
-        self.render_title(frame, chunks[0]);
+        self.render_title(frame, title);
 
         // Render tabs
-        self.render_tabs(frame, chunks[1]);
+        self.render_tabs(frame, tabs);
 
         // Render main content based on current tab
         match self.current_tab {
-            0 => self.render_scan_tab(frame, chunks[2]),
-            1 => self.render_stats_tab(frame, chunks[2]),
-            2 => self.render_settings_tab(frame, chunks[2]),
+            0 => self.render_scan_tab(frame, content),
+            1 => self.render_stats_tab(frame, content),
+            2 => self.render_settings_tab(frame, content),
             _ => {}
         }
 
         // Render status bar
-        self.render_status_bar(frame, chunks[3]);
+        self.render_status_bar(frame, status);
 
         // Render help popup if needed
         if self.show_help {
             self.render_help_popup(frame, size);
         }
+
+        if let Some(groups) = &self.confirm_clean {
+            self.render_confirm_popup(frame, size, groups);
+        }
+    }
+
+    /// Render the notice shown instead of the UI on a small terminal
+    fn render_too_small(&mut self, frame: &mut Frame, area: Rect) {
+        // Nothing is clickable until the full layout is drawn again
+        self.tabs_area = Rect::default();
+        self.list_area = Rect::default();
+
+        let message = vec![
+            Line::from(Span::styled(
+                "Terminal too small",
+                Style::default().fg(Color::Yellow).bold(),
+            )),
+            Line::from(format!(
+                "{}x{}, need at least {}x{}",
+                area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+            )),
+        ];
+        // Vertically centered when there is room for it
+        let top = area.height.saturating_sub(message.len() as u16) / 2;
+        let area = Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        };
+
+        frame.render_widget(
+            Paragraph::new(message)
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            area,
+        );
     }
 
     /// Render title bar
     fn render_title(&self, frame: &mut Frame, area: Rect) {
         let title_text = vec![
-            Span::styled("🧹 ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!("{} ", glyphs().broom),
+                Style::default().fg(Color::Cyan),
+            ),
             Span::styled("Clean", Style::default().fg(Color::Cyan).bold()),
             Span::styled("My", Style::default().fg(Color::Blue).bold()),
             Span::styled("Mac", Style::default().fg(Color::Magenta).bold()),
             Span::styled("-rs", Style::default().fg(Color::Yellow).bold()),
-            Span::styled(" • Modern System Cleaner", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!(" {} Modern System Cleaner", glyphs().bullet),
+                Style::default().fg(Color::Gray),
+            ),
         ];
 
         let title = Paragraph::new(Line::from(title_text))
@@ -473,9 +988,10 @@ impl App {
     }
 
     /// Render tabs
-    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
-        let titles = vec!["📂 Scan", "📊 Stats", "⚙️  Settings"];
-        let tabs = Tabs::new(titles)
+    fn render_tabs(&mut self, frame: &mut Frame, area: Rect) {
+        self.tabs_area = area;
+        let g = glyphs();
+        let tabs = Tabs::new(tab_titles())
             .block(
                 Block::default()
                     .borders(Borders::ALL)
@@ -489,36 +1005,75 @@ impl App {
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             )
-            .divider("│");
+            .divider(g.divider);
 
         frame.render_widget(tabs, area);
     }
 
     /// Render scan tab
     fn render_scan_tab(&mut self, frame: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
+        let [gauge, panels] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .areas(area);
+
+        // Top row - Selected share of the reclaimable space
+        self.render_selection_gauge(frame, gauge);
+
+        let [list, details] = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-            .split(area);
+            .areas(panels);
 
         // Left panel - Item list
-        self.render_item_list(frame, chunks[0]);
+        self.render_item_list(frame, list);
 
         // Right panel - Details
-        self.render_details_panel(frame, chunks[1]);
+        self.render_details_panel(frame, details);
+    }
+
+    /// Render selected bytes as a share of all listed bytes
+    fn render_selection_gauge(&self, frame: &mut Frame, area: Rect) {
+        let total: u64 = self
+            .visible_indices()
+            .into_iter()
+            .map(|i| self.items[i].size)
+            .sum();
+        let ratio = if total > 0 {
+            (self.selected_size as f64 / total as f64).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let gauge = Gauge::default()
+            .ratio(ratio)
+            .label(format!(
+                "Selected {} of {} ({:.0}%)",
+                format_bytes(self.selected_size),
+                format_bytes(total),
+                ratio * 100.0
+            ))
+            .gauge_style(Style::default().fg(Color::Green).bg(Color::DarkGray))
+            // Partial block characters for sub-cell precision
+            .use_unicode(GlyphSet::current() == GlyphSet::Unicode);
+
+        frame.render_widget(gauge, area);
     }
 
     /// Render item list
     fn render_item_list(&mut self, frame: &mut Frame, area: Rect) {
-        let items: Vec<ListItem> = self
-            .items
+        self.list_area = area;
+        let visible = self.visible_indices();
+        // Borders, padding, pointer, checkbox, risk dot and size column
+        let path_width = (area.width as usize).saturating_sub(21).max(10);
+        let items: Vec<ListItem> = visible
             .iter()
-            .enumerate()
+            .map(|&i| (i, &self.items[i]))
             .map(|(i, item)| {
                 let checkbox = if self.selected.get(i).copied().unwrap_or(false) {
-                    "☑ "
+                    format!("{} ", glyphs().checked)
                 } else {
-                    "☐ "
+                    format!("{} ", glyphs().unchecked)
                 };
 
                 let risk_color = match item.risk_level {
@@ -528,18 +1083,14 @@ impl App {
                 };
 
                 let size_str = format_bytes(item.size);
-                let path_str = item.path.display().to_string();
-                let path_short = if path_str.chars().count() > 50 {
-                    let chars: Vec<char> = path_str.chars().collect();
-                    let start = chars.len().saturating_sub(47);
-                    format!("...{}", chars[start..].iter().collect::<String>())
-                } else {
-                    path_str
-                };
+                let path_short = display_path(&item.path, Some(path_width));
 
                 let content = Line::from(vec![
                     Span::styled(checkbox, Style::default().fg(Color::Cyan)),
-                    Span::styled("● ", Style::default().fg(risk_color)),
+                    Span::styled(
+                        format!("{} ", glyphs().dot),
+                        Style::default().fg(risk_color),
+                    ),
                     Span::styled(
                         format!("{:>10} ", size_str),
                         Style::default().fg(Color::Yellow),
@@ -551,10 +1102,24 @@ impl App {
             })
             .collect();
 
+        let pointer = format!("{} ", glyphs().pointer);
         let list = List::new(items)
             .block(
                 Block::default()
-                    .title(format!(" 📁 Items ({}) ", self.items.len()))
+                    .title(format!(
+                        " {} Items ({}){}{} ",
+                        glyphs().folder,
+                        visible.len(),
+                        self.category_filter
+                            .as_ref()
+                            .map(|category| format!(" [{}]", category))
+                            .unwrap_or_default(),
+                        if self.visual_anchor.is_some() {
+                            " -- VISUAL --"
+                        } else {
+                            ""
+                        }
+                    ))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
@@ -566,15 +1131,15 @@ impl App {
                     .bg(Color::DarkGray)
                     .add_modifier(Modifier::BOLD),
             )
-            .highlight_symbol("▶ ");
+            .highlight_symbol(&pointer);
 
         frame.render_stateful_widget(list, area, &mut self.list_state);
 
         // Render scrollbar
         frame.render_stateful_widget(
             Scrollbar::new(ScrollbarOrientation::VerticalRight)
-                .begin_symbol(Some("↑"))
-                .end_symbol(Some("↓")),
+                .begin_symbol(Some(glyphs().up))
+                .end_symbol(Some(glyphs().down)),
             area.inner(ratatui::layout::Margin {
                 vertical: 1,
                 horizontal: 0,
@@ -587,7 +1152,7 @@ impl App {
     fn render_details_panel(&self, frame: &mut Frame, area: Rect) {
         let selected_count = self.selected.iter().filter(|&&s| s).count();
 
-        let details = if let Some(i) = self.list_state.selected() {
+        let details = if let Some(i) = self.current_index() {
             if let Some(item) = self.items.get(i) {
                 vec![
                     Line::from(vec![Span::styled(
@@ -595,7 +1160,7 @@ impl App {
                         Style::default().fg(Color::Gray),
                     )]),
                     Line::from(vec![Span::styled(
-                        format!("  {}", item.path.display()),
+                        format!("  {}", display_path(&item.path, None)),
                         Style::default().fg(Color::White),
                     )]),
                     Line::from(""),
@@ -642,7 +1207,7 @@ impl App {
 
         let mut content = details;
         content.push(Line::from(""));
-        content.push(Line::from("─".repeat(25)));
+        content.push(Line::from(glyphs().line(25)));
         content.push(Line::from(""));
         content.push(Line::from(vec![
             Span::styled("Selected: ", Style::default().fg(Color::Gray)),
@@ -662,7 +1227,7 @@ impl App {
         let paragraph = Paragraph::new(content)
             .block(
                 Block::default()
-                    .title(" 📋 Details ")
+                    .title(format!(" {} Details ", glyphs().clipboard))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
@@ -679,23 +1244,30 @@ impl App {
         use std::collections::HashMap;
 
         // Split area into two columns
-        let chunks = Layout::default()
+        let [left_area, right_area] = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(area);
+            .areas(area);
 
         // Left panel: Category breakdown with bar chart
+        // Totals follow the scan tab's category filter
+        let items: Vec<&CleanItem> = self
+            .visible_indices()
+            .into_iter()
+            .map(|i| &self.items[i])
+            .collect();
+
         let mut by_category: HashMap<String, u64> = HashMap::new();
-        for item in &self.items {
+        for item in &items {
             *by_category.entry(item.category.to_string()).or_insert(0) += item.size;
         }
 
-        let total_size: u64 = self.items.iter().map(|i| i.size).sum();
+        let total_size: u64 = items.iter().map(|i| i.size).sum();
 
         let mut content = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("📊 ", Style::default()),
+                Span::styled(format!("{} ", glyphs().stats), Style::default()),
                 Span::styled(
                     "Storage by Category",
                     Style::default().fg(Color::Cyan).bold(),
@@ -709,12 +1281,21 @@ impl App {
                     Style::default().fg(Color::Green).bold(),
                 ),
                 Span::styled(
-                    format!(" ({} items)", self.items.len()),
+                    format!(" ({} items)", items.len()),
                     Style::default().fg(Color::Gray),
                 ),
             ]),
             Line::from(""),
         ];
+        if let Some(category) = &self.category_filter {
+            content.insert(
+                content.len() - 1,
+                Line::from(vec![Span::styled(
+                    format!("Filtered to {} ('F' to show all)", category),
+                    Style::default().fg(Color::Gray).italic(),
+                )]),
+            );
+        }
 
         let mut categories: Vec<_> = by_category.iter().collect();
         categories.sort_by(|a, b| b.1.cmp(a.1));
@@ -748,8 +1329,8 @@ impl App {
                 0
             };
 
-            let bar = "█".repeat(bar_len);
-            let empty = "░".repeat(bar_width - bar_len);
+            let bar = glyphs().shades[0].repeat(bar_len);
+            let empty = glyphs().shades[3].repeat(bar_width - bar_len);
             let color = colors[i % colors.len()];
 
             content.push(Line::from(vec![Span::styled(
@@ -774,7 +1355,7 @@ impl App {
         let left_panel = Paragraph::new(content)
             .block(
                 Block::default()
-                    .title(" 📊 Category Analysis ")
+                    .title(format!(" {} Category Analysis ", glyphs().stats))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
@@ -783,20 +1364,20 @@ impl App {
             )
             .wrap(Wrap { trim: true });
 
-        frame.render_widget(left_panel, chunks[0]);
+        frame.render_widget(left_panel, left_area);
 
         // Right panel: Top items and summary
         let mut right_content = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("📁 ", Style::default()),
+                Span::styled(format!("{} ", glyphs().folder), Style::default()),
                 Span::styled("Top Items by Size", Style::default().fg(Color::Cyan).bold()),
             ]),
             Line::from(""),
         ];
 
         // Show top 10 items
-        let mut sorted_items: Vec<_> = self.items.iter().collect();
+        let mut sorted_items = items;
         sorted_items.sort_by(|a, b| b.size.cmp(&a.size));
 
         for (i, item) in sorted_items.iter().take(10).enumerate() {
@@ -806,12 +1387,7 @@ impl App {
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
-            let name_short = if name.chars().count() > 20 {
-                let chars: Vec<char> = name.chars().collect();
-                format!("{}...", chars.into_iter().take(17).collect::<String>())
-            } else {
-                name
-            };
+            let name_short = fit_width(&name, 20);
 
             let percentage = if total_size > 0 {
                 (item.size as f64 / total_size as f64 * 100.0) as u16
@@ -828,7 +1404,7 @@ impl App {
 
             right_content.push(Line::from(vec![
                 Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(Color::Gray)),
-                Span::styled(format!("{:<20} ", name_short), Style::default().fg(color)),
+                Span::styled(format!("{} ", name_short), Style::default().fg(color)),
                 Span::styled(
                     format!("{:>8}", format_bytes(item.size)),
                     Style::default().fg(Color::Yellow),
@@ -842,10 +1418,10 @@ impl App {
 
         // Add disk usage visualization
         right_content.push(Line::from(""));
-        right_content.push(Line::from("─".repeat(35)));
+        right_content.push(Line::from(glyphs().line(35)));
         right_content.push(Line::from(""));
         right_content.push(Line::from(vec![
-            Span::styled("💾 ", Style::default()),
+            Span::styled(format!("{} ", glyphs().disk), Style::default()),
             Span::styled(
                 "Disk Space Reclaimable",
                 Style::default().fg(Color::Cyan).bold(),
@@ -870,17 +1446,15 @@ impl App {
 
         let mut used = 0;
         for (i, &seg) in category_segments.iter().enumerate() {
-            let symbol = match i {
-                0 => "█",
-                1 => "▓",
-                2 => "▒",
-                3 => "░",
-                _ => "·",
-            };
+            let symbol = glyphs()
+                .shades
+                .get(i)
+                .copied()
+                .unwrap_or(glyphs().bar_empty);
             usage_bar.push_str(&symbol.repeat(seg));
             used += seg;
         }
-        usage_bar.push_str(&"·".repeat(segments.saturating_sub(used)));
+        usage_bar.push_str(&glyphs().bar_empty.repeat(segments.saturating_sub(used)));
 
         right_content.push(Line::from(vec![
             Span::styled("[", Style::default().fg(Color::Gray)),
@@ -894,7 +1468,7 @@ impl App {
         let right_panel = Paragraph::new(right_content)
             .block(
                 Block::default()
-                    .title(" � Size Analysis ")
+                    .title(format!(" {} Size Analysis ", glyphs().disk))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
@@ -903,43 +1477,123 @@ impl App {
             )
             .wrap(Wrap { trim: true });
 
-        frame.render_widget(right_panel, chunks[1]);
+        frame.render_widget(right_panel, right_area);
+    }
+
+    /// Whether scans include `category`
+    fn category_enabled(&self, category: &str) -> bool {
+        !self
+            .config
+            .categories
+            .disabled
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category))
     }
 
-    /// Toggle current setting
+    /// Toggle current setting and save it
     fn toggle_setting(&mut self) {
-        match self.settings_index {
-            0 => self.setting_use_trash = !self.setting_use_trash,
-            1 => self.setting_confirm = !self.setting_confirm,
-            2 => self.setting_scan_hidden = !self.setting_scan_hidden,
-            3 => self.setting_heuristic = !self.setting_heuristic,
-            _ => {}
-        }
+        let edit: Box<dyn Fn(&mut Config)> = match self.settings_index {
+            0 => {
+                let value = !self.config.general.use_trash;
+                Box::new(move |config| config.general.use_trash = value)
+            }
+            1 => {
+                let value = !self.config.general.confirm_high_risk;
+                Box::new(move |config| config.general.confirm_high_risk = value)
+            }
+            2 => {
+                let value = !self.config.heuristic.enabled;
+                Box::new(move |config| config.heuristic.enabled = value)
+            }
+            index => {
+                let Some(category) = self.setting_categories.get(index - GENERAL_SETTINGS) else {
+                    return;
+                };
+                let category = category.to_lowercase();
+                let enabled = !self.category_enabled(&category);
+                Box::new(move |config| {
+                    let disabled = &mut config.categories.disabled;
+                    disabled.retain(|c| !c.eq_ignore_ascii_case(&category));
+                    if !enabled {
+                        disabled.push(category.clone());
+                    }
+                })
+            }
+        };
+
+        edit(&mut self.config);
+        self.save_setting(edit.as_ref());
+    }
+
+    /// Apply `edit` to the configuration file
+    ///
+    /// The file is re-read rather than overwritten with `self.config`, so
+    /// profile and command-line overrides are not written into it.
+    fn save_setting(&mut self, edit: &dyn Fn(&mut Config)) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+        let on_disk = if path.exists() {
+            Config::load(path)
+        } else {
+            Ok(Config::default())
+        };
+        let result = on_disk.and_then(|mut config| {
+            edit(&mut config);
+            config.save(path)
+        });
+
+        self.status_message = match result {
+            Ok(()) => format!("{} Saved settings to {}", glyphs().success, path.display()),
+            Err(e) => format!("{} Failed to save settings: {}", glyphs().failure, e),
+        };
     }
 
     /// Render settings tab
     fn render_settings_tab(&self, frame: &mut Frame, area: Rect) {
-        let settings = [
-            ("Move to Trash", self.setting_use_trash),
-            ("Confirm High-Risk Operations", self.setting_confirm),
-            ("Scan Hidden Files", self.setting_scan_hidden),
-            ("Heuristic Detection", self.setting_heuristic),
+        let general = [
+            ("Move to Trash", self.config.general.use_trash),
+            (
+                "Confirm High-Risk Operations",
+                self.config.general.confirm_high_risk,
+            ),
+            ("Heuristic Detection", self.config.heuristic.enabled),
         ];
+        let categories = self
+            .setting_categories
+            .iter()
+            .map(|c| (c.as_str(), self.category_enabled(c)));
 
         let mut content = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("⚙️  ", Style::default()),
+                Span::styled(format!("{}  ", glyphs().settings), Style::default()),
                 Span::styled("Settings", Style::default().fg(Color::Cyan).bold()),
             ]),
             Line::from(""),
         ];
+        let mut selected_line = 0;
+
+        for (i, (label, value)) in general.into_iter().chain(categories).enumerate() {
+            if i == GENERAL_SETTINGS {
+                content.push(Line::from(""));
+                content.push(Line::from(vec![Span::styled(
+                    "Categories",
+                    Style::default().fg(Color::Cyan).bold(),
+                )]));
+            }
 
-        for (i, (label, value)) in settings.iter().enumerate() {
             let is_selected = i == self.settings_index;
-            let cursor = if is_selected { "▶ " } else { "  " };
-            let checkbox = if *value { "✓" } else { " " };
-            let color = if *value { Color::Green } else { Color::Red };
+            if is_selected {
+                selected_line = content.len();
+            }
+            let cursor = if is_selected {
+                format!("{} ", glyphs().pointer)
+            } else {
+                "  ".to_string()
+            };
+            let checkbox = if value { glyphs().check } else { " " };
+            let color = if value { Color::Green } else { Color::Red };
 
             let style = if is_selected {
                 Style::default().bg(Color::DarkGray)
@@ -953,33 +1607,44 @@ impl App {
                     Span::styled("[", Style::default().fg(Color::Gray)),
                     Span::styled(checkbox, Style::default().fg(color).bold()),
                     Span::styled("] ", Style::default().fg(Color::Gray)),
-                    Span::styled(*label, Style::default().fg(Color::White)),
+                    Span::styled(label.to_string(), Style::default().fg(Color::White)),
                 ])
                 .style(style),
             );
         }
 
+        let note = match &self.config_path {
+            Some(path) => format!(
+                "Space/Enter toggles; changes are saved to {}",
+                path.display()
+            ),
+            None => "Space/Enter toggles; changes last until exit".to_string(),
+        };
         content.extend(vec![
             Line::from(""),
-            Line::from("─".repeat(40)),
+            Line::from(glyphs().line(40)),
             Line::from(""),
             Line::from(vec![Span::styled(
-                "Note: Settings reset on restart (Config file WIP)",
+                note,
                 Style::default().fg(Color::Gray).italic(),
             )]),
         ]);
 
+        // Keep the selected row in view below the borders and padding
+        let visible = area.height.saturating_sub(4) as usize;
+        let scroll = (selected_line + 1).saturating_sub(visible) as u16;
+
         let paragraph = Paragraph::new(content)
             .block(
                 Block::default()
-                    .title(" ⚙️  Settings ")
+                    .title(format!(" {}  Settings ", glyphs().settings))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
                     .border_style(Style::default().fg(Color::DarkGray))
                     .padding(Padding::new(2, 2, 1, 1)),
             )
-            .wrap(Wrap { trim: true });
+            .scroll((scroll, 0));
 
         frame.render_widget(paragraph, area);
     }
@@ -987,17 +1652,23 @@ impl App {
     /// Render status bar
     fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
         let spinner = if self.is_scanning || self.is_cleaning {
-            let frames = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧"];
-            frames[self.animation_frame]
+            let frames = glyphs().spinner;
+            frames[self.animation_frame % frames.len()]
         } else {
             ""
         };
 
-        let status = Line::from(vec![
+        let mut status = Line::from(vec![
             Span::styled(spinner, Style::default().fg(Color::Cyan)),
             Span::styled(" ", Style::default()),
             Span::styled(&self.status_message, Style::default().fg(Color::White)),
         ]);
+        if let Some(space) = &self.disk_space {
+            status.push_span(Span::styled(
+                format!("  {} {}", glyphs().disk, space),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
 
         let help = Line::from(vec![
             Span::styled(" s", Style::default().fg(Color::Cyan).bold()),
@@ -1012,10 +1683,10 @@ impl App {
             Span::styled(":quit", Style::default().fg(Color::Gray)),
         ]);
 
-        let chunks = Layout::default()
+        let [left_area, right_area] = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-            .split(area);
+            .areas(area);
 
         let left = Paragraph::new(status).block(
             Block::default()
@@ -1033,8 +1704,61 @@ impl App {
             )
             .alignment(ratatui::layout::Alignment::Right);
 
-        frame.render_widget(left, chunks[0]);
-        frame.render_widget(right, chunks[1]);
+        frame.render_widget(left, left_area);
+        frame.render_widget(right, right_area);
+    }
+
+    /// Render the dialog confirming risky items before a clean
+    fn render_confirm_popup(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        groups: &[(RiskLevel, usize, u64)],
+    ) {
+        let popup_area = centered_rect(60, 40, area);
+
+        frame.render_widget(Clear, popup_area);
+
+        let mut text = vec![Line::from("")];
+        for (level, count, bytes) in groups {
+            let color = match level {
+                RiskLevel::High => Color::Red,
+                _ => Color::Yellow,
+            };
+            text.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} {}-risk ", glyphs().bullet, level),
+                    Style::default().fg(color).bold(),
+                ),
+                Span::styled(
+                    format!("{} items ({})", count, format_bytes(*bytes)),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(vec![
+            Span::styled("  y/Enter  ", Style::default().fg(Color::Yellow)),
+            Span::styled("Clean the selection", Style::default().fg(Color::White)),
+        ]));
+        text.push(Line::from(vec![
+            Span::styled("  any key  ", Style::default().fg(Color::Yellow)),
+            Span::styled("Cancel", Style::default().fg(Color::White)),
+        ]));
+
+        let dialog = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(format!(" {} Clean risky items? ", glyphs().warning))
+                    .title_style(Style::default().fg(Color::Yellow).bold())
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .padding(Padding::new(1, 1, 0, 0)),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        frame.render_widget(dialog, popup_area);
     }
 
     /// Render help popup
@@ -1068,22 +1792,56 @@ impl App {
                     Style::default().fg(Color::White),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("  A        ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "Select/Deselect the current category",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("  f/F      ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    "Filter by next category / show all",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
             Line::from(vec![
                 Span::styled("  Space    ", Style::default().fg(Color::Yellow)),
                 Span::styled("Toggle selection", Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
-                Span::styled("  ↑/k      ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!("  V/S-{}{}  ", glyphs().up, glyphs().down),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(
+                    "Select a range (Space/Esc to finish)",
+                    Style::default().fg(Color::White),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled(
+                    format!("  {}/k      ", glyphs().up),
+                    Style::default().fg(Color::Yellow),
+                ),
                 Span::styled("Move up", Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
-                Span::styled("  ↓/j      ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!("  {}/j      ", glyphs().down),
+                    Style::default().fg(Color::Yellow),
+                ),
                 Span::styled("Move down", Style::default().fg(Color::White)),
             ]),
             Line::from(vec![
                 Span::styled("  Tab      ", Style::default().fg(Color::Yellow)),
                 Span::styled("Switch tabs", Style::default().fg(Color::White)),
             ]),
+            Line::from(vec![
+                Span::styled("  x/Esc    ", Style::default().fg(Color::Yellow)),
+                Span::styled("Cancel a running scan", Style::default().fg(Color::White)),
+            ]),
             Line::from(vec![
                 Span::styled("  ?        ", Style::default().fg(Color::Yellow)),
                 Span::styled("Show this help", Style::default().fg(Color::White)),
@@ -1102,7 +1860,7 @@ impl App {
         let help = Paragraph::new(help_text)
             .block(
                 Block::default()
-                    .title(" ❓ Help ")
+                    .title(format!(" {} Help ", glyphs().help))
                     .title_style(Style::default().fg(Color::Cyan).bold())
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
@@ -1115,6 +1873,29 @@ impl App {
     }
 }
 
+/// Titles of the tabs, in order
+fn tab_titles() -> [String; 3] {
+    let g = glyphs();
+    [
+        format!("{} Scan", g.open_folder),
+        format!("{} Stats", g.stats),
+        format!("{}  Settings", g.settings),
+    ]
+}
+
+/// Display names of the categories of all rules, sorted
+fn category_names(config: &Config) -> Vec<String> {
+    // List the heuristic category even while detection is switched off
+    let mut config = config.clone();
+    config.heuristic.enabled = true;
+    get_all_rules_with_config(&config)
+        .iter()
+        .map(|rule| rule.category().to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
 /// Format bytes to human-readable string
 fn format_bytes(bytes: u64) -> String {
     bytesize::ByteSize::b(bytes).to_string()
@@ -1122,21 +1903,263 @@ fn format_bytes(bytes: u64) -> String {
 
 /// Create a centered rect
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
+    let [_, middle, _] = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Percentage((100 - percent_y) / 2),
             Constraint::Percentage(percent_y),
             Constraint::Percentage((100 - percent_y) / 2),
         ])
-        .split(r);
+        .areas(r);
 
-    Layout::default()
+    let [_, center, _] = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Percentage((100 - percent_x) / 2),
             Constraint::Percentage(percent_x),
             Constraint::Percentage((100 - percent_x) / 2),
         ])
-        .split(popup_layout[1])[1]
+        .areas(middle);
+    center
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Category;
+    use crate::test_support::env_read;
+
+    fn app_with_items(categories: &[Category]) -> App {
+        let mut app = App::with_config(Config::default(), None);
+        app.items = categories
+            .iter()
+            .enumerate()
+            .map(|(i, category)| {
+                CleanItem::new(
+                    PathBuf::from(format!("/tmp/item{}", i)),
+                    100,
+                    "test",
+                    RiskLevel::Low,
+                    category.clone(),
+                )
+            })
+            .collect();
+        app.selected = vec![false; app.items.len()];
+        app.list_state.select(Some(0));
+        app
+    }
+
+    #[test]
+    fn test_visual_mode_selects_range() {
+        let _env = env_read();
+        let mut app = app_with_items(&vec![Category::Rust; 5]);
+        app.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        app.handle_key(KeyCode::Char('V'), KeyModifiers::SHIFT);
+        app.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        app.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(app.selected, [false, true, true, true, false]);
+        assert_eq!(app.selected_size, 300);
+
+        // Moving back shrinks the range
+        app.handle_key(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(app.selected, [false, true, true, false, false]);
+
+        // Esc leaves visual mode without quitting
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.should_quit);
+        app.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(app.selected, [false, true, true, false, false]);
+
+        // Shift+Down adds to the existing selection
+        app.handle_key(KeyCode::Down, KeyModifiers::SHIFT);
+        assert_eq!(app.selected, [false, true, true, true, true]);
+    }
+
+    #[test]
+    fn test_category_filter_keeps_visible_selection() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust, Category::Go, Category::Rust]);
+        app.selected = vec![true, true, false];
+        app.list_state.select(Some(2));
+
+        app.handle_key(KeyCode::Char('f'), KeyModifiers::NONE);
+        assert_eq!(app.category_filter, Some(Category::Go));
+        assert_eq!(app.visible_indices(), [1]);
+        assert_eq!(app.selected, [false, true, false]);
+
+        app.handle_key(KeyCode::Char('f'), KeyModifiers::NONE);
+        assert_eq!(app.category_filter, Some(Category::Rust));
+        assert_eq!(app.selected, [false, false, false]);
+
+        // Positions map through the filter to the underlying items
+        app.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(app.current_index(), Some(2));
+        app.handle_key(KeyCode::Char(' '), KeyModifiers::NONE);
+        assert_eq!(app.selected, [false, false, true]);
+
+        app.handle_key(KeyCode::Char('F'), KeyModifiers::SHIFT);
+        assert_eq!(app.category_filter, None);
+        assert_eq!(app.current_index(), Some(2));
+        assert_eq!(app.selected, [false, false, true]);
+    }
+
+    /// Render the app into an off-screen terminal of the given size
+    fn render(app: &mut App, width: u16, height: u16) -> String {
+        let mut terminal =
+            Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| app.ui(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_small_terminal_shows_notice() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust, Category::Go]);
+        app.show_help = true;
+
+        let tiny = render(&mut app, 30, 6);
+        assert!(tiny.contains("Terminal too small"));
+
+        // No size down to nothing panics
+        for width in 0..MIN_WIDTH + 2 {
+            for height in [0, 1, 5, MIN_HEIGHT - 1, MIN_HEIGHT] {
+                render(&mut app, width, height);
+            }
+        }
+
+        let full = render(&mut app, MIN_WIDTH, MIN_HEIGHT);
+        assert!(!full.contains("Terminal too small"));
+        assert!(full.contains("Items (2)"));
+    }
+
+    fn click(app: &mut App, column: u16, row: u16) {
+        app.handle_mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+
+    #[test]
+    fn test_mouse_selects_rows_and_switches_tabs() {
+        let _env = env_read();
+        let mut app = app_with_items(&vec![Category::Rust; 3]);
+        app.tabs_area = Rect::new(0, 0, 60, 3);
+        app.list_area = Rect::new(0, 3, 40, 10);
+
+        // Clicking a path moves the cursor, clicking a checkbox toggles
+        click(&mut app, 20, 5);
+        assert_eq!(app.list_state.selected(), Some(1));
+        assert_eq!(app.selected, [false, false, false]);
+        click(&mut app, 3, 6);
+        assert_eq!(app.list_state.selected(), Some(2));
+        assert_eq!(app.selected, [false, false, true]);
+
+        // Rows below the last item are ignored
+        click(&mut app, 3, 8);
+        assert_eq!(app.list_state.selected(), Some(2));
+
+        app.handle_mouse(MouseEvent {
+            kind: MouseEventKind::ScrollUp,
+            column: 20,
+            row: 5,
+            modifiers: KeyModifiers::NONE,
+        });
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        click(&mut app, 2, 1);
+        assert_eq!(app.current_tab, 0);
+        let stats_column = 1 + Line::from(tab_titles()[0].as_str()).width() as u16 + 4;
+        click(&mut app, stats_column, 1);
+        assert_eq!(app.current_tab, 1);
+    }
+
+    #[test]
+    fn test_escape_cancels_scan_instead_of_quitting() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust]);
+        app.is_scanning = true;
+
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.should_quit);
+        assert!(app.scan_cancel.load(Ordering::Relaxed));
+
+        app.finish_scan(true);
+        assert!(app.status_message.contains("Cancelled (partial results)"));
+        assert_eq!(app.selected, [false]);
+    }
+
+    #[test]
+    fn test_select_all_while_scanning_does_not_panic() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust, Category::Go]);
+        // Items streamed in before the selection was sized for them
+        app.is_scanning = true;
+        app.selected.clear();
+
+        app.handle_key(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert_eq!(app.selected_size, 0);
+    }
+
+    #[test]
+    fn test_select_current_category() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust, Category::Go, Category::Rust]);
+        app.handle_key(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(app.selected, [true, false, true]);
+        app.handle_key(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(app.selected, [false, false, false]);
+    }
+
+    #[test]
+    fn test_risky_clean_is_confirmed_in_a_dialog() {
+        let _env = env_read();
+        let mut app = app_with_items(&[Category::Rust, Category::Go]);
+        app.config.general.use_trash = false;
+        app.config.risk.confirm_medium_risk = true;
+        app.items[1].risk_level = RiskLevel::Medium;
+        app.items[1].path = PathBuf::from("/nonexistent/cmm-tui-item");
+        app.selected = vec![false, true];
+
+        app.handle_key(KeyCode::Char('c'), KeyModifiers::NONE);
+        assert_eq!(app.confirm_clean, Some(vec![(RiskLevel::Medium, 1, 100)]));
+        assert!(render(&mut app, 80, 30).contains("Medium-risk 1 items"));
+
+        app.handle_key(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(app.confirm_clean, None);
+        assert!(app.status_message.contains("Clean cancelled"));
+        assert_eq!(app.items.len(), 2);
+
+        // Confirming cleans without the cleaner prompting on the terminal
+        app.handle_key(KeyCode::Char('c'), KeyModifiers::NONE);
+        app.handle_key(KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!(app.confirm_clean, None);
+        assert_eq!(app.items.len(), 1);
+    }
+
+    #[test]
+    fn test_settings_toggles_are_saved() {
+        let _env = env_read();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut app = App::with_config(Config::default(), Some(path.clone()));
+
+        // Move to Trash
+        app.toggle_setting();
+        assert!(!app.config.general.use_trash);
+
+        let rust = app.setting_categories.iter().position(|c| c == "Rust");
+        app.settings_index = GENERAL_SETTINGS + rust.unwrap();
+        app.toggle_setting();
+        assert!(!app.category_enabled("Rust"));
+
+        let saved = Config::load(&path).unwrap();
+        assert!(!saved.general.use_trash);
+        assert_eq!(saved.categories.disabled, ["rust"]);
+
+        app.toggle_setting();
+        assert!(Config::load(&path).unwrap().categories.disabled.is_empty());
+    }
 }
@@ -0,0 +1,124 @@
+//! Shared helpers used across the crate
+
+/// Parse a human readable size such as "1.5GB", "500 MB" or "12KiB"
+///
+/// Whitespace and case are ignored. `binary` selects 1024-based multipliers
+/// for the plain suffixes (KB, MB, ...); explicit IEC suffixes (KiB, MiB, ...)
+/// are always 1024-based. Returns `None` if the input is not a valid size.
+pub fn parse_size(s: &str, binary: bool) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num_part, unit) = s.split_at(split);
+
+    let value: f64 = num_part.parse().ok()?;
+    let base: u64 = if binary { 1024 } else { 1000 };
+
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => base,
+        "M" | "MB" => base.pow(2),
+        "G" | "GB" => base.pow(3),
+        "T" | "TB" => base.pow(4),
+        "KIB" => 1024,
+        "MIB" => 1024u64.pow(2),
+        "GIB" => 1024u64.pow(3),
+        "TIB" => 1024u64.pow(4),
+        _ => return None,
+    };
+
+    Some((value * multiplier as f64) as u64)
+}
+
+/// Expand a leading `~` to the home directory
+///
+/// Paths without a leading `~`, or when the home directory is unknown, are
+/// returned unchanged.
+pub fn expand_tilde(path: &str) -> std::path::PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match crate::paths::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => path.into(),
+        },
+        _ => path.into(),
+    }
+}
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+const DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+/// The user's editor as a program and its arguments
+///
+/// Taken from `$VISUAL`, then `$EDITOR` (which may carry arguments, e.g.
+/// `code --wait`), falling back to `vi`, or `notepad` on Windows.
+pub fn editor_command() -> (String, Vec<String>) {
+    let command = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+
+    let mut parts = command.split_whitespace().map(str::to_string);
+    let program = parts.next().unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    (program, parts.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::with_env;
+    use std::path::Path;
+
+    #[test]
+    fn test_editor_command_prefers_visual_then_editor() {
+        let code = Some(Path::new("code --wait"));
+        let nano = Some(Path::new("nano"));
+
+        with_env(&[("VISUAL", code), ("EDITOR", nano)], || {
+            assert_eq!(
+                editor_command(),
+                ("code".to_string(), vec!["--wait".to_string()])
+            );
+        });
+        with_env(
+            &[("VISUAL", Some(Path::new(" "))), ("EDITOR", nano)],
+            || {
+                assert_eq!(editor_command(), ("nano".to_string(), vec![]));
+            },
+        );
+        with_env(&[("VISUAL", None), ("EDITOR", None)], || {
+            assert_eq!(editor_command(), (DEFAULT_EDITOR.to_string(), vec![]));
+        });
+    }
+
+    #[test]
+    fn test_parse_size_table() {
+        let cases: &[(&str, bool, Option<u64>)] = &[
+            ("1.5GB", false, Some(1_500_000_000)),
+            ("1.5GB", true, Some(1_610_612_736)),
+            ("500 MB", false, Some(500_000_000)),
+            ("500 mb", true, Some(524_288_000)),
+            ("0B", false, Some(0)),
+            ("12KiB", false, Some(12_288)),
+            ("12kB", false, Some(12_000)),
+            ("  2TB ", false, Some(2_000_000_000_000)),
+            ("42", false, Some(42)),
+            ("", false, None),
+            ("GB", false, None),
+            ("abc", false, None),
+            ("1.2.3MB", false, None),
+            ("10 parsecs", false, None),
+        ];
+
+        for (input, binary, expected) in cases {
+            assert_eq!(
+                parse_size(input, *binary),
+                *expected,
+                "parse_size({:?}, {})",
+                input,
+                binary
+            );
+        }
+    }
+}